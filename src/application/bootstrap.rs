@@ -1,23 +1,300 @@
+use crate::application::cli::{self, Command};
+use crate::domain::gateway::rate_limit::RateLimitConfig;
+use crate::domain::gateway::routes::ProxyRoute;
+use crate::domain::gateway::subgraphs::SubgraphConfig;
+use crate::domain::repositories::permission_repository::PermissionRepository;
+use crate::domain::repositories::rate_limit_repository::RateLimitRepository;
+use crate::infrastructure::adapters::graphql::federation::Federation;
 use crate::infrastructure::adapters::graphql::schema::create_schema;
 use crate::infrastructure::adapters::http::server;
+use crate::infrastructure::adapters::http::upstream_client::UpstreamClient;
+use crate::infrastructure::adapters::jwt::jwt_signer::{JwtAlgorithm, JwtConfig, JwtKeyConfig};
+use crate::infrastructure::adapters::jwt::{JwtError, JwtSigner};
+use crate::infrastructure::adapters::keto::KetoPermissionRepository;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, KratosResilience};
+use crate::infrastructure::adapters::memory::{
+    InMemoryPermissionRepository, InMemoryRateLimitRepository,
+};
+use crate::infrastructure::adapters::redis::{
+    RedisRateLimitRepository, RedisRefreshTokenRepository,
+};
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
 
 pub async fn run() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = Command::parse(&args).map_err(|e| {
+        eprintln!("{}\n\n{}", e, cli::USAGE);
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, e)
+    })?;
+
+    // Logs go to stderr so `export-identities` can write JSONL to stdout
     tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::new("info"))
+        .with_writer(std::io::stderr)
         .init();
 
     info!("Starting application...");
 
-    let jwt_secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| {
-        info!("JWT_SECRET not set, using default (change in production!)");
-        "your-default-secret-key-change-in-production".to_string()
+    let kratos_admin_url =
+        std::env::var("KRATOS_ADMIN_URL").unwrap_or_else(|_| "http://localhost:4434".to_string());
+    let kratos_public_url =
+        std::env::var("KRATOS_PUBLIC_URL").unwrap_or_else(|_| "http://localhost:4433".to_string());
+
+    let kratos_client = KratosClient::new(kratos_admin_url, kratos_public_url)
+        .with_resilience(kratos_resilience()?);
+
+    if !matches!(command, Command::Serve) {
+        return cli::run(command, &kratos_client).await;
+    }
+
+    let jwt_signer = JwtSigner::new(jwt_config()?)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+    info!(
+        algorithm = jwt_signer.algorithm().as_str(),
+        "JWT signer ready"
+    );
+
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    let refresh_token_ttl = match std::env::var("REFRESH_TOKEN_TTL_SECONDS") {
+        Ok(value) => value.parse::<i64>().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Invalid REFRESH_TOKEN_TTL_SECONDS '{}'", value),
+            )
+        })?,
+        Err(_) => 30 * 24 * 60 * 60,
+    };
+    let refresh_tokens = RedisRefreshTokenRepository::connect(
+        &redis_url,
+        chrono::Duration::seconds(refresh_token_ttl),
+    )
+    .await
+    .map_err(|e| std::io::Error::other(format!("Redis at {}: {}", redis_url, e)))?;
+    info!("Refresh token store ready");
+
+    let permissions = permission_repository()?;
+
+    let rate_limit_config = Arc::new(match std::env::var("RATE_LIMITS_PATH") {
+        Ok(path) => RateLimitConfig::load(&std::fs::read_to_string(path)?)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+        Err(_) => RateLimitConfig::default(),
     });
+    let rate_limits = rate_limit_repository(&redis_url).await?;
 
     info!("Creating GraphQL schema...");
-    let schema = Arc::new(create_schema(jwt_secret));
+    let schema = Arc::new(create_schema(
+        jwt_signer.clone(),
+        kratos_client.clone(),
+        Arc::new(refresh_tokens),
+        permissions.clone(),
+        rate_limits.clone(),
+        rate_limit_config.clone(),
+    ));
+
+    let routes = match std::env::var("GATEWAY_ROUTES_PATH") {
+        Ok(path) => ProxyRoute::load(&std::fs::read_to_string(path)?)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+        Err(_) => Vec::new(),
+    };
+
+    let federation = match std::env::var("GATEWAY_SUBGRAPHS_PATH") {
+        Ok(path) => {
+            let subgraphs = SubgraphConfig::load(&std::fs::read_to_string(path)?)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            let federation = Federation::load(subgraphs, schema.sdl(), &UpstreamClient::new())
+                .await
+                .map_err(|e| std::io::Error::other(format!("Supergraph composition: {}", e)))?;
+            info!("Supergraph composed");
+            Some(federation)
+        }
+        Err(_) => None,
+    };
+
+    let services = server::Services {
+        kratos_client,
+        jwt_signer,
+        permissions,
+        rate_limits,
+        rate_limit_config,
+    };
+    server::start(schema, services, routes, federation).await
+}
+
+/// `RATE_LIMIT_BACKEND=redis` (default) shares counters between replicas
+/// through `REDIS_URL`, counting in memory while Redis is unreachable;
+/// `memory` keeps them per process.
+async fn rate_limit_repository(redis_url: &str) -> std::io::Result<Arc<dyn RateLimitRepository>> {
+    let backend = std::env::var("RATE_LIMIT_BACKEND").unwrap_or_else(|_| "redis".to_string());
+
+    match backend.as_str() {
+        "redis" => match RedisRateLimitRepository::connect(redis_url).await {
+            Ok(repository) => {
+                info!("Rate limiting through Redis");
+                Ok(Arc::new(repository))
+            }
+            Err(e) => {
+                warn!(error = %e, "Redis unavailable, rate limiting in memory");
+                Ok(Arc::new(InMemoryRateLimitRepository::default()))
+            }
+        },
+        "memory" => {
+            info!("Rate limiting in memory");
+            Ok(Arc::new(InMemoryRateLimitRepository::default()))
+        }
+        other => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Unknown RATE_LIMIT_BACKEND '{}'", other),
+        )),
+    }
+}
+
+/// Per-call timeouts `KRATOS_{FLOW,SUBMIT,SESSION,ADMIN}_TIMEOUT_MS`, retries
+/// `KRATOS_RETRY_ATTEMPTS` / `KRATOS_RETRY_BASE_DELAY_MS` and the breaker's
+/// `KRATOS_BREAKER_FAILURES` / `KRATOS_BREAKER_OPEN_MS`.
+fn kratos_resilience() -> std::io::Result<KratosResilience> {
+    let mut resilience = KratosResilience::default();
+
+    let number = |name: &str, default: u64| match std::env::var(name) {
+        Ok(value) => value.parse::<u64>().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Invalid {} '{}'", name, value),
+            )
+        }),
+        Err(_) => Ok(default),
+    };
+    let duration = |name: &str, default: std::time::Duration| {
+        number(name, default.as_millis() as u64).map(std::time::Duration::from_millis)
+    };
+
+    resilience.flow_timeout = duration("KRATOS_FLOW_TIMEOUT_MS", resilience.flow_timeout)?;
+    resilience.submit_timeout = duration("KRATOS_SUBMIT_TIMEOUT_MS", resilience.submit_timeout)?;
+    resilience.session_timeout = duration("KRATOS_SESSION_TIMEOUT_MS", resilience.session_timeout)?;
+    resilience.admin_timeout = duration("KRATOS_ADMIN_TIMEOUT_MS", resilience.admin_timeout)?;
+    resilience.retry.attempts =
+        number("KRATOS_RETRY_ATTEMPTS", resilience.retry.attempts.into())? as u32;
+    resilience.retry.base_delay_ms =
+        number("KRATOS_RETRY_BASE_DELAY_MS", resilience.retry.base_delay_ms)?;
+    resilience.breaker.failure_threshold = number(
+        "KRATOS_BREAKER_FAILURES",
+        resilience.breaker.failure_threshold.into(),
+    )? as u32;
+    resilience.breaker.open_ms = number("KRATOS_BREAKER_OPEN_MS", resilience.breaker.open_ms)?;
+
+    resilience
+        .retry
+        .validate()
+        .and_then(|_| resilience.breaker.validate())
+        .map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Kratos {}", e))
+        })?;
+    Ok(resilience)
+}
+
+/// `PERMISSIONS_BACKEND=keto` (default) checks against `KETO_READ_URL`;
+/// `memory` serves the tuples listed in `PERMISSIONS_TUPLES_PATH` (a JSON array).
+fn permission_repository() -> std::io::Result<Arc<dyn PermissionRepository>> {
+    let backend = std::env::var("PERMISSIONS_BACKEND").unwrap_or_else(|_| "keto".to_string());
+
+    match backend.as_str() {
+        "keto" => {
+            let keto_read_url = std::env::var("KETO_READ_URL")
+                .unwrap_or_else(|_| "http://localhost:4466".to_string());
+            info!(url = %keto_read_url, "Checking permissions against Keto");
+            Ok(Arc::new(KetoPermissionRepository::new(keto_read_url)))
+        }
+        "memory" => {
+            let repository = match std::env::var("PERMISSIONS_TUPLES_PATH") {
+                Ok(path) => {
+                    InMemoryPermissionRepository::from_json(&std::fs::read_to_string(path)?)
+                        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?
+                }
+                Err(_) => InMemoryPermissionRepository::default(),
+            };
+            info!("Checking permissions against in-memory relation tuples");
+            Ok(Arc::new(repository))
+        }
+        other => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Unknown PERMISSIONS_BACKEND '{}'", other),
+        )),
+    }
+}
+
+/// `JWT_ALGORITHM` selects HS256 (default), RS256 or EdDSA. Keys come from
+/// `JWT_KEYS` (`kid=path,kid=path`, first one signs; files hold a PEM private
+/// key or the HS256 secret), else from `JWT_PRIVATE_KEY_PATH` / `JWT_SECRET`
+/// with an optional `JWT_KEY_ID`. HS256 secrets need at least 32 bytes.
+/// `JWT_ROTATED_AT` (RFC 3339) retires the older keys one token lifetime
+/// after the rotation.
+fn jwt_config() -> std::io::Result<JwtConfig> {
+    let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, e);
+
+    let algorithm: JwtAlgorithm = std::env::var("JWT_ALGORITHM")
+        .unwrap_or_else(|_| "HS256".to_string())
+        .parse()
+        .map_err(|e: JwtError| invalid(e.to_string()))?;
+
+    let keys = match std::env::var("JWT_KEYS") {
+        Ok(entries) => entries
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (kid, path) = entry
+                    .split_once('=')
+                    .ok_or_else(|| invalid(format!("Invalid JWT_KEYS entry '{}'", entry)))?;
+                Ok(JwtKeyConfig {
+                    kid: Some(kid.trim().to_string()),
+                    material: std::fs::read_to_string(path.trim())?.trim().to_string(),
+                })
+            })
+            .collect::<std::io::Result<Vec<_>>>()?,
+        Err(_) => {
+            let material = match (algorithm, std::env::var("JWT_PRIVATE_KEY_PATH")) {
+                // No fallback secret: anyone knowing it could mint tokens
+                (JwtAlgorithm::Hs256, _) => std::env::var("JWT_SECRET")
+                    .map_err(|_| invalid("HS256 needs JWT_SECRET".to_string()))?,
+                (_, Ok(path)) => std::fs::read_to_string(path)?,
+                (_, Err(_)) => {
+                    return Err(invalid(format!(
+                        "{} needs JWT_KEYS or JWT_PRIVATE_KEY_PATH",
+                        algorithm.as_str()
+                    )));
+                }
+            };
+            vec![JwtKeyConfig {
+                kid: std::env::var("JWT_KEY_ID").ok(),
+                material,
+            }]
+        }
+    };
+
+    let rotated_at = match std::env::var("JWT_ROTATED_AT") {
+        Ok(value) => Some(
+            chrono::DateTime::parse_from_rfc3339(&value)
+                .map_err(|_| invalid(format!("Invalid JWT_ROTATED_AT '{}'", value)))?
+                .with_timezone(&chrono::Utc),
+        ),
+        Err(_) => None,
+    };
+
+    let ttl_seconds = match std::env::var("JWT_TTL_SECONDS") {
+        Ok(value) => value
+            .parse::<i64>()
+            .map_err(|_| invalid(format!("Invalid JWT_TTL_SECONDS '{}'", value)))?,
+        Err(_) => 900,
+    };
 
-    server::start(schema).await
+    Ok(JwtConfig {
+        algorithm,
+        keys,
+        rotated_at,
+        issuer: std::env::var("JWT_ISSUER").unwrap_or_else(|_| "rust-gateway".to_string()),
+        audience: std::env::var("JWT_AUDIENCE").ok(),
+        ttl: chrono::Duration::seconds(ttl_seconds),
+    })
 }
@@ -0,0 +1,130 @@
+use crate::application::usecases::admin::export_identities::ExportIdentitiesUseCase;
+use crate::application::usecases::admin::import_identities::{
+    DEFAULT_BATCH_SIZE, ImportIdentitiesUseCase,
+};
+use crate::domain::admin::import::ImportFormat;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+
+pub const USAGE: &str = "\
+Usage:
+  rust-gateway [serve]
+  rust-gateway import-identities <file> [--format jsonl|csv] [--batch-size <n>]
+  rust-gateway export-identities [<file>]";
+
+pub enum Command {
+    Serve,
+    ImportIdentities {
+        path: String,
+        format: ImportFormat,
+        batch_size: usize,
+    },
+    /// Writes to stdout when no path is given.
+    ExportIdentities {
+        path: Option<String>,
+    },
+}
+
+impl Command {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        match args.first().map(String::as_str) {
+            None | Some("serve") => Ok(Command::Serve),
+            Some("import-identities") => {
+                let mut path = None;
+                let mut format = None;
+                let mut batch_size = DEFAULT_BATCH_SIZE;
+
+                let mut rest = args[1..].iter();
+                while let Some(arg) = rest.next() {
+                    match arg.as_str() {
+                        "--format" => {
+                            let value = rest.next().ok_or("--format needs a value")?;
+                            format = Some(value.parse()?);
+                        }
+                        "--batch-size" => {
+                            let value = rest.next().ok_or("--batch-size needs a value")?;
+                            batch_size = value
+                                .parse()
+                                .map_err(|_| format!("Invalid batch size '{}'", value))?;
+                        }
+                        _ if path.is_none() => path = Some(arg.clone()),
+                        other => return Err(format!("Unexpected argument '{}'", other)),
+                    }
+                }
+
+                let path = path.ok_or("import-identities needs a file")?;
+                let format = format.unwrap_or_else(|| ImportFormat::from_file_name(&path));
+
+                Ok(Command::ImportIdentities {
+                    path,
+                    format,
+                    batch_size,
+                })
+            }
+            Some("export-identities") => match &args[1..] {
+                [] => Ok(Command::ExportIdentities { path: None }),
+                [path] => Ok(Command::ExportIdentities {
+                    path: Some(path.clone()),
+                }),
+                _ => Err("export-identities takes at most one file".to_string()),
+            },
+            Some(other) => Err(format!("Unknown command '{}'", other)),
+        }
+    }
+}
+
+/// Runs a one-off admin command against Kratos; `Serve` is handled by bootstrap.
+pub async fn run(command: Command, kratos_client: &KratosClient) -> io::Result<()> {
+    match command {
+        Command::Serve => Ok(()),
+        Command::ImportIdentities {
+            path,
+            format,
+            batch_size,
+        } => {
+            let reader = BufReader::new(File::open(&path)?);
+
+            let report =
+                ImportIdentitiesUseCase::execute(reader, format, batch_size, kratos_client)
+                    .await
+                    .map_err(io::Error::other)?;
+
+            for error in &report.errors {
+                eprintln!(
+                    "line {}{}: {}",
+                    error.line,
+                    error
+                        .identifier
+                        .as_ref()
+                        .map(|id| format!(" ({})", id))
+                        .unwrap_or_default(),
+                    error.message
+                );
+            }
+            eprintln!(
+                "Imported {} of {} identities, {} failed",
+                report.imported, report.total, report.failed
+            );
+
+            Ok(())
+        }
+        Command::ExportIdentities { path } => {
+            let exported = match path {
+                Some(path) => {
+                    let mut writer = BufWriter::new(File::create(path)?);
+                    ExportIdentitiesUseCase::execute(&mut writer, kratos_client).await
+                }
+                None => {
+                    let mut writer = BufWriter::new(io::stdout());
+                    ExportIdentitiesUseCase::execute(&mut writer, kratos_client).await
+                }
+            }
+            .map_err(io::Error::other)?;
+
+            eprintln!("Exported {} identities", exported);
+
+            Ok(())
+        }
+    }
+}
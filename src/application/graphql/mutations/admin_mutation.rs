@@ -0,0 +1,94 @@
+use crate::application::usecases::admin::create_identity::CreateIdentityUseCase;
+use crate::application::usecases::admin::delete_identity::DeleteIdentityUseCase;
+use crate::application::usecases::admin::import_identities::{
+    DEFAULT_BATCH_SIZE, ImportIdentitiesUseCase,
+};
+use crate::application::usecases::admin::revoke_identity_sessions::RevokeIdentitySessionsUseCase;
+use crate::application::usecases::admin::update_identity::UpdateIdentityUseCase;
+use crate::domain::admin::import::ImportFormat;
+use crate::domain::admin::inputs::{CreateIdentityInput, UpdateIdentityInput};
+use crate::domain::admin::responses::{IdentityView, ImportReport};
+use crate::infrastructure::adapters::graphql::guards::{RequireAal2, identity_admin};
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, ID, Object, Result, Upload};
+use std::io::BufReader;
+
+#[derive(Default)]
+pub struct AdminMutation;
+
+#[Object]
+impl AdminMutation {
+    #[graphql(guard = "identity_admin()")]
+    async fn create_identity(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateIdentityInput,
+    ) -> Result<IdentityView> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+
+        CreateIdentityUseCase::execute(input, kratos_client)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    #[graphql(guard = "identity_admin()")]
+    async fn update_identity(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        input: UpdateIdentityInput,
+    ) -> Result<IdentityView> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+
+        UpdateIdentityUseCase::execute(&id, input, kratos_client)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Deleting needs a second factor on the caller's session.
+    #[graphql(guard = "identity_admin().and(RequireAal2)")]
+    async fn delete_identity(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+
+        DeleteIdentityUseCase::execute(&id, kratos_client)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    #[graphql(guard = "identity_admin()")]
+    async fn revoke_identity_sessions(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+
+        RevokeIdentitySessionsUseCase::execute(&id, kratos_client)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Imports a JSONL or CSV file of identities (multipart upload). The format
+    /// is guessed from the file name unless given.
+    #[graphql(guard = "identity_admin()")]
+    async fn import_identities(
+        &self,
+        ctx: &Context<'_>,
+        file: Upload,
+        format: Option<ImportFormat>,
+        batch_size: Option<u32>,
+    ) -> Result<ImportReport> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let upload = file.value(ctx)?;
+
+        let format = format.unwrap_or_else(|| ImportFormat::from_file_name(&upload.filename));
+        let batch_size = batch_size
+            .map(|size| size as usize)
+            .unwrap_or(DEFAULT_BATCH_SIZE);
+
+        ImportIdentitiesUseCase::execute(
+            BufReader::new(upload.content),
+            format,
+            batch_size,
+            kratos_client,
+        )
+        .await
+        .map_err(|e| e.extend())
+    }
+}
@@ -1,27 +1,47 @@
 use crate::application::usecases::auth::login::LoginUseCase;
-use crate::domain::auth::inputs::LoginInput;
+use crate::application::usecases::auth::login_second_factor::LoginSecondFactorUseCase;
+use crate::domain::auth::inputs::{ClientType, LoginInput, LoginSecondFactorInput};
 use crate::domain::auth::responses::AuthResponse;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::graphql::credentials::{
+    auth_state, resolve_client_type, session_credentials,
+};
+use crate::infrastructure::adapters::graphql::guards::RateLimit;
 use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
+use crate::infrastructure::adapters::jwt::JwtSigner;
 use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct LoginMutation;
 
 #[Object]
 impl LoginMutation {
-    async fn login(&self, ctx: &Context<'_>, input: LoginInput) -> Result<AuthResponse> {
+    #[graphql(guard = "RateLimit(\"login\", input.email.as_deref().or(input.username.as_deref()))")]
+    async fn login(
+        &self,
+        ctx: &Context<'_>,
+        input: LoginInput,
+        client_type: Option<ClientType>,
+    ) -> Result<AuthResponse> {
         let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let jwt_signer = ctx.data_unchecked::<JwtSigner>();
+        let refresh_tokens = ctx.data_unchecked::<Arc<dyn RefreshTokenRepository>>();
 
-        // ✅ Правильное извлечение cookie из контекста
-        let cookie = ctx
-            .data_opt::<Option<String>>()
-            .and_then(|opt| opt.as_ref())
-            .map(|s| s.as_str());
+        let auth = auth_state(ctx).context().map_err(|e| e.extend())?;
+        let client_type = resolve_client_type(ctx, client_type);
 
-        let (auth_response, cookies) = LoginUseCase::execute(input, kratos_client, cookie)
-            .await
-            .map_err(async_graphql::Error::new)?;
+        let (auth_response, cookies) = LoginUseCase::execute(
+            input,
+            client_type,
+            kratos_client,
+            jwt_signer,
+            refresh_tokens.as_ref(),
+            auth,
+        )
+        .await
+        .map_err(|e| e.extend())?;
 
         // ✅ Добавляем новые cookies в ответ
         if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
@@ -32,4 +52,41 @@ impl LoginMutation {
 
         Ok(auth_response)
     }
+
+    #[graphql(guard = "RateLimit(\"login_second_factor\", Some(&flow_id))")]
+    async fn login_second_factor(
+        &self,
+        ctx: &Context<'_>,
+        flow_id: String,
+        totp_code: Option<String>,
+        lookup_secret: Option<String>,
+    ) -> Result<AuthResponse> {
+        let input = LoginSecondFactorInput {
+            flow_id,
+            totp_code,
+            lookup_secret,
+        };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let jwt_signer = ctx.data_unchecked::<JwtSigner>();
+        let refresh_tokens = ctx.data_unchecked::<Arc<dyn RefreshTokenRepository>>();
+        let credentials = session_credentials(ctx);
+
+        let (auth_response, cookies) = LoginSecondFactorUseCase::execute(
+            input,
+            kratos_client,
+            jwt_signer,
+            refresh_tokens.as_ref(),
+            credentials,
+        )
+        .await
+        .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(auth_response)
+    }
 }
@@ -0,0 +1,35 @@
+use crate::application::usecases::auth::logout::LogoutUseCase;
+use crate::domain::auth::responses::LogoutResponse;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::graphql::credentials::session_credentials;
+use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct LogoutMutation;
+
+#[Object]
+impl LogoutMutation {
+    async fn logout(&self, ctx: &Context<'_>) -> Result<LogoutResponse> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let refresh_tokens = ctx.data_unchecked::<Arc<dyn RefreshTokenRepository>>();
+
+        let credentials = session_credentials(ctx);
+
+        let (logout_response, cookies) =
+            LogoutUseCase::execute(kratos_client, refresh_tokens.as_ref(), credentials)
+                .await
+                .map_err(|e| e.extend())?;
+
+        // Forward the cookie-clearing Set-Cookie headers from Kratos
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(logout_response)
+    }
+}
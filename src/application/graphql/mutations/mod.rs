@@ -1,2 +1,12 @@
+pub mod admin_mutation;
 pub mod login_mutation;
+pub mod logout_mutation;
+pub mod recovery_mutation;
 pub mod register_mutation;
+pub mod sessions_mutation;
+pub mod settings_mutation;
+pub mod social_login_mutation;
+pub mod token_mutation;
+pub mod two_factor_mutation;
+pub mod verification_mutation;
+pub mod webauthn_mutation;
@@ -0,0 +1,70 @@
+use crate::application::usecases::auth::complete_recovery::CompleteRecoveryUseCase;
+use crate::application::usecases::auth::request_recovery::RequestRecoveryUseCase;
+use crate::domain::auth::inputs::{ClientType, CompleteRecoveryInput, RequestRecoveryInput};
+use crate::domain::auth::responses::{RecoveryRequestResponse, RecoveryResponse};
+use crate::infrastructure::adapters::graphql::credentials::{
+    resolve_client_type, session_credentials,
+};
+use crate::infrastructure::adapters::graphql::guards::RateLimit;
+use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+
+#[derive(Default)]
+pub struct RecoveryMutation;
+
+#[Object]
+impl RecoveryMutation {
+    #[graphql(guard = "RateLimit(\"request_password_recovery\", Some(&email))")]
+    async fn request_password_recovery(
+        &self,
+        ctx: &Context<'_>,
+        email: String,
+        client_type: Option<ClientType>,
+    ) -> Result<RecoveryRequestResponse> {
+        let input = RequestRecoveryInput { email };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+        let client_type = resolve_client_type(ctx, client_type);
+
+        let (response, cookies) =
+            RequestRecoveryUseCase::execute(input, client_type, kratos_client, credentials)
+                .await
+                .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+
+    #[graphql(guard = "RateLimit(\"complete_recovery\", Some(&flow_id))")]
+    async fn complete_recovery(
+        &self,
+        ctx: &Context<'_>,
+        flow_id: String,
+        code: String,
+        client_type: Option<ClientType>,
+    ) -> Result<RecoveryResponse> {
+        let input = CompleteRecoveryInput { flow_id, code };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+        let client_type = resolve_client_type(ctx, client_type);
+
+        let (response, cookies) =
+            CompleteRecoveryUseCase::execute(input, client_type, kratos_client, credentials)
+                .await
+                .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+}
@@ -1,27 +1,36 @@
 use crate::application::usecases::auth::register::RegisterUseCase;
-use crate::domain::auth::inputs::RegisterInput;
+use crate::domain::auth::inputs::{ClientType, RegisterInput};
 use crate::domain::auth::responses::AuthResponse;
+use crate::infrastructure::adapters::graphql::credentials::{
+    resolve_client_type, session_credentials,
+};
+use crate::infrastructure::adapters::graphql::guards::RateLimit;
 use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
 use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, ErrorExtensions, Object, Result};
 
 #[derive(Default)]
 pub struct RegisterMutation;
 
 #[Object]
 impl RegisterMutation {
-    async fn register(&self, ctx: &Context<'_>, input: RegisterInput) -> Result<AuthResponse> {
+    #[graphql(guard = "RateLimit(\"register\", Some(&input.email))")]
+    async fn register(
+        &self,
+        ctx: &Context<'_>,
+        input: RegisterInput,
+        client_type: Option<ClientType>,
+    ) -> Result<AuthResponse> {
         let kratos_client = ctx.data_unchecked::<KratosClient>();
 
-        // Get cookie from context
-        let cookie = ctx
-            .data_opt::<Option<String>>()
-            .and_then(|opt| opt.as_ref())
-            .map(|s| s.as_str());
+        // Get cookie or session token from context
+        let credentials = session_credentials(ctx);
+        let client_type = resolve_client_type(ctx, client_type);
 
-        let (auth_response, cookies) = RegisterUseCase::execute(input, kratos_client, cookie)
-            .await
-            .map_err(async_graphql::Error::new)?;
+        let (auth_response, cookies) =
+            RegisterUseCase::execute(input, client_type, kratos_client, credentials)
+                .await
+                .map_err(|e| e.extend())?;
 
         if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
             for cookie_str in cookies {
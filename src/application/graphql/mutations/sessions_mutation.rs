@@ -0,0 +1,33 @@
+use crate::application::usecases::auth::revoke_other_sessions::RevokeOtherSessionsUseCase;
+use crate::application::usecases::auth::revoke_session::RevokeSessionUseCase;
+use crate::domain::auth::responses::RevokeSessionsResponse;
+use crate::infrastructure::adapters::graphql::credentials::session_credentials;
+use crate::infrastructure::adapters::graphql::guards::RequireAuth;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, ID, Object, Result};
+
+#[derive(Default)]
+pub struct SessionsMutation;
+
+#[Object]
+impl SessionsMutation {
+    #[graphql(guard = "RequireAuth")]
+    async fn revoke_session(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        RevokeSessionUseCase::execute(&id, kratos_client, credentials)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    #[graphql(guard = "RequireAuth")]
+    async fn revoke_other_sessions(&self, ctx: &Context<'_>) -> Result<RevokeSessionsResponse> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        RevokeOtherSessionsUseCase::execute(kratos_client, credentials)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
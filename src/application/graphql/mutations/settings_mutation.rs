@@ -0,0 +1,60 @@
+use crate::application::usecases::auth::change_password::ChangePasswordUseCase;
+use crate::application::usecases::auth::update_profile::UpdateProfileUseCase;
+use crate::domain::auth::inputs::{ChangePasswordInput, UpdateProfileInput};
+use crate::domain::auth::responses::UserView;
+use crate::infrastructure::adapters::graphql::credentials::session_credentials;
+use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+
+#[derive(Default)]
+pub struct SettingsMutation;
+
+#[Object]
+impl SettingsMutation {
+    async fn change_password(&self, ctx: &Context<'_>, password: String) -> Result<UserView> {
+        let input = ChangePasswordInput { password };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (user, cookies) = ChangePasswordUseCase::execute(input, kratos_client, credentials)
+            .await
+            .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(user)
+    }
+
+    async fn update_profile(
+        &self,
+        ctx: &Context<'_>,
+        email: Option<String>,
+        username: Option<String>,
+        geo_location: Option<String>,
+    ) -> Result<UserView> {
+        let input = UpdateProfileInput {
+            email,
+            username,
+            geo_location,
+        };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (user, cookies) = UpdateProfileUseCase::execute(input, kratos_client, credentials)
+            .await
+            .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(user)
+    }
+}
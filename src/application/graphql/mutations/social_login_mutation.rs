@@ -0,0 +1,44 @@
+use crate::application::usecases::auth::start_social_login::StartSocialLoginUseCase;
+use crate::domain::auth::responses::SocialLoginResponse;
+use crate::infrastructure::adapters::graphql::credentials::session_credentials;
+use crate::infrastructure::adapters::graphql::guards::RateLimit;
+use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+
+#[derive(Default)]
+pub struct SocialLoginMutation;
+
+#[Object]
+impl SocialLoginMutation {
+    // The provider checks the credentials; this only caps the Kratos flows
+    // an address can open
+    #[graphql(guard = "RateLimit(\"social_login\", None)")]
+    async fn start_social_login(
+        &self,
+        ctx: &Context<'_>,
+        provider: String,
+        return_to: Option<String>,
+    ) -> Result<SocialLoginResponse> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (response, cookies) = StartSocialLoginUseCase::execute(
+            &provider,
+            return_to.as_deref(),
+            kratos_client,
+            credentials,
+        )
+        .await
+        .map_err(|e| e.extend())?;
+
+        // The continuity cookie must reach the browser before the provider redirect
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::application::usecases::auth::issue_access_token::IssueAccessTokenUseCase;
+use crate::application::usecases::auth::refresh_token::RefreshTokenUseCase;
+use crate::domain::auth::responses::AccessTokenView;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::graphql::credentials::auth_state;
+use crate::infrastructure::adapters::graphql::guards::RateLimit;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct TokenMutation;
+
+#[Object]
+impl TokenMutation {
+    async fn issue_access_token(&self, ctx: &Context<'_>) -> Result<AccessTokenView> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let jwt_signer = ctx.data_unchecked::<JwtSigner>();
+        let refresh_tokens = ctx.data_unchecked::<Arc<dyn RefreshTokenRepository>>();
+        let auth = auth_state(ctx).context().map_err(|e| e.extend())?;
+
+        IssueAccessTokenUseCase::execute(kratos_client, jwt_signer, refresh_tokens.as_ref(), auth)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Swaps a refresh token for a new access/refresh pair; no session needed.
+    /// Counted by IP only, so refresh tokens never end up in counter keys.
+    #[graphql(guard = "RateLimit(\"refresh_token\", None)")]
+    async fn refresh_token(
+        &self,
+        ctx: &Context<'_>,
+        refresh_token: String,
+    ) -> Result<AccessTokenView> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let jwt_signer = ctx.data_unchecked::<JwtSigner>();
+        let refresh_tokens = ctx.data_unchecked::<Arc<dyn RefreshTokenRepository>>();
+
+        RefreshTokenUseCase::execute(
+            &refresh_token,
+            kratos_client,
+            jwt_signer,
+            refresh_tokens.as_ref(),
+        )
+        .await
+        .map_err(|e| e.extend())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::application::usecases::auth::confirm_totp::ConfirmTotpUseCase;
+use crate::application::usecases::auth::disable_totp::DisableTotpUseCase;
+use crate::application::usecases::auth::enroll_totp::EnrollTotpUseCase;
+use crate::application::usecases::auth::regenerate_lookup_secrets::RegenerateLookupSecretsUseCase;
+use crate::domain::auth::inputs::ConfirmTotpInput;
+use crate::domain::auth::responses::{LookupSecretsResponse, TotpEnrollmentResponse, UserView};
+use crate::infrastructure::adapters::graphql::credentials::session_credentials;
+use crate::infrastructure::adapters::graphql::guards::{RequireAal2, RequireVerifiedEmail};
+use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+
+#[derive(Default)]
+pub struct TwoFactorMutation;
+
+#[Object]
+impl TwoFactorMutation {
+    /// A verified email is required first, so the account stays recoverable.
+    #[graphql(guard = "RequireVerifiedEmail")]
+    async fn enroll_totp(&self, ctx: &Context<'_>) -> Result<TotpEnrollmentResponse> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (response, cookies) = EnrollTotpUseCase::execute(kratos_client, credentials)
+            .await
+            .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+
+    async fn confirm_totp(
+        &self,
+        ctx: &Context<'_>,
+        flow_id: String,
+        code: String,
+    ) -> Result<UserView> {
+        let input = ConfirmTotpInput { flow_id, code };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (user, cookies) = ConfirmTotpUseCase::execute(input, kratos_client, credentials)
+            .await
+            .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(user)
+    }
+
+    /// Only a session completed with a second factor may remove it.
+    #[graphql(guard = "RequireAal2")]
+    async fn disable_totp(&self, ctx: &Context<'_>) -> Result<UserView> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (user, cookies) = DisableTotpUseCase::execute(kratos_client, credentials)
+            .await
+            .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(user)
+    }
+
+    async fn regenerate_lookup_secrets(&self, ctx: &Context<'_>) -> Result<LookupSecretsResponse> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (response, cookies) =
+            RegenerateLookupSecretsUseCase::execute(kratos_client, credentials)
+                .await
+                .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+}
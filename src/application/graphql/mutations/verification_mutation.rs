@@ -0,0 +1,72 @@
+use crate::application::usecases::auth::send_verification::SendVerificationUseCase;
+use crate::application::usecases::auth::verify_email::VerifyEmailUseCase;
+use crate::domain::auth::inputs::{ClientType, SendVerificationInput, VerifyEmailInput};
+use crate::domain::auth::responses::{VerificationRequestResponse, VerificationResponse};
+use crate::infrastructure::adapters::graphql::credentials::{
+    auth_state, resolve_client_type, session_credentials,
+};
+use crate::infrastructure::adapters::graphql::guards::RateLimit;
+use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+
+#[derive(Default)]
+pub struct VerificationMutation;
+
+#[Object]
+impl VerificationMutation {
+    // Each call makes Kratos send a mail, so it is limited per address too
+    #[graphql(guard = "RateLimit(\"send_verification_email\", email.as_deref())")]
+    async fn send_verification_email(
+        &self,
+        ctx: &Context<'_>,
+        email: Option<String>,
+        client_type: Option<ClientType>,
+    ) -> Result<VerificationRequestResponse> {
+        let input = SendVerificationInput { email };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let auth = auth_state(ctx).context().map_err(|e| e.extend())?;
+        let credentials = session_credentials(ctx);
+        let client_type = resolve_client_type(ctx, client_type);
+
+        let (response, cookies) =
+            SendVerificationUseCase::execute(input, client_type, kratos_client, auth, credentials)
+                .await
+                .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+
+    #[graphql(guard = "RateLimit(\"verify_email\", Some(&flow_id))")]
+    async fn verify_email(
+        &self,
+        ctx: &Context<'_>,
+        flow_id: String,
+        code: String,
+        client_type: Option<ClientType>,
+    ) -> Result<VerificationResponse> {
+        let input = VerifyEmailInput { flow_id, code };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+        let client_type = resolve_client_type(ctx, client_type);
+
+        let (response, cookies) =
+            VerifyEmailUseCase::execute(input, client_type, kratos_client, credentials)
+                .await
+                .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+}
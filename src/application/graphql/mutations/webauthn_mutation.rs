@@ -0,0 +1,149 @@
+use crate::application::usecases::auth::finish_webauthn_login::FinishWebauthnLoginUseCase;
+use crate::application::usecases::auth::finish_webauthn_registration::FinishWebauthnRegistrationUseCase;
+use crate::application::usecases::auth::start_webauthn_login::StartWebauthnLoginUseCase;
+use crate::application::usecases::auth::start_webauthn_registration::StartWebauthnRegistrationUseCase;
+use crate::domain::auth::inputs::{
+    FinishWebauthnLoginInput, FinishWebauthnRegistrationInput, WebauthnMethod,
+};
+use crate::domain::auth::responses::{AuthResponse, UserView, WebauthnChallengeResponse};
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::graphql::credentials::session_credentials;
+use crate::infrastructure::adapters::graphql::guards::{RateLimit, RequireVerifiedEmail};
+use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct WebauthnMutation;
+
+#[Object]
+impl WebauthnMutation {
+    /// `method` picks a security key (`WEBAUTHN`) or a passkey (`PASSKEY`).
+    /// A verified email is required first, so the account stays recoverable.
+    #[graphql(guard = "RequireVerifiedEmail")]
+    async fn start_webauthn_registration(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] method: WebauthnMethod,
+    ) -> Result<WebauthnChallengeResponse> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (response, cookies) =
+            StartWebauthnRegistrationUseCase::execute(method, kratos_client, credentials)
+                .await
+                .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// `credential` is the JSON encoded result of `navigator.credentials.create()`.
+    /// `displayName` is required for security keys; Kratos names passkeys itself.
+    async fn finish_webauthn_registration(
+        &self,
+        ctx: &Context<'_>,
+        flow_id: String,
+        display_name: Option<String>,
+        credential: String,
+        #[graphql(default)] method: WebauthnMethod,
+    ) -> Result<UserView> {
+        let input = FinishWebauthnRegistrationInput {
+            method,
+            flow_id,
+            display_name,
+            credential,
+        };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (user, cookies) =
+            FinishWebauthnRegistrationUseCase::execute(input, kratos_client, credentials)
+                .await
+                .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(user)
+    }
+
+    /// `identifier` is required for security keys; passkeys are discoverable.
+    async fn start_webauthn_login(
+        &self,
+        ctx: &Context<'_>,
+        identifier: Option<String>,
+        #[graphql(default)] method: WebauthnMethod,
+    ) -> Result<WebauthnChallengeResponse> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let credentials = session_credentials(ctx);
+
+        let (response, cookies) = StartWebauthnLoginUseCase::execute(
+            method,
+            identifier.as_deref(),
+            kratos_client,
+            credentials,
+        )
+        .await
+        .map_err(|e| e.extend())?;
+
+        // The login flow's CSRF cookie has to come back with finishWebauthnLogin
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// `assertion` is the JSON encoded result of `navigator.credentials.get()`.
+    #[graphql(guard = "RateLimit(\"webauthn_login\", identifier.as_deref().or(Some(&flow_id)))")]
+    async fn finish_webauthn_login(
+        &self,
+        ctx: &Context<'_>,
+        flow_id: String,
+        identifier: Option<String>,
+        assertion: String,
+        #[graphql(default)] method: WebauthnMethod,
+    ) -> Result<AuthResponse> {
+        let input = FinishWebauthnLoginInput {
+            method,
+            flow_id,
+            identifier,
+            assertion,
+        };
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let jwt_signer = ctx.data_unchecked::<JwtSigner>();
+        let refresh_tokens = ctx.data_unchecked::<Arc<dyn RefreshTokenRepository>>();
+        let credentials = session_credentials(ctx);
+
+        let (auth_response, cookies) = FinishWebauthnLoginUseCase::execute(
+            input,
+            kratos_client,
+            jwt_signer,
+            refresh_tokens.as_ref(),
+            credentials,
+        )
+        .await
+        .map_err(|e| e.extend())?;
+
+        if let Some(response_cookies) = ctx.data_opt::<ResponseCookies>() {
+            for cookie_str in cookies {
+                response_cookies.add_cookie(cookie_str).await;
+            }
+        }
+
+        Ok(auth_response)
+    }
+}
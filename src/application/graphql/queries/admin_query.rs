@@ -0,0 +1,53 @@
+use crate::application::usecases::admin::export_identities::ExportIdentitiesUseCase;
+use crate::application::usecases::admin::get_identity::GetIdentityUseCase;
+use crate::application::usecases::admin::list_identities::ListIdentitiesUseCase;
+use crate::domain::admin::responses::{IdentityConnection, IdentityView};
+use crate::infrastructure::adapters::graphql::guards::{RequireOwner, identity_admin};
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, ID, Object, Result};
+
+#[derive(Default)]
+pub struct AdminQuery;
+
+#[Object]
+impl AdminQuery {
+    /// `search` matches a login identifier (email or username) exactly.
+    #[graphql(guard = "identity_admin()")]
+    async fn identities(
+        &self,
+        ctx: &Context<'_>,
+        page_size: Option<u32>,
+        page_token: Option<String>,
+        search: Option<String>,
+    ) -> Result<IdentityConnection> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+
+        ListIdentitiesUseCase::execute(page_size, page_token, search, kratos_client)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Callers may read their own identity.
+    #[graphql(guard = "RequireOwner(&id).or(identity_admin())")]
+    async fn identity(&self, ctx: &Context<'_>, id: ID) -> Result<Option<IdentityView>> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+
+        GetIdentityUseCase::execute(&id, kratos_client)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// All identities as JSONL, one `IdentityRecord` per line. Use the
+    /// `export-identities` CLI command for large tenants.
+    #[graphql(guard = "identity_admin()")]
+    async fn export_identities(&self, ctx: &Context<'_>) -> Result<String> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let mut buffer = Vec::new();
+
+        ExportIdentitiesUseCase::execute(&mut buffer, kratos_client)
+            .await
+            .map_err(|e| e.extend())?;
+
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
@@ -1 +1,5 @@
+pub mod admin_query;
 pub mod health_query;
+pub mod permission_query;
+pub mod session_query;
+pub mod social_query;
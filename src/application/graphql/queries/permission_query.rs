@@ -0,0 +1,22 @@
+use crate::application::usecases::auth::check_permission::CheckPermissionUseCase;
+use crate::domain::repositories::permission_repository::PermissionRepository;
+use crate::infrastructure::adapters::graphql::credentials::auth_state;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct PermissionQuery;
+
+#[Object]
+impl PermissionQuery {
+    /// Whether the caller has `relation` on `object`, written as `namespace:id`
+    /// (e.g. `can(relation: "edit", object: "projects:42")`).
+    async fn can(&self, ctx: &Context<'_>, relation: String, object: String) -> Result<bool> {
+        let permissions = ctx.data_unchecked::<Arc<dyn PermissionRepository>>();
+        let auth = auth_state(ctx).context().map_err(|e| e.extend())?;
+
+        CheckPermissionUseCase::execute(&relation, &object, auth, permissions.as_ref())
+            .await
+            .map_err(|e| e.extend())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::application::usecases::auth::list_sessions::ListSessionsUseCase;
+use crate::application::usecases::auth::session::CurrentSessionUseCase;
+use crate::domain::auth::responses::{DeviceSessionView, SessionView, UserView};
+use crate::infrastructure::adapters::graphql::credentials::{auth_state, session_credentials};
+use crate::infrastructure::adapters::graphql::guards::RequireAuth;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+
+#[derive(Default)]
+pub struct SessionQuery;
+
+impl SessionQuery {
+    async fn resolve_session(ctx: &Context<'_>) -> Result<Option<SessionView>> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let auth = auth_state(ctx).context().map_err(|e| e.extend())?;
+
+        CurrentSessionUseCase::execute(kratos_client, auth)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
+
+#[Object]
+impl SessionQuery {
+    async fn current_session(&self, ctx: &Context<'_>) -> Result<Option<SessionView>> {
+        Self::resolve_session(ctx).await
+    }
+
+    async fn me(&self, ctx: &Context<'_>) -> Result<Option<UserView>> {
+        Ok(Self::resolve_session(ctx)
+            .await?
+            .map(|session| session.user))
+    }
+
+    #[graphql(guard = "RequireAuth")]
+    async fn my_sessions(&self, ctx: &Context<'_>) -> Result<Vec<DeviceSessionView>> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+        let auth = auth_state(ctx).context().map_err(|e| e.extend())?;
+        let credentials = session_credentials(ctx);
+
+        ListSessionsUseCase::execute(kratos_client, auth, credentials)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
@@ -0,0 +1,18 @@
+use crate::application::usecases::auth::list_social_providers::ListSocialProvidersUseCase;
+use crate::domain::auth::responses::SocialProvider;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use async_graphql::{Context, ErrorExtensions, Object, Result};
+
+#[derive(Default)]
+pub struct SocialQuery;
+
+#[Object]
+impl SocialQuery {
+    async fn social_providers(&self, ctx: &Context<'_>) -> Result<Vec<SocialProvider>> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+
+        ListSocialProvidersUseCase::execute(kratos_client)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
@@ -1,5 +1,15 @@
+use crate::application::usecases::auth::check_permission::CheckPermissionUseCase;
 use crate::application::usecases::health_check::HealthCheck;
-use actix_web::{HttpResponse, Responder, get};
+use crate::domain::admin::inputs::{ADMIN_ROLE, IDENTITIES_OBJECT, MANAGE_RELATION};
+use crate::domain::auth::context::AuthState;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::repositories::permission_repository::PermissionRepository;
+use crate::infrastructure::adapters::http::auth_middleware::resolve_caller;
+use crate::infrastructure::adapters::http::error_response::auth_error_response;
+use crate::infrastructure::adapters::http::upstream_pool::UpstreamPools;
+use actix_web::http::StatusCode;
+use actix_web::middleware::from_fn;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, Responder, get};
 use tracing::instrument;
 
 #[get("/health")]
@@ -10,8 +20,45 @@ async fn health() -> impl Responder {
     HttpResponse::Ok().body(result)
 }
 
+/// Balancing and health state of every proxied route's upstream pool, for
+/// identity administrators.
+#[get("/health/upstreams", wrap = "from_fn(resolve_caller)")]
+#[instrument(skip(req, pools, permissions))]
+async fn upstreams(
+    req: HttpRequest,
+    pools: web::Data<UpstreamPools>,
+    permissions: web::Data<dyn PermissionRepository>,
+) -> HttpResponse {
+    let auth_state = req
+        .extensions()
+        .get::<AuthState>()
+        .cloned()
+        .unwrap_or_default();
+
+    let auth = match auth_state.context() {
+        Ok(Some(auth)) => auth,
+        Ok(None) => {
+            return auth_error_response(StatusCode::UNAUTHORIZED, AuthError::NotAuthenticated);
+        }
+        Err(e) => return auth_error_response(StatusCode::SERVICE_UNAVAILABLE, e),
+    };
+
+    match CheckPermissionUseCase::identity_admin(auth, permissions.get_ref()).await {
+        Ok(true) => HttpResponse::Ok().json(pools.status()),
+        Ok(false) => auth_error_response(
+            StatusCode::FORBIDDEN,
+            AuthError::Forbidden(format!(
+                "the '{}' role or '{}' on '{}' is required",
+                ADMIN_ROLE, MANAGE_RELATION, IDENTITIES_OBJECT
+            )),
+        ),
+        Err(e) => auth_error_response(StatusCode::SERVICE_UNAVAILABLE, e),
+    }
+}
+
 use actix_web::web;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(health);
+    cfg.service(upstreams);
 }
@@ -0,0 +1,20 @@
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use actix_web::http::header::{CACHE_CONTROL, CacheControl, CacheDirective};
+use actix_web::{HttpResponse, Responder, get, web};
+use tracing::instrument;
+
+/// Public keys downstream services use to verify gateway access tokens.
+#[get("/.well-known/jwks.json")]
+#[instrument(skip(jwt_signer))]
+async fn jwks(jwt_signer: web::Data<JwtSigner>) -> impl Responder {
+    HttpResponse::Ok()
+        .insert_header((
+            CACHE_CONTROL,
+            CacheControl(vec![CacheDirective::Public, CacheDirective::MaxAge(300)]),
+        ))
+        .json(jwt_signer.jwks())
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(jwks);
+}
@@ -0,0 +1,87 @@
+use crate::infrastructure::adapters::http::resilience::{BreakerState, BreakerStatus};
+use crate::infrastructure::adapters::http::upstream_pool::UpstreamPools;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use actix_web::{HttpResponse, Responder, get, web};
+use std::fmt::Write;
+use tracing::instrument;
+
+/// Circuit breakers of Kratos and every proxied route, in the Prometheus
+/// text exposition format.
+#[get("/metrics")]
+#[instrument(skip(kratos_client, pools))]
+async fn metrics(
+    kratos_client: web::Data<KratosClient>,
+    pools: web::Data<UpstreamPools>,
+) -> impl Responder {
+    let breakers: Vec<BreakerStatus> = std::iter::once(kratos_client.breaker().status())
+        .chain(pools.iter().map(|pool| pool.breaker().status()))
+        .collect();
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(render(&breakers))
+}
+
+type Counter = fn(&BreakerStatus) -> u64;
+
+fn render(breakers: &[BreakerStatus]) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP gateway_circuit_breaker_state Current state, 1 for the active one.\n");
+    out.push_str("# TYPE gateway_circuit_breaker_state gauge\n");
+    for breaker in breakers {
+        for state in [
+            BreakerState::Closed,
+            BreakerState::Open,
+            BreakerState::HalfOpen,
+        ] {
+            writeln!(
+                out,
+                "gateway_circuit_breaker_state{{breaker=\"{}\",state=\"{}\"}} {}",
+                escape(&breaker.name),
+                state.as_str(),
+                u8::from(breaker.state == state)
+            )
+            .ok();
+        }
+    }
+
+    let counters: [(&str, &str, Counter); 4] = [
+        ("failures", "Failed calls.", |b| b.failures),
+        ("rejected", "Calls rejected while open.", |b| b.rejected),
+        ("opened", "Times the circuit opened.", |b| b.opened),
+        ("retries", "Retried calls.", |b| b.retries),
+    ];
+    for (name, help, value) in counters {
+        writeln!(
+            out,
+            "# HELP gateway_circuit_breaker_{}_total {}",
+            name, help
+        )
+        .ok();
+        writeln!(out, "# TYPE gateway_circuit_breaker_{}_total counter", name).ok();
+        for breaker in breakers {
+            writeln!(
+                out,
+                "gateway_circuit_breaker_{}_total{{breaker=\"{}\"}} {}",
+                name,
+                escape(&breaker.name),
+                value(breaker)
+            )
+            .ok();
+        }
+    }
+
+    out
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(metrics);
+}
@@ -1 +1,5 @@
 pub mod health_check;
+pub mod jwks;
+pub mod metrics;
+pub mod oidc_callback;
+pub mod proxy;
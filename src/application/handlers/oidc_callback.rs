@@ -0,0 +1,69 @@
+use crate::infrastructure::adapters::http::error_response::auth_error_response;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use actix_web::http::StatusCode;
+use actix_web::http::header::{CONTENT_TYPE, COOKIE, LOCATION, SET_COOKIE};
+use actix_web::{HttpRequest, HttpResponse, web};
+use tracing::{error, info, instrument};
+
+/// Providers redirect here (Kratos `base_redirect_uri` points at the gateway).
+/// The callback is replayed to Kratos and its redirect plus session cookie are
+/// returned to the browser.
+#[instrument(skip(req, body, kratos_client))]
+async fn oidc_callback(
+    req: HttpRequest,
+    provider: web::Path<String>,
+    body: web::Bytes,
+    kratos_client: web::Data<KratosClient>,
+) -> HttpResponse {
+    let cookie = req
+        .headers()
+        .get(COOKIE)
+        .and_then(|value| value.to_str().ok());
+
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/x-www-form-urlencoded");
+
+    let form = (!body.is_empty()).then(|| (content_type, body.to_vec()));
+
+    let result = match kratos_client
+        .handle_oidc_callback(&provider, req.query_string(), cookie, form)
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            error!(error = %e, provider = %provider, "OIDC callback failed");
+            return auth_error_response(StatusCode::BAD_GATEWAY, e.into());
+        }
+    };
+
+    info!(
+        provider = %provider,
+        status = result.status,
+        cookies_count = result.cookies.len(),
+        "OIDC callback completed"
+    );
+
+    let status = StatusCode::from_u16(result.status).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut response = HttpResponse::build(status);
+
+    if let Some(location) = result.location {
+        response.insert_header((LOCATION, location));
+    }
+
+    for cookie in result.cookies {
+        response.append_header((SET_COOKIE, cookie));
+    }
+
+    response.body(result.body)
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/self-service/methods/oidc/callback/{provider}")
+            .route(web::get().to(oidc_callback))
+            .route(web::post().to(oidc_callback)),
+    );
+}
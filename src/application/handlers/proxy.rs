@@ -0,0 +1,319 @@
+use crate::domain::auth::context::AuthState;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::gateway::rate_limit::{RateLimitConfig, RateLimitScope};
+use crate::infrastructure::adapters::http::auth_middleware::{
+    extract_bearer_token, resolve_caller,
+};
+use crate::infrastructure::adapters::http::error_response::{auth_error_response, error_response};
+use crate::infrastructure::adapters::http::identity_headers::{IDENTITY_HEADERS, identity_headers};
+use crate::infrastructure::adapters::http::rate_limit_middleware::{client_ip, rate_limit};
+use crate::infrastructure::adapters::http::resilience::backoff_delay;
+use crate::infrastructure::adapters::http::upstream_client::{UpstreamClient, UpstreamError};
+use crate::infrastructure::adapters::http::upstream_pool::{UpstreamPool, UpstreamPools};
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use actix_web::http::StatusCode;
+use actix_web::http::header::HOST;
+use actix_web::middleware::from_fn;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, web};
+use tracing::{error, warn};
+
+/// Connection-level headers that must not be forwarded (RFC 9110 §7.6.1),
+/// plus the ones the HTTP clients recompute.
+const HOP_BY_HOP_HEADERS: [&str; 10] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "host",
+    "content-length",
+];
+
+/// Gateway credentials, consumed here and never passed upstream.
+const CREDENTIAL_HEADERS: [&str; 2] = ["authorization", "x-session-token"];
+
+const KRATOS_SESSION_COOKIE: &str = "ory_kratos_session";
+
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+pub fn configure(cfg: &mut web::ServiceConfig, pools: &UpstreamPools) {
+    for pool in pools.iter() {
+        cfg.service(
+            web::scope(&pool.route().prefix)
+                .app_data(web::Data::from(pool.clone()))
+                .app_data(web::PayloadConfig::new(MAX_BODY_BYTES))
+                .app_data(web::Data::new(RateLimitScope {
+                    name: format!("route:{}", pool.route().prefix),
+                    rules: pool.route().rate_limit.clone(),
+                }))
+                .wrap(from_fn(resolve_caller))
+                .wrap(from_fn(rate_limit))
+                .default_service(web::to(proxy)),
+        );
+    }
+}
+
+async fn proxy(
+    req: HttpRequest,
+    body: web::Bytes,
+    pool: web::Data<UpstreamPool>,
+    upstream_client: web::Data<UpstreamClient>,
+    jwt_signer: web::Data<JwtSigner>,
+) -> HttpResponse {
+    let route = pool.route();
+
+    if !route.allows_method(req.method().as_str()) {
+        return error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "METHOD_NOT_ALLOWED",
+            &format!("{} is not allowed on {}", req.method(), route.prefix),
+        );
+    }
+
+    let auth_state = req
+        .extensions()
+        .get::<AuthState>()
+        .cloned()
+        .unwrap_or_default();
+
+    let auth = match auth_state.context() {
+        Ok(Some(auth)) => Some(auth.clone()),
+        Ok(None) if route.require_auth => {
+            return auth_error_response(StatusCode::UNAUTHORIZED, AuthError::NotAuthenticated);
+        }
+        Ok(None) => None,
+        Err(e) if route.require_auth => {
+            return auth_error_response(StatusCode::SERVICE_UNAVAILABLE, e);
+        }
+        // Public routes stay reachable while Kratos is down, just anonymously
+        Err(_) => None,
+    };
+
+    let mut headers = forwarded_headers(&req);
+
+    if let Some(auth) = &auth {
+        match identity_headers(
+            auth,
+            route.identity,
+            &jwt_signer,
+            extract_bearer_token(req.headers()),
+        ) {
+            Ok(identity) => headers.extend(identity),
+            Err(e) => {
+                error!(error = %e, "Failed to build identity headers");
+                return auth_error_response(StatusCode::INTERNAL_SERVER_ERROR, e);
+            }
+        }
+    }
+
+    let identity_id = auth.as_ref().map(|auth| auth.identity_id.as_str());
+    let attempts = match req.method().as_str() {
+        "GET" | "HEAD" | "OPTIONS" => route.retry.attempts,
+        _ => 1,
+    };
+
+    let Some(mut upstream) = pool.select(identity_id) else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "NO_HEALTHY_UPSTREAM",
+            &format!("No healthy upstream for {}", route.prefix),
+        );
+    };
+    let Some(mut permit) = pool.breaker().try_acquire() else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "SERVICE_UNAVAILABLE",
+            &format!("{} is failing, try again later", route.prefix),
+        );
+    };
+
+    let mut attempt = 1;
+    let response = loop {
+        let url = route.upstream_url(upstream.url(), req.path(), req.query_string());
+        let result = upstream_client
+            .send(
+                req.method().as_str(),
+                &url,
+                headers.clone(),
+                body.to_vec(),
+                route.timeout(),
+            )
+            .await;
+        let failed = match &result {
+            Ok(response) => matches!(response.status, 502..=504),
+            Err(e) => {
+                warn!(error = %e, route = %route.prefix, url = %url, attempt, "Proxy request failed");
+                true
+            }
+        };
+        upstream.report(!failed);
+        permit.record(!failed);
+        drop(upstream);
+
+        if !failed || attempt >= attempts {
+            break result;
+        }
+        actix_web::rt::time::sleep(backoff_delay(&route.retry, attempt)).await;
+
+        // The circuit may have opened meanwhile; then the last failure stands
+        let Some(next_upstream) = pool.select(identity_id) else {
+            break result;
+        };
+        let Some(next_permit) = pool.breaker().try_acquire() else {
+            break result;
+        };
+        pool.breaker().record_retry();
+        (permit, upstream) = (next_permit, next_upstream);
+        attempt += 1;
+    };
+
+    let response = match response {
+        Ok(response) => response,
+        // The error names the upstream's URL; it was logged with the attempt
+        Err(e) => {
+            let (status, code, message) = match e {
+                UpstreamError::Timeout(_) => (
+                    StatusCode::GATEWAY_TIMEOUT,
+                    "UPSTREAM_TIMEOUT",
+                    "Upstream did not answer in time",
+                ),
+                UpstreamError::Connect(_) => (
+                    StatusCode::BAD_GATEWAY,
+                    "BAD_GATEWAY",
+                    "Upstream unreachable",
+                ),
+                UpstreamError::Request(_) => (
+                    StatusCode::BAD_GATEWAY,
+                    "BAD_GATEWAY",
+                    "Upstream request failed",
+                ),
+            };
+            return error_response(status, code, message);
+        }
+    };
+
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut builder = HttpResponse::build(status);
+    for (name, value) in response.headers {
+        if !HOP_BY_HOP_HEADERS.contains(&name.as_str()) {
+            builder.append_header((name, value));
+        }
+    }
+
+    builder.body(response.body)
+}
+
+/// Client headers minus hop-by-hop, credential and identity headers, with the
+/// Kratos session cookie removed and `X-Forwarded-*` added. Client supplied
+/// forwarding headers are only relayed behind a trusted proxy.
+fn forwarded_headers(req: &HttpRequest) -> Vec<(String, Vec<u8>)> {
+    let mut headers: Vec<(String, Vec<u8>)> = req
+        .headers()
+        .iter()
+        .filter(|(name, _)| {
+            let name = name.as_str();
+            !HOP_BY_HOP_HEADERS.contains(&name)
+                && !CREDENTIAL_HEADERS.contains(&name)
+                && !IDENTITY_HEADERS.contains(&name)
+                && !name.starts_with("x-forwarded-")
+                && name != "forwarded"
+        })
+        .filter_map(|(name, value)| {
+            if name.as_str() != "cookie" {
+                return Some((name.as_str().to_string(), value.as_bytes().to_vec()));
+            }
+
+            let cookies: Vec<&str> = value
+                .to_str()
+                .ok()?
+                .split(';')
+                .map(str::trim)
+                .filter(|cookie| !cookie.starts_with(KRATOS_SESSION_COOKIE))
+                .collect();
+            (!cookies.is_empty()).then(|| ("cookie".to_string(), cookies.join("; ").into_bytes()))
+        })
+        .collect();
+
+    let trust_forwarded_for = req
+        .app_data::<web::Data<RateLimitConfig>>()
+        .is_some_and(|config| config.trust_forwarded_for);
+    let connection_info = req.connection_info();
+
+    if let Some(client_ip) = client_ip(&connection_info, trust_forwarded_for) {
+        headers.push(("x-forwarded-for".to_string(), client_ip.into_bytes()));
+    }
+
+    let (host, scheme) = if trust_forwarded_for {
+        (connection_info.host(), connection_info.scheme())
+    } else {
+        let host = req
+            .headers()
+            .get(HOST)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_else(|| req.app_config().host());
+        let scheme = if req.app_config().secure() {
+            "https"
+        } else {
+            "http"
+        };
+        (host, scheme)
+    };
+    headers.push(("x-forwarded-host".to_string(), host.as_bytes().to_vec()));
+    headers.push(("x-forwarded-proto".to_string(), scheme.as_bytes().to_vec()));
+
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn header<'a>(headers: &'a [(String, Vec<u8>)], name: &str) -> Option<&'a [u8]> {
+        headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    fn request(trust_forwarded_for: bool) -> HttpRequest {
+        TestRequest::default()
+            .peer_addr("10.0.0.7:41000".parse().unwrap())
+            .insert_header((HOST, "gateway.local"))
+            .insert_header(("x-forwarded-for", "203.0.113.9"))
+            .insert_header(("x-forwarded-host", "evil.example"))
+            .insert_header(("forwarded", "for=198.51.100.1;proto=https"))
+            .app_data(web::Data::new(RateLimitConfig {
+                trust_forwarded_for,
+                ..RateLimitConfig::default()
+            }))
+            .to_http_request()
+    }
+
+    #[test]
+    fn ignores_client_forwarding_headers_by_default() {
+        let headers = forwarded_headers(&request(false));
+
+        assert_eq!(header(&headers, "x-forwarded-for"), Some(&b"10.0.0.7"[..]));
+        assert_eq!(
+            header(&headers, "x-forwarded-host"),
+            Some(&b"gateway.local"[..])
+        );
+        assert_eq!(header(&headers, "x-forwarded-proto"), Some(&b"http"[..]));
+        assert_eq!(header(&headers, "forwarded"), None);
+    }
+
+    #[test]
+    fn relays_the_forwarded_client_behind_a_trusted_proxy() {
+        let headers = forwarded_headers(&request(true));
+
+        assert_eq!(
+            header(&headers, "x-forwarded-for"),
+            Some(&b"198.51.100.1"[..])
+        );
+        assert_eq!(header(&headers, "x-forwarded-proto"), Some(&b"https"[..]));
+    }
+}
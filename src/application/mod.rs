@@ -1,4 +1,5 @@
 pub mod bootstrap;
+pub mod cli;
 pub mod graphql;
 pub mod handlers;
 pub mod usecases;
@@ -0,0 +1,67 @@
+use crate::domain::admin::inputs::CreateIdentityInput;
+use crate::domain::admin::responses::IdentityView;
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::kratos_client::{
+    IdentityTraits, KratosClient, NewIdentity,
+};
+use tracing::{error, info};
+use validator::Validate;
+
+#[derive(Validate)]
+struct CreateIdentityValidation {
+    #[validate(email)]
+    email: String,
+    #[validate(length(min = 3, max = 20))]
+    username: String,
+    #[validate(length(min = 8))]
+    password: Option<String>,
+}
+
+pub struct CreateIdentityUseCase;
+
+impl CreateIdentityUseCase {
+    pub async fn execute(
+        input: CreateIdentityInput,
+        kratos_client: &KratosClient,
+    ) -> Result<IdentityView, AuthError> {
+        Self::validate_input(&input)?;
+
+        let new_identity = NewIdentity {
+            traits: IdentityTraits {
+                email: input.email,
+                username: input.username,
+                geo_location: input.geo_location,
+            },
+            password: input.password,
+            password_hash: None,
+            state: input.state.map(|state| state.as_str().to_string()),
+            roles: input.roles,
+            verified: input.verified,
+        };
+
+        let identity = kratos_client
+            .admin_create_identity(new_identity)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to create identity");
+                AuthError::from(e)
+            })?;
+
+        info!(identity_id = %identity.id, "Identity created by admin");
+
+        Ok(IdentityView::from(identity))
+    }
+
+    fn validate_input(input: &CreateIdentityInput) -> Result<(), AuthError> {
+        let validation = CreateIdentityValidation {
+            email: input.email.clone(),
+            username: input.username.clone(),
+            password: input.password.clone(),
+        };
+
+        validation
+            .validate()
+            .map_err(|e| AuthError::InvalidInput(format!("Validation error: {}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use tracing::{error, info};
+
+pub struct DeleteIdentityUseCase;
+
+impl DeleteIdentityUseCase {
+    pub async fn execute(
+        identity_id: &str,
+        kratos_client: &KratosClient,
+    ) -> Result<bool, AuthError> {
+        kratos_client
+            .admin_delete_identity(identity_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, identity_id = identity_id, "Failed to delete identity");
+                AuthError::from(e)
+            })?;
+
+        info!(identity_id = identity_id, "Identity deleted by admin");
+
+        Ok(true)
+    }
+}
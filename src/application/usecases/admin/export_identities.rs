@@ -0,0 +1,51 @@
+use crate::domain::admin::import::IdentityRecord;
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use std::io::Write;
+use tracing::{error, info};
+
+const EXPORT_PAGE_SIZE: u32 = 250;
+
+pub struct ExportIdentitiesUseCase;
+
+impl ExportIdentitiesUseCase {
+    /// Writes every identity as one JSONL `IdentityRecord`, page by page.
+    /// Passwords are never exported. Returns the number of identities written.
+    pub async fn execute<W: Write + Send>(
+        writer: &mut W,
+        kratos_client: &KratosClient,
+    ) -> Result<u64, AuthError> {
+        let mut exported = 0;
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let page = kratos_client
+                .admin_list_identities(EXPORT_PAGE_SIZE, page_token.as_deref(), None)
+                .await
+                .map_err(|e| {
+                    error!(error = %e, "Failed to list identities for export");
+                    AuthError::from(e)
+                })?;
+
+            for identity in page.identities {
+                let line = serde_json::to_string(&IdentityRecord::from(identity))
+                    .map_err(|e| AuthError::Internal(e.to_string()))?;
+                writeln!(writer, "{}", line).map_err(|e| AuthError::Internal(e.to_string()))?;
+                exported += 1;
+            }
+
+            match page.next_page_token {
+                Some(token) if page_token.as_ref() != Some(&token) => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        writer
+            .flush()
+            .map_err(|e| AuthError::Internal(e.to_string()))?;
+
+        info!(exported = exported, "Identity export finished");
+
+        Ok(exported)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::domain::admin::responses::IdentityView;
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::KratosError;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use tracing::error;
+
+pub struct GetIdentityUseCase;
+
+impl GetIdentityUseCase {
+    /// Returns `None` for unknown identities instead of an error.
+    pub async fn execute(
+        identity_id: &str,
+        kratos_client: &KratosClient,
+    ) -> Result<Option<IdentityView>, AuthError> {
+        match kratos_client.admin_get_identity(identity_id).await {
+            Ok(identity) => Ok(Some(IdentityView::from(identity))),
+            Err(KratosError::NotFound) => Ok(None),
+            Err(e) => {
+                error!(error = %e, identity_id = identity_id, "Failed to fetch identity");
+                Err(AuthError::from(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use crate::domain::admin::import::{IdentityRecord, ImportFormat, split_csv_line};
+use crate::domain::admin::responses::{ImportReport, ImportRowError};
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::KratosError;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, NewIdentity};
+use std::io::BufRead;
+use tracing::{info, warn};
+
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+const MAX_BATCH_SIZE: usize = 1000;
+
+struct PendingRow {
+    line: u64,
+    identifier: Option<String>,
+    identity: NewIdentity,
+}
+
+pub struct ImportIdentitiesUseCase;
+
+impl ImportIdentitiesUseCase {
+    /// Streams `reader` line by line into Kratos batches of `batch_size`. Rows
+    /// that cannot be parsed or are rejected by Kratos end up in the report;
+    /// they never abort the import.
+    pub async fn execute<R: BufRead + Send>(
+        reader: R,
+        format: ImportFormat,
+        batch_size: usize,
+        kratos_client: &KratosClient,
+    ) -> Result<ImportReport, AuthError> {
+        if batch_size == 0 || batch_size > MAX_BATCH_SIZE {
+            return Err(AuthError::InvalidInput(format!(
+                "batchSize must be between 1 and {}",
+                MAX_BATCH_SIZE
+            )));
+        }
+
+        let mut report = ImportReport::default();
+        let mut csv_header: Option<Vec<String>> = None;
+        let mut pending = Vec::with_capacity(batch_size);
+
+        for (index, line) in reader.lines().enumerate() {
+            let line_number = index as u64 + 1;
+            let line =
+                line.map_err(|e| AuthError::InvalidInput(format!("Failed to read input: {}", e)))?;
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let record = match (format, &csv_header) {
+                (ImportFormat::Jsonl, _) => IdentityRecord::from_json_line(line),
+                (ImportFormat::Csv, None) => {
+                    let header = split_csv_line(line).map_err(|e| {
+                        AuthError::InvalidInput(format!("Invalid CSV header: {}", e))
+                    })?;
+                    csv_header = Some(header.into_iter().map(|c| c.trim().to_string()).collect());
+                    continue;
+                }
+                (ImportFormat::Csv, Some(header)) => IdentityRecord::from_csv_row(header, line),
+            };
+
+            report.total += 1;
+
+            let identifier = record.as_ref().ok().and_then(|r| r.identifier());
+            match record.and_then(IdentityRecord::into_new_identity) {
+                Ok(identity) => pending.push(PendingRow {
+                    line: line_number,
+                    identifier,
+                    identity,
+                }),
+                Err(message) => report.errors.push(ImportRowError {
+                    line: line_number,
+                    identifier,
+                    message,
+                }),
+            }
+
+            if pending.len() >= batch_size {
+                Self::flush(&mut pending, &mut report, kratos_client).await?;
+            }
+        }
+
+        Self::flush(&mut pending, &mut report, kratos_client).await?;
+
+        report.failed = report.errors.len() as u64;
+        info!(
+            total = report.total,
+            imported = report.imported,
+            failed = report.failed,
+            "Identity import finished"
+        );
+
+        Ok(report)
+    }
+
+    /// Sends one batch; if Kratos rejects it, retries the rows one by one so
+    /// the error can be attributed to the offending line. An unreachable
+    /// Kratos aborts the import instead of failing every remaining row.
+    async fn flush(
+        pending: &mut Vec<PendingRow>,
+        report: &mut ImportReport,
+        kratos_client: &KratosClient,
+    ) -> Result<(), AuthError> {
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let identities: Vec<NewIdentity> = pending.iter().map(|row| row.identity.clone()).collect();
+
+        match kratos_client
+            .admin_batch_create_identities(&identities)
+            .await
+        {
+            Ok(ids) => report.imported += ids.len() as u64,
+            Err(e @ (KratosError::Network(_) | KratosError::Upstream { .. })) => {
+                return Err(AuthError::from(e));
+            }
+            Err(e) => {
+                warn!(error = %e, rows = pending.len(), "Batch rejected, importing rows one by one");
+
+                for row in pending.iter() {
+                    match kratos_client
+                        .admin_create_identity(row.identity.clone())
+                        .await
+                    {
+                        Ok(_) => report.imported += 1,
+                        Err(e) => report.errors.push(ImportRowError {
+                            line: row.line,
+                            identifier: row.identifier.clone(),
+                            message: AuthError::from(e).to_string(),
+                        }),
+                    }
+                }
+            }
+        }
+
+        pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, HttpResponse, HttpServer, web};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const IDENTITY_ID: &str = "0b6f2a44-1c8e-4f0a-a5d2-6e3c9b7f1a20";
+
+    #[derive(Default)]
+    struct Calls {
+        batches: AtomicUsize,
+        singles: AtomicUsize,
+    }
+
+    fn taken(identity: &serde_json::Value) -> bool {
+        identity["traits"]["email"] == "taken@example.com"
+    }
+
+    fn conflict() -> HttpResponse {
+        HttpResponse::Conflict().json(serde_json::json!({
+            "error": { "code": 409, "message": "an identity with the same identifier already exists" }
+        }))
+    }
+
+    /// Kratos admin API rejecting any identity whose email is
+    /// `taken@example.com`, in batches as well as one by one.
+    async fn mock_kratos(calls: Arc<Calls>) -> KratosClient {
+        let server = HttpServer::new(move || {
+            let batch_calls = calls.clone();
+            let single_calls = calls.clone();
+            App::new()
+                .route(
+                    "/admin/identities",
+                    web::patch().to(move |body: web::Json<serde_json::Value>| {
+                        batch_calls.batches.fetch_add(1, Ordering::SeqCst);
+                        async move {
+                            let patches = body["identities"].as_array().cloned().unwrap_or_default();
+                            if patches.iter().any(|patch| taken(&patch["create"])) {
+                                return conflict();
+                            }
+                            let identities: Vec<serde_json::Value> = patches
+                                .iter()
+                                .map(|_| serde_json::json!({ "action": "create", "identity": IDENTITY_ID }))
+                                .collect();
+                            HttpResponse::Ok().json(serde_json::json!({ "identities": identities }))
+                        }
+                    }),
+                )
+                .route(
+                    "/admin/identities",
+                    web::post().to(move |body: web::Json<serde_json::Value>| {
+                        single_calls.singles.fetch_add(1, Ordering::SeqCst);
+                        async move {
+                            if taken(&body) {
+                                return conflict();
+                            }
+                            let mut identity = body.into_inner();
+                            identity["id"] = IDENTITY_ID.into();
+                            HttpResponse::Created().json(identity)
+                        }
+                    }),
+                )
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("Failed to bind mock Kratos");
+
+        let url = format!("http://{}", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+        KratosClient::new(url.clone(), url)
+    }
+
+    #[actix_web::test]
+    async fn rejected_batches_are_retried_row_by_row() {
+        let calls = Arc::new(Calls::default());
+        let kratos_client = mock_kratos(calls.clone()).await;
+        let input = [
+            r#"{"email":"ada@example.com","username":"ada"}"#,
+            r#"{"email":"taken@example.com","username":"taken"}"#,
+            "not json",
+            "",
+            r#"{"email":"grace@example.com","username":"grace"}"#,
+        ]
+        .join("\n");
+
+        let report = ImportIdentitiesUseCase::execute(
+            input.as_bytes(),
+            ImportFormat::Jsonl,
+            2,
+            &kratos_client,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.total, 4);
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.failed, 2);
+        // One rejected batch of two, then one accepted batch of one
+        assert_eq!(calls.batches.load(Ordering::SeqCst), 2);
+        assert_eq!(calls.singles.load(Ordering::SeqCst), 2);
+
+        let duplicate = &report.errors[0];
+        assert_eq!(duplicate.line, 2);
+        assert_eq!(duplicate.identifier.as_deref(), Some("taken@example.com"));
+        assert_eq!(
+            duplicate.message,
+            "An account with the same identifier already exists"
+        );
+
+        let unparsable = &report.errors[1];
+        assert_eq!(unparsable.line, 3);
+        assert_eq!(unparsable.identifier, None);
+        assert!(unparsable.message.starts_with("Invalid JSON"));
+    }
+
+    #[actix_web::test]
+    async fn csv_rows_are_numbered_after_the_header() {
+        let calls = Arc::new(Calls::default());
+        let kratos_client = mock_kratos(calls.clone()).await;
+        let input = "email,username,roles\nada@example.com,ada,admin\ngrace@example.com\n";
+
+        let report = ImportIdentitiesUseCase::execute(
+            input.as_bytes(),
+            ImportFormat::Csv,
+            10,
+            &kratos_client,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!((report.total, report.imported, report.failed), (2, 1, 1));
+        assert_eq!(report.errors[0].line, 3);
+        assert_eq!(report.errors[0].message, "Expected 3 columns, found 1");
+        assert_eq!(calls.singles.load(Ordering::SeqCst), 0);
+    }
+
+    #[actix_web::test]
+    async fn an_unreachable_kratos_aborts_the_import() {
+        let kratos_client = KratosClient::new(
+            "http://127.0.0.1:9".to_string(),
+            "http://127.0.0.1:9".to_string(),
+        );
+        let input = r#"{"email":"ada@example.com","username":"ada"}"#;
+
+        let result = ImportIdentitiesUseCase::execute(
+            input.as_bytes(),
+            ImportFormat::Jsonl,
+            10,
+            &kratos_client,
+        )
+        .await;
+
+        assert!(matches!(result, Err(AuthError::ServiceUnavailable(_))));
+    }
+}
@@ -0,0 +1,38 @@
+use crate::domain::admin::responses::IdentityConnection;
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use tracing::error;
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 500;
+
+pub struct ListIdentitiesUseCase;
+
+impl ListIdentitiesUseCase {
+    pub async fn execute(
+        page_size: Option<u32>,
+        page_token: Option<String>,
+        search: Option<String>,
+        kratos_client: &KratosClient,
+    ) -> Result<IdentityConnection, AuthError> {
+        let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+        if page_size == 0 || page_size > MAX_PAGE_SIZE {
+            return Err(AuthError::InvalidInput(format!(
+                "pageSize must be between 1 and {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+
+        let search = search.as_deref().map(str::trim).filter(|s| !s.is_empty());
+
+        let page = kratos_client
+            .admin_list_identities(page_size, page_token.as_deref(), search)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to list identities");
+                AuthError::from(e)
+            })?;
+
+        Ok(IdentityConnection::from(page))
+    }
+}
@@ -0,0 +1,8 @@
+pub mod create_identity;
+pub mod delete_identity;
+pub mod export_identities;
+pub mod get_identity;
+pub mod import_identities;
+pub mod list_identities;
+pub mod revoke_identity_sessions;
+pub mod update_identity;
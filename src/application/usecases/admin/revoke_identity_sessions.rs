@@ -0,0 +1,27 @@
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use tracing::{error, info};
+
+pub struct RevokeIdentitySessionsUseCase;
+
+impl RevokeIdentitySessionsUseCase {
+    pub async fn execute(
+        identity_id: &str,
+        kratos_client: &KratosClient,
+    ) -> Result<bool, AuthError> {
+        kratos_client
+            .admin_revoke_identity_sessions(identity_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, identity_id = identity_id, "Failed to revoke identity sessions");
+                AuthError::from(e)
+            })?;
+
+        info!(
+            identity_id = identity_id,
+            "All sessions of identity revoked by admin"
+        );
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::domain::admin::inputs::UpdateIdentityInput;
+use crate::domain::admin::responses::IdentityView;
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::kratos_client::{IdentityUpdate, KratosClient};
+use tracing::{error, info};
+use validator::Validate;
+
+#[derive(Validate)]
+struct UpdateIdentityValidation {
+    #[validate(email)]
+    email: Option<String>,
+    #[validate(length(min = 3, max = 20))]
+    username: Option<String>,
+}
+
+pub struct UpdateIdentityUseCase;
+
+impl UpdateIdentityUseCase {
+    pub async fn execute(
+        identity_id: &str,
+        input: UpdateIdentityInput,
+        kratos_client: &KratosClient,
+    ) -> Result<IdentityView, AuthError> {
+        let validation = UpdateIdentityValidation {
+            email: input.email.clone(),
+            username: input.username.clone(),
+        };
+        validation
+            .validate()
+            .map_err(|e| AuthError::InvalidInput(format!("Validation error: {}", e)))?;
+
+        let update = IdentityUpdate {
+            email: input.email,
+            username: input.username,
+            geo_location: input.geo_location,
+            state: input.state.map(|state| state.as_str().to_string()),
+            roles: input.roles,
+        };
+
+        let identity = kratos_client
+            .admin_update_identity(identity_id, update)
+            .await
+            .map_err(|e| {
+                error!(error = %e, identity_id = identity_id, "Failed to update identity");
+                AuthError::from(e)
+            })?;
+
+        info!(identity_id = %identity.id, state = ?identity.state, "Identity updated by admin");
+
+        Ok(IdentityView::from(identity))
+    }
+}
@@ -0,0 +1,49 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::ChangePasswordInput;
+use crate::domain::auth::responses::UserView;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+use validator::Validate;
+
+#[derive(Validate)]
+struct ChangePasswordValidation {
+    #[validate(length(min = 8))]
+    password: String,
+}
+
+pub struct ChangePasswordUseCase;
+
+impl ChangePasswordUseCase {
+    pub async fn execute(
+        input: ChangePasswordInput,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(UserView, Vec<String>), AuthError> {
+        Self::validate_input(&input)?;
+
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let (identity, cookies) = kratos_client
+            .handle_change_password(&input.password, credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Password change failed");
+                AuthError::from(e)
+            })?;
+
+        info!(identity_id = %identity.id, "Password changed");
+
+        Ok((UserView::from(identity), cookies))
+    }
+
+    fn validate_input(input: &ChangePasswordInput) -> Result<(), AuthError> {
+        let validation = ChangePasswordValidation {
+            password: input.password.clone(),
+        };
+
+        validation
+            .validate()
+            .map_err(|e| AuthError::InvalidInput(format!("Validation error: {}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use crate::domain::admin::inputs::{ADMIN_ROLE, IDENTITIES_OBJECT, MANAGE_RELATION};
+use crate::domain::auth::context::AuthContext;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::repositories::permission_repository::{PermissionRepository, RelationTuple};
+use tracing::{debug, error};
+
+pub struct CheckPermissionUseCase;
+
+impl CheckPermissionUseCase {
+    /// Whether the caller has `relation` on `object` (`namespace:id`).
+    /// Anonymous callers have no relations.
+    pub async fn execute(
+        relation: &str,
+        object: &str,
+        auth: Option<&AuthContext>,
+        permissions: &dyn PermissionRepository,
+    ) -> Result<bool, AuthError> {
+        let Some(auth) = auth else {
+            return Ok(false);
+        };
+
+        let tuple = RelationTuple::for_subject(object.trim(), relation.trim(), &auth.identity_id)
+            .map_err(AuthError::InvalidInput)?;
+
+        let allowed = permissions.check(&tuple).await.map_err(|e| {
+            error!(error = %e, "Permission check failed");
+            AuthError::ServiceUnavailable("permission service".to_string())
+        })?;
+
+        debug!(
+            identity_id = %auth.identity_id,
+            namespace = %tuple.namespace,
+            object = %tuple.object,
+            relation = %tuple.relation,
+            allowed,
+            "Permission checked"
+        );
+
+        Ok(allowed)
+    }
+
+    /// Identity administration: the admin role, or the `manage` relation on
+    /// `gateway:identities`.
+    pub async fn identity_admin(
+        auth: &AuthContext,
+        permissions: &dyn PermissionRepository,
+    ) -> Result<bool, AuthError> {
+        if auth.has_role(ADMIN_ROLE) {
+            return Ok(true);
+        }
+
+        Self::execute(MANAGE_RELATION, IDENTITIES_OBJECT, Some(auth), permissions).await
+    }
+}
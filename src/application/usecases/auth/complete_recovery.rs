@@ -0,0 +1,58 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::{ClientType, CompleteRecoveryInput};
+use crate::domain::auth::responses::RecoveryResponse;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct CompleteRecoveryUseCase;
+
+impl CompleteRecoveryUseCase {
+    pub async fn execute(
+        input: CompleteRecoveryInput,
+        client_type: ClientType,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(RecoveryResponse, Vec<String>), AuthError> {
+        Self::validate_input(&input)?;
+
+        let result = kratos_client
+            .handle_complete_recovery(
+                &input.flow_id,
+                input.code.trim(),
+                client_type.into(),
+                credentials,
+            )
+            .await
+            .map_err(|e| {
+                error!(error = %e, flow_id = %input.flow_id, "Recovery failed");
+                AuthError::from(e)
+            })?;
+
+        info!(flow_id = %input.flow_id, "Recovery completed");
+
+        Ok((
+            RecoveryResponse {
+                recovered: true,
+                settings_flow_id: result.settings_flow_id,
+                session_token: result.session_token,
+            },
+            result.cookies,
+        ))
+    }
+
+    fn validate_input(input: &CompleteRecoveryInput) -> Result<(), AuthError> {
+        if input.flow_id.is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Flow ID cannot be empty".to_string(),
+            ));
+        }
+
+        if input.code.trim().is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Recovery code cannot be empty".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
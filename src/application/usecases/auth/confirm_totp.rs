@@ -0,0 +1,47 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::ConfirmTotpInput;
+use crate::domain::auth::responses::UserView;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct ConfirmTotpUseCase;
+
+impl ConfirmTotpUseCase {
+    pub async fn execute(
+        input: ConfirmTotpInput,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(UserView, Vec<String>), AuthError> {
+        Self::validate_input(&input)?;
+
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let (identity, cookies) = kratos_client
+            .handle_totp_enrollment_confirm(&input.flow_id, input.code.trim(), credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, flow_id = %input.flow_id, "TOTP enrollment failed");
+                AuthError::from(e)
+            })?;
+
+        info!(identity_id = %identity.id, "TOTP enabled");
+
+        Ok((UserView::from(identity), cookies))
+    }
+
+    fn validate_input(input: &ConfirmTotpInput) -> Result<(), AuthError> {
+        if input.flow_id.is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Flow ID cannot be empty".to_string(),
+            ));
+        }
+
+        if input.code.trim().is_empty() {
+            return Err(AuthError::InvalidInput(
+                "TOTP code cannot be empty".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
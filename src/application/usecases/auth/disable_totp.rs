@@ -0,0 +1,27 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::UserView;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct DisableTotpUseCase;
+
+impl DisableTotpUseCase {
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(UserView, Vec<String>), AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let (identity, cookies) = kratos_client
+            .handle_totp_disable(credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to disable TOTP");
+                AuthError::from(e)
+            })?;
+
+        info!(identity_id = %identity.id, "TOTP disabled");
+
+        Ok((UserView::from(identity), cookies))
+    }
+}
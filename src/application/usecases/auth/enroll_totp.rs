@@ -0,0 +1,41 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::TotpEnrollmentResponse;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct EnrollTotpUseCase;
+
+impl EnrollTotpUseCase {
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(TotpEnrollmentResponse, Vec<String>), AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let enrollment = kratos_client
+            .handle_totp_enrollment_start(credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to start TOTP enrollment");
+                AuthError::from(e)
+            })?;
+
+        let already_enabled = enrollment.qr_code.is_none() && enrollment.secret.is_none();
+
+        info!(
+            flow_id = %enrollment.flow_id,
+            already_enabled = already_enabled,
+            "TOTP enrollment started"
+        );
+
+        Ok((
+            TotpEnrollmentResponse {
+                flow_id: enrollment.flow_id,
+                qr_code: enrollment.qr_code,
+                secret: enrollment.secret,
+                already_enabled,
+            },
+            enrollment.cookies,
+        ))
+    }
+}
@@ -0,0 +1,64 @@
+use crate::application::usecases::auth::issue_token_pair::IssueTokenPairUseCase;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::FinishWebauthnLoginInput;
+use crate::domain::auth::responses::AuthResponse;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct FinishWebauthnLoginUseCase;
+
+impl FinishWebauthnLoginUseCase {
+    pub async fn execute(
+        input: FinishWebauthnLoginInput,
+        kratos_client: &KratosClient,
+        jwt_signer: &JwtSigner,
+        refresh_tokens: &dyn RefreshTokenRepository,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(AuthResponse, Vec<String>), AuthError> {
+        if input.assertion.trim().is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Assertion cannot be empty".to_string(),
+            ));
+        }
+
+        let login_result = kratos_client
+            .handle_webauthn_login_finish(
+                input.method,
+                &input.flow_id,
+                input.identifier.as_deref().map(str::trim),
+                &input.assertion,
+                credentials,
+            )
+            .await
+            .map_err(|e| {
+                error!(error = %e, flow_id = %input.flow_id, "WebAuthn login failed");
+                AuthError::from(e)
+            })?;
+
+        info!(
+            identity_id = %login_result.session.identity.id,
+            requires_second_factor = login_result.second_factor.is_some(),
+            "WebAuthn login successful"
+        );
+
+        // Only fully authenticated sessions get an access token
+        let access_token = match login_result.second_factor {
+            None => Some(
+                IssueTokenPairUseCase::execute(&login_result.session, jwt_signer, refresh_tokens)
+                    .await?,
+            ),
+            Some(_) => None,
+        };
+
+        let auth_response = AuthResponse::from_kratos_identity(
+            login_result.session.identity,
+            login_result.session_token,
+        )
+        .with_second_factor(login_result.second_factor)
+        .with_access_token(access_token);
+
+        Ok((auth_response, login_result.cookies))
+    }
+}
@@ -0,0 +1,53 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::{FinishWebauthnRegistrationInput, WebauthnMethod};
+use crate::domain::auth::responses::UserView;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct FinishWebauthnRegistrationUseCase;
+
+impl FinishWebauthnRegistrationUseCase {
+    pub async fn execute(
+        input: FinishWebauthnRegistrationInput,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(UserView, Vec<String>), AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        // Kratos names passkeys itself
+        let display_name = input
+            .display_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        if input.method == WebauthnMethod::Webauthn && display_name.is_none() {
+            return Err(AuthError::InvalidInput(
+                "Display name cannot be empty".to_string(),
+            ));
+        }
+
+        if input.credential.trim().is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Credential cannot be empty".to_string(),
+            ));
+        }
+
+        let (identity, cookies) = kratos_client
+            .handle_webauthn_registration_finish(
+                input.method,
+                &input.flow_id,
+                display_name,
+                &input.credential,
+                credentials,
+            )
+            .await
+            .map_err(|e| {
+                error!(error = %e, flow_id = %input.flow_id, "Failed to register WebAuthn credential");
+                AuthError::from(e)
+            })?;
+
+        info!(identity_id = %identity.id, "WebAuthn credential registered");
+
+        Ok((UserView::from(identity), cookies))
+    }
+}
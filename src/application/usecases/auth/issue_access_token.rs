@@ -0,0 +1,98 @@
+use crate::application::usecases::auth::issue_token_pair::IssueTokenPairUseCase;
+use crate::application::usecases::auth::session::CurrentSessionUseCase;
+use crate::domain::auth::context::{AuthContext, AuthSource};
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::AccessTokenView;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use tracing::info;
+
+pub struct IssueAccessTokenUseCase;
+
+impl IssueAccessTokenUseCase {
+    /// Mints a fresh access/refresh pair for the caller's current Kratos
+    /// session. Only a session cookie or token will do: an access token would
+    /// otherwise renew itself forever, bypassing refresh token rotation.
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        jwt_signer: &JwtSigner,
+        refresh_tokens: &dyn RefreshTokenRepository,
+        auth: Option<&AuthContext>,
+    ) -> Result<AccessTokenView, AuthError> {
+        if auth.is_some_and(|auth| auth.source == AuthSource::AccessToken) {
+            return Err(AuthError::Forbidden(
+                "access tokens are renewed with refreshToken".to_string(),
+            ));
+        }
+
+        let session = CurrentSessionUseCase::kratos_session(kratos_client, auth)
+            .await?
+            .ok_or(AuthError::NotAuthenticated)?;
+
+        let access_token =
+            IssueTokenPairUseCase::execute(&session, jwt_signer, refresh_tokens).await?;
+
+        info!(
+            identity_id = %session.identity.id,
+            session_id = %session.id,
+            "Access token issued"
+        );
+
+        Ok(access_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::adapters::jwt::jwt_signer::{JwtAlgorithm, JwtConfig, JwtKeyConfig};
+    use crate::infrastructure::adapters::kratos::kratos_client::IdentityTraits;
+    use crate::infrastructure::adapters::memory::InMemoryRefreshTokenRepository;
+
+    #[tokio::test]
+    async fn access_tokens_cannot_mint_new_tokens() {
+        // Unreachable: the caller is turned away before Kratos is asked
+        let kratos_client = KratosClient::new(
+            "http://127.0.0.1:9".to_string(),
+            "http://127.0.0.1:9".to_string(),
+        );
+        let jwt_signer = JwtSigner::new(JwtConfig {
+            algorithm: JwtAlgorithm::Hs256,
+            keys: vec![JwtKeyConfig {
+                kid: None,
+                material: "a-test-secret-that-is-long-enough!".to_string(),
+            }],
+            rotated_at: None,
+            issuer: "rust-gateway".to_string(),
+            audience: None,
+            ttl: chrono::Duration::minutes(15),
+        })
+        .unwrap();
+        let refresh_tokens = InMemoryRefreshTokenRepository::default();
+        let auth = AuthContext {
+            identity_id: "identity-1".to_string(),
+            traits: IdentityTraits {
+                email: "ada@example.com".to_string(),
+                username: "ada".to_string(),
+                geo_location: None,
+            },
+            email_verified: true,
+            session_id: "session-1".to_string(),
+            aal: "aal1".to_string(),
+            roles: Vec::new(),
+            source: AuthSource::AccessToken,
+            session: None,
+        };
+
+        let result = IssueAccessTokenUseCase::execute(
+            &kratos_client,
+            &jwt_signer,
+            &refresh_tokens,
+            Some(&auth),
+        )
+        .await;
+
+        assert!(matches!(result, Err(AuthError::Forbidden(_))));
+    }
+}
@@ -0,0 +1,29 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::AccessTokenView;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosSession;
+use tracing::error;
+
+pub struct IssueTokenPairUseCase;
+
+impl IssueTokenPairUseCase {
+    /// Signs an access token and starts a new refresh token family for the session.
+    pub async fn execute(
+        session: &KratosSession,
+        jwt_signer: &JwtSigner,
+        refresh_tokens: &dyn RefreshTokenRepository,
+    ) -> Result<AccessTokenView, AuthError> {
+        let issued = jwt_signer.issue(session)?;
+
+        let (_, refresh_token) = refresh_tokens
+            .create_family(&session.identity.id, &session.id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, session_id = %session.id, "Failed to store refresh token");
+                AuthError::ServiceUnavailable("refresh token store".to_string())
+            })?;
+
+        Ok(AccessTokenView::from(issued).with_refresh_token(refresh_token))
+    }
+}
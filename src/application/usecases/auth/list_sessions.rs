@@ -0,0 +1,42 @@
+use crate::application::usecases::auth::session::CurrentSessionUseCase;
+use crate::domain::auth::context::AuthContext;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::DeviceSessionView;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::error;
+
+pub struct ListSessionsUseCase;
+
+impl ListSessionsUseCase {
+    /// The current session first, followed by every other active session.
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        auth: Option<&AuthContext>,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<Vec<DeviceSessionView>, AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let current = CurrentSessionUseCase::kratos_session(kratos_client, auth)
+            .await?
+            .ok_or(AuthError::NotAuthenticated)?;
+
+        let others = kratos_client
+            .list_sessions(credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to list sessions");
+                AuthError::from(e)
+            })?;
+
+        let current_id = current.id.clone();
+        let mut sessions = vec![DeviceSessionView::from_kratos_session(current, true)];
+        sessions.extend(
+            others
+                .into_iter()
+                .filter(|session| session.id != current_id)
+                .map(|session| DeviceSessionView::from_kratos_session(session, false)),
+        );
+
+        Ok(sessions)
+    }
+}
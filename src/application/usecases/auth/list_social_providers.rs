@@ -0,0 +1,26 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::SocialProvider;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use tracing::error;
+
+pub struct ListSocialProvidersUseCase;
+
+impl ListSocialProvidersUseCase {
+    pub async fn execute(kratos_client: &KratosClient) -> Result<Vec<SocialProvider>, AuthError> {
+        let providers = kratos_client
+            .handle_list_oidc_providers()
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to list OIDC providers");
+                AuthError::from(e)
+            })?;
+
+        Ok(providers
+            .into_iter()
+            .map(|provider| SocialProvider {
+                id: provider.id,
+                label: provider.label,
+            })
+            .collect())
+    }
+}
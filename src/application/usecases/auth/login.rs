@@ -1,5 +1,10 @@
-use crate::domain::auth::inputs::LoginInput;
+use crate::application::usecases::auth::issue_token_pair::IssueTokenPairUseCase;
+use crate::domain::auth::context::AuthContext;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::{ClientType, LoginInput};
 use crate::domain::auth::responses::AuthResponse;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::jwt::JwtSigner;
 use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
 use tracing::{debug, error, info};
 
@@ -8,46 +13,56 @@ pub struct LoginUseCase;
 impl LoginUseCase {
     pub async fn execute(
         input: LoginInput,
+        client_type: ClientType,
         kratos_client: &KratosClient,
-        cookie: Option<&str>,
-    ) -> Result<(AuthResponse, Vec<String>), String> {
+        jwt_signer: &JwtSigner,
+        refresh_tokens: &dyn RefreshTokenRepository,
+        auth: Option<&AuthContext>,
+    ) -> Result<(AuthResponse, Vec<String>), AuthError> {
         Self::validate_input(&input)?;
 
         let identifier = input
             .email
             .as_ref()
             .or(input.username.as_ref())
-            .ok_or("Email or username required")?;
+            .ok_or_else(|| AuthError::InvalidInput("Email or username required".to_string()))?;
 
         info!(
             identifier = identifier,
-            cookie_present = cookie.is_some(),
+            client_type = ?client_type,
+            authenticated = auth.is_some(),
             "Starting login process"
         );
 
         // ✅ Проверяем наличие активной сессии и ВОЗВРАЩАЕМ ОШИБКУ
-        if let Some(cookie) = cookie {
-            if let Ok(Some(_session)) = kratos_client.get_session(cookie).await {
-                error!("Login attempt with active session for {}", identifier);
-                return Err(
-                    "Already logged in. Please logout first before logging in again.".to_string(),
-                );
-            }
+        if auth.is_some() {
+            error!("Login attempt with active session for {}", identifier);
+            return Err(AuthError::AlreadyAuthenticated);
         }
 
         // ✅ Если сессии нет — выполняем логин
-        let (session, cookies) = match kratos_client
-            .handle_login(identifier, &input.password, None) // ⚠️ Передаём None, чтобы не путать cookies
+        let login_result = match kratos_client
+            .handle_login(identifier, &input.password, client_type.into(), None) // ⚠️ Передаём None, чтобы не путать cookies
             .await
         {
             Ok(result) => result,
             Err(e) => {
-                let error_msg = e.to_string();
-                error!(error = %error_msg, "Login failed");
-                return Err(format!("Login failed: {}", error_msg));
+                error!(error = %e, "Login failed");
+                return Err(e.into());
             }
         };
 
+        let cookies = login_result.cookies;
+
+        if let Some(challenge) = &login_result.second_factor {
+            info!(
+                flow_id = %challenge.flow_id,
+                methods = ?challenge.methods,
+                "Second factor required for identifier={}",
+                identifier
+            );
+        }
+
         if cookies.is_empty() {
             debug!("No cookies returned from Kratos");
         } else {
@@ -60,31 +75,51 @@ impl LoginUseCase {
 
         info!("Login successful for identifier={}", identifier);
 
+        // Only fully authenticated sessions get an access token
+        let access_token = match login_result.second_factor {
+            None => Some(
+                IssueTokenPairUseCase::execute(&login_result.session, jwt_signer, refresh_tokens)
+                    .await?,
+            ),
+            Some(_) => None,
+        };
+
         Ok((
-            AuthResponse::from_kratos_identity(session.identity, String::new()),
+            AuthResponse::from_kratos_identity(
+                login_result.session.identity,
+                login_result.session_token,
+            )
+            .with_second_factor(login_result.second_factor)
+            .with_access_token(access_token),
             cookies,
         ))
     }
 
-    fn validate_input(input: &LoginInput) -> Result<(), String> {
+    fn validate_input(input: &LoginInput) -> Result<(), AuthError> {
         if input.email.is_none() && input.username.is_none() {
-            return Err("Email or username required".to_string());
+            return Err(AuthError::InvalidInput(
+                "Email or username required".to_string(),
+            ));
         }
 
         if input.password.is_empty() {
-            return Err("Password cannot be empty".to_string());
+            return Err(AuthError::InvalidInput(
+                "Password cannot be empty".to_string(),
+            ));
         }
 
-        if let Some(ref email) = input.email {
-            if email.is_empty() {
-                return Err("Email cannot be empty".to_string());
-            }
+        if let Some(ref email) = input.email
+            && email.is_empty()
+        {
+            return Err(AuthError::InvalidInput("Email cannot be empty".to_string()));
         }
 
-        if let Some(ref username) = input.username {
-            if username.is_empty() {
-                return Err("Username cannot be empty".to_string());
-            }
+        if let Some(ref username) = input.username
+            && username.is_empty()
+        {
+            return Err(AuthError::InvalidInput(
+                "Username cannot be empty".to_string(),
+            ));
         }
 
         Ok(())
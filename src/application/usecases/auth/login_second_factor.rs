@@ -0,0 +1,64 @@
+use crate::application::usecases::auth::issue_token_pair::IssueTokenPairUseCase;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::LoginSecondFactorInput;
+use crate::domain::auth::responses::AuthResponse;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::{
+    KratosClient, SecondFactor, SessionCredentials,
+};
+use tracing::{error, info};
+
+pub struct LoginSecondFactorUseCase;
+
+impl LoginSecondFactorUseCase {
+    pub async fn execute(
+        input: LoginSecondFactorInput,
+        kratos_client: &KratosClient,
+        jwt_signer: &JwtSigner,
+        refresh_tokens: &dyn RefreshTokenRepository,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(AuthResponse, Vec<String>), AuthError> {
+        // The AAL2 flow is bound to the AAL1 session created by `login`
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let factor = match (&input.totp_code, &input.lookup_secret) {
+            (Some(code), None) if !code.trim().is_empty() => SecondFactor::Totp(code.trim()),
+            (None, Some(secret)) if !secret.trim().is_empty() => {
+                SecondFactor::LookupSecret(secret.trim())
+            }
+            _ => {
+                return Err(AuthError::InvalidInput(
+                    "Provide exactly one of totpCode or lookupSecret".to_string(),
+                ));
+            }
+        };
+
+        let login_result = kratos_client
+            .handle_login_second_factor(&input.flow_id, factor, credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, flow_id = %input.flow_id, "Second factor login failed");
+                AuthError::from(e)
+            })?;
+
+        info!(
+            identity_id = %login_result.session.identity.id,
+            aal = ?login_result.session.authenticator_assurance_level,
+            "Second factor login successful"
+        );
+
+        let access_token =
+            IssueTokenPairUseCase::execute(&login_result.session, jwt_signer, refresh_tokens)
+                .await?;
+
+        Ok((
+            AuthResponse::from_kratos_identity(
+                login_result.session.identity,
+                login_result.session_token,
+            )
+            .with_access_token(Some(access_token)),
+            login_result.cookies,
+        ))
+    }
+}
@@ -0,0 +1,53 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::LogoutResponse;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::kratos::KratosError;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct LogoutUseCase;
+
+impl LogoutUseCase {
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        refresh_tokens: &dyn RefreshTokenRepository,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(LogoutResponse, Vec<String>), AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        match kratos_client.get_session(credentials).await {
+            Ok(Some(session)) => {
+                // Refreshing re-checks the Kratos session, so a failure here
+                // only leaves behind tokens that can no longer be used
+                if let Err(e) = refresh_tokens.revoke_session(&session.id).await {
+                    error!(error = %e, session_id = %session.id, "Failed to revoke refresh tokens");
+                }
+            }
+            // A half-finished 2FA login still has a session worth revoking
+            // and no refresh tokens yet
+            Err(KratosError::Aal2Required) => {}
+            Ok(None) => return Err(AuthError::NotAuthenticated),
+            Err(e) => {
+                error!(error = %e, "Failed to check session before logout");
+                return Err(e.into());
+            }
+        }
+
+        let result = match credentials {
+            SessionCredentials::Cookie(cookie) => kratos_client.handle_logout(cookie).await,
+            SessionCredentials::Token(token) => kratos_client
+                .handle_logout_api(token)
+                .await
+                .map(|_| Vec::new()),
+        };
+
+        let cookies = result.map_err(|e| {
+            error!(error = %e, "Logout failed");
+            AuthError::from(e)
+        })?;
+
+        info!(cookies_count = cookies.len(), "Logout successful");
+
+        Ok((LogoutResponse { logged_out: true }, cookies))
+    }
+}
@@ -1,2 +1,29 @@
+pub mod change_password;
+pub mod check_permission;
+pub mod complete_recovery;
+pub mod confirm_totp;
+pub mod disable_totp;
+pub mod enroll_totp;
+pub mod finish_webauthn_login;
+pub mod finish_webauthn_registration;
+pub mod issue_access_token;
+pub mod issue_token_pair;
+pub mod list_sessions;
+pub mod list_social_providers;
 pub mod login;
+pub mod login_second_factor;
+pub mod logout;
+pub mod refresh_token;
+pub mod regenerate_lookup_secrets;
 pub mod register;
+pub mod request_recovery;
+pub mod resolve_caller;
+pub mod revoke_other_sessions;
+pub mod revoke_session;
+pub mod send_verification;
+pub mod session;
+pub mod start_social_login;
+pub mod start_webauthn_login;
+pub mod start_webauthn_registration;
+pub mod update_profile;
+pub mod verify_email;
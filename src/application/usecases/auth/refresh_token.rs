@@ -0,0 +1,288 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::AccessTokenView;
+use crate::domain::repositories::refresh_token_repository::{
+    RefreshTokenFamily, RefreshTokenRepository, RefreshTokenUse,
+};
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use tracing::{error, info, warn};
+
+pub struct RefreshTokenUseCase;
+
+impl RefreshTokenUseCase {
+    /// Trades a refresh token for a new access/refresh pair. Every token is
+    /// single use: replaying one revokes its whole family.
+    pub async fn execute(
+        refresh_token: &str,
+        kratos_client: &KratosClient,
+        jwt_signer: &JwtSigner,
+        refresh_tokens: &dyn RefreshTokenRepository,
+    ) -> Result<AccessTokenView, AuthError> {
+        let refresh_token = refresh_token.trim();
+        if refresh_token.is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Refresh token cannot be empty".to_string(),
+            ));
+        }
+
+        // Peek first so a Kratos outage leaves the token unspent and the
+        // client's retry is not mistaken for a replay
+        let family = Self::family(
+            refresh_tokens
+                .peek(refresh_token)
+                .await
+                .map_err(store_error)?,
+            refresh_tokens,
+        )
+        .await?;
+
+        // The Kratos session stays the source of truth: once it is gone the
+        // family cannot be refreshed any more
+        let session = kratos_client
+            .admin_get_session(&family.session_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, session_id = %family.session_id, "Failed to look up session");
+                AuthError::from(e)
+            })?
+            .filter(|session| session.active && session.identity.id == family.identity_id);
+
+        let Some(session) = session else {
+            info!(
+                session_id = %family.session_id,
+                "Session ended, revoking refresh token family"
+            );
+            refresh_tokens
+                .revoke_family(&family.family_id)
+                .await
+                .map_err(store_error)?;
+            return Err(AuthError::InvalidRefreshToken);
+        };
+
+        // A concurrent request may have spent the token since the peek
+        let family = Self::family(
+            refresh_tokens
+                .consume(refresh_token)
+                .await
+                .map_err(store_error)?,
+            refresh_tokens,
+        )
+        .await?;
+
+        let issued = jwt_signer.issue(&session)?;
+        let next_refresh_token = refresh_tokens.issue(&family).await.map_err(store_error)?;
+
+        info!(
+            identity_id = %session.identity.id,
+            session_id = %session.id,
+            "Refresh token rotated"
+        );
+
+        Ok(AccessTokenView::from(issued).with_refresh_token(next_refresh_token))
+    }
+
+    /// The token's family, revoking it when the token has been replayed.
+    async fn family(
+        token_use: RefreshTokenUse,
+        refresh_tokens: &dyn RefreshTokenRepository,
+    ) -> Result<RefreshTokenFamily, AuthError> {
+        match token_use {
+            RefreshTokenUse::Valid(family) => Ok(family),
+            RefreshTokenUse::Reused(family) => {
+                warn!(
+                    family_id = %family.family_id,
+                    identity_id = %family.identity_id,
+                    "Refresh token reuse detected, revoking token family"
+                );
+                refresh_tokens
+                    .revoke_family(&family.family_id)
+                    .await
+                    .map_err(store_error)?;
+                Err(AuthError::RefreshTokenReused)
+            }
+            RefreshTokenUse::Invalid => Err(AuthError::InvalidRefreshToken),
+        }
+    }
+}
+
+fn store_error(e: String) -> AuthError {
+    error!(error = %e, "Refresh token store failed");
+    AuthError::ServiceUnavailable("refresh token store".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::adapters::jwt::jwt_signer::{JwtAlgorithm, JwtConfig, JwtKeyConfig};
+    use crate::infrastructure::adapters::memory::InMemoryRefreshTokenRepository;
+    use actix_web::{App, HttpResponse, HttpServer, web};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    const SESSION_ID: &str = "7d5b3d0e-5a1f-4c53-9d61-2f1d0a7c9e11";
+    const IDENTITY_ID: &str = "0b6f2a44-1c8e-4f0a-a5d2-6e3c9b7f1a20";
+
+    const SESSION_ACTIVE: u8 = 0;
+    const SESSION_GONE: u8 = 1;
+    const KRATOS_DOWN: u8 = 2;
+
+    /// Kratos admin API answering `GET /admin/sessions/{id}` per `state`.
+    async fn mock_kratos(state: Arc<AtomicU8>) -> KratosClient {
+        let server = HttpServer::new(move || {
+            let state = state.clone();
+            App::new().route(
+                "/admin/sessions/{id}",
+                web::get().to(move || {
+                    let state = state.load(Ordering::SeqCst);
+                    async move {
+                        match state {
+                            SESSION_ACTIVE => HttpResponse::Ok().json(serde_json::json!({
+                                "id": SESSION_ID,
+                                "active": true,
+                                "identity": {
+                                    "id": IDENTITY_ID,
+                                    "traits": { "email": "ada@example.com", "username": "ada" }
+                                }
+                            })),
+                            SESSION_GONE => HttpResponse::NotFound().json(serde_json::json!({
+                                "error": { "code": 404, "message": "Unable to locate the resource" }
+                            })),
+                            _ => HttpResponse::ServiceUnavailable().finish(),
+                        }
+                    }
+                }),
+            )
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("Failed to bind mock Kratos");
+
+        let url = format!("http://{}", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+        KratosClient::new(url.clone(), url)
+    }
+
+    fn jwt_signer() -> JwtSigner {
+        JwtSigner::new(JwtConfig {
+            algorithm: JwtAlgorithm::Hs256,
+            keys: vec![JwtKeyConfig {
+                kid: None,
+                material: "a-test-secret-that-is-long-enough!".to_string(),
+            }],
+            rotated_at: None,
+            issuer: "rust-gateway".to_string(),
+            audience: None,
+            ttl: chrono::Duration::minutes(15),
+        })
+        .unwrap()
+    }
+
+    struct Fixture {
+        state: Arc<AtomicU8>,
+        kratos_client: KratosClient,
+        jwt_signer: JwtSigner,
+        refresh_tokens: InMemoryRefreshTokenRepository,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let state = Arc::new(AtomicU8::new(SESSION_ACTIVE));
+            Self {
+                kratos_client: mock_kratos(state.clone()).await,
+                state,
+                jwt_signer: jwt_signer(),
+                refresh_tokens: InMemoryRefreshTokenRepository::default(),
+            }
+        }
+
+        async fn login(&self) -> String {
+            let (_, token) = self
+                .refresh_tokens
+                .create_family(IDENTITY_ID, SESSION_ID)
+                .await
+                .unwrap();
+            token
+        }
+
+        async fn refresh(&self, token: &str) -> Result<String, &'static str> {
+            RefreshTokenUseCase::execute(
+                token,
+                &self.kratos_client,
+                &self.jwt_signer,
+                &self.refresh_tokens,
+            )
+            .await
+            .map(|view| view.refresh_token.expect("rotated refresh token"))
+            .map_err(|e| e.code())
+        }
+    }
+
+    #[actix_web::test]
+    async fn rotates_the_token_and_signs_for_the_session() {
+        let fixture = Fixture::new().await;
+        let first = fixture.login().await;
+
+        let view = RefreshTokenUseCase::execute(
+            &first,
+            &fixture.kratos_client,
+            &fixture.jwt_signer,
+            &fixture.refresh_tokens,
+        )
+        .await
+        .unwrap();
+        let claims = fixture.jwt_signer.verify(&view.token).unwrap();
+        assert_eq!(claims.sub, IDENTITY_ID);
+        assert_eq!(claims.sid, SESSION_ID);
+
+        let second = view.refresh_token.unwrap();
+        assert_ne!(second, first);
+        let third = fixture.refresh(&second).await.unwrap();
+        assert_ne!(third, second);
+    }
+
+    #[actix_web::test]
+    async fn replaying_a_rotated_token_revokes_the_family() {
+        let fixture = Fixture::new().await;
+        let first = fixture.login().await;
+        let second = fixture.refresh(&first).await.unwrap();
+
+        assert_eq!(fixture.refresh(&first).await, Err("REFRESH_TOKEN_REUSED"));
+        // The legitimate holder's newer token died with the family
+        assert_eq!(fixture.refresh(&second).await, Err("INVALID_REFRESH_TOKEN"));
+    }
+
+    #[actix_web::test]
+    async fn an_ended_session_revokes_the_family() {
+        let fixture = Fixture::new().await;
+        let token = fixture.login().await;
+
+        fixture.state.store(SESSION_GONE, Ordering::SeqCst);
+        assert_eq!(fixture.refresh(&token).await, Err("INVALID_REFRESH_TOKEN"));
+
+        fixture.state.store(SESSION_ACTIVE, Ordering::SeqCst);
+        assert_eq!(fixture.refresh(&token).await, Err("INVALID_REFRESH_TOKEN"));
+    }
+
+    #[actix_web::test]
+    async fn a_kratos_outage_leaves_the_token_for_a_retry() {
+        let fixture = Fixture::new().await;
+        let token = fixture.login().await;
+
+        fixture.state.store(KRATOS_DOWN, Ordering::SeqCst);
+        assert_eq!(fixture.refresh(&token).await, Err("SERVICE_UNAVAILABLE"));
+
+        fixture.state.store(SESSION_ACTIVE, Ordering::SeqCst);
+        assert!(fixture.refresh(&token).await.is_ok());
+    }
+
+    #[actix_web::test]
+    async fn rejects_unknown_and_empty_tokens() {
+        let fixture = Fixture::new().await;
+
+        assert_eq!(
+            fixture.refresh("made-up").await,
+            Err("INVALID_REFRESH_TOKEN")
+        );
+        assert_eq!(fixture.refresh("  ").await, Err("BAD_USER_INPUT"));
+    }
+}
@@ -0,0 +1,27 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::LookupSecretsResponse;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct RegenerateLookupSecretsUseCase;
+
+impl RegenerateLookupSecretsUseCase {
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(LookupSecretsResponse, Vec<String>), AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let (codes, cookies) = kratos_client
+            .handle_regenerate_lookup_secrets(credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to regenerate lookup secrets");
+                AuthError::from(e)
+            })?;
+
+        info!(codes_count = codes.len(), "Lookup secrets regenerated");
+
+        Ok((LookupSecretsResponse { codes }, cookies))
+    }
+}
@@ -1,6 +1,7 @@
-use crate::domain::auth::inputs::RegisterInput;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::{ClientType, RegisterInput};
 use crate::domain::auth::responses::AuthResponse;
-use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
 use validator::Validate;
 
 #[derive(Validate)]
@@ -18,23 +19,33 @@ pub struct RegisterUseCase;
 impl RegisterUseCase {
     pub async fn execute(
         input: RegisterInput,
+        client_type: ClientType,
         kratos_client: &KratosClient,
-        cookie: Option<&str>,
-    ) -> Result<(AuthResponse, Vec<String>), String> {
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(AuthResponse, Vec<String>), AuthError> {
         Self::validate_input(&input)?;
 
-        let (session, cookies) = kratos_client
-            .handle_signup(&input.email, &input.username, &input.password, cookie)
-            .await
-            .map_err(|e| format!("Failed to register: {}", e))?;
+        let login_result = kratos_client
+            .handle_signup(
+                &input.email,
+                &input.username,
+                &input.password,
+                client_type.into(),
+                credentials,
+            )
+            .await?;
 
         Ok((
-            AuthResponse::from_kratos_identity(session.identity, String::new()),
-            cookies,
+            AuthResponse::from_kratos_identity(
+                login_result.session.identity,
+                login_result.session_token,
+            )
+            .with_second_factor(login_result.second_factor),
+            login_result.cookies,
         ))
     }
 
-    fn validate_input(input: &RegisterInput) -> Result<(), String> {
+    fn validate_input(input: &RegisterInput) -> Result<(), AuthError> {
         let validation = RegisterValidation {
             email: input.email.clone(),
             username: input.username.clone(),
@@ -43,7 +54,7 @@ impl RegisterUseCase {
 
         validation
             .validate()
-            .map_err(|e| format!("Validation error: {}", e))?;
+            .map_err(|e| AuthError::InvalidInput(format!("Validation error: {}", e)))?;
         Ok(())
     }
 }
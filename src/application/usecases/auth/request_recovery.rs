@@ -0,0 +1,68 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::{ClientType, RequestRecoveryInput};
+use crate::domain::auth::responses::RecoveryRequestResponse;
+use crate::infrastructure::adapters::kratos::KratosError;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{info, warn};
+use validator::Validate;
+
+const RECOVERY_MESSAGE: &str =
+    "If an account exists for this address, a recovery code has been sent to it.";
+
+#[derive(Validate)]
+struct RequestRecoveryValidation {
+    #[validate(email)]
+    email: String,
+}
+
+pub struct RequestRecoveryUseCase;
+
+impl RequestRecoveryUseCase {
+    pub async fn execute(
+        input: RequestRecoveryInput,
+        client_type: ClientType,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(RecoveryRequestResponse, Vec<String>), AuthError> {
+        Self::validate_input(&input)?;
+
+        match kratos_client
+            .handle_request_recovery(&input.email, client_type.into(), credentials)
+            .await
+        {
+            Ok((flow_id, cookies)) => {
+                info!(flow_id = %flow_id, "Recovery flow started");
+                Ok((
+                    RecoveryRequestResponse {
+                        flow_id: Some(flow_id),
+                        message: RECOVERY_MESSAGE.to_string(),
+                    },
+                    cookies,
+                ))
+            }
+            // Never let Kratos form messages leak whether the address is known
+            Err(KratosError::Validation(messages)) => {
+                warn!(messages = ?messages, "Recovery request rejected by Kratos");
+                Ok((
+                    RecoveryRequestResponse {
+                        flow_id: None,
+                        message: RECOVERY_MESSAGE.to_string(),
+                    },
+                    Vec::new(),
+                ))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn validate_input(input: &RequestRecoveryInput) -> Result<(), AuthError> {
+        let validation = RequestRecoveryValidation {
+            email: input.email.clone(),
+        };
+
+        validation
+            .validate()
+            .map_err(|e| AuthError::InvalidInput(format!("Validation error: {}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::domain::auth::context::{AuthContext, AuthSource, AuthState};
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::KratosError;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{debug, error};
+
+pub struct ResolveCallerUseCase;
+
+impl ResolveCallerUseCase {
+    /// A bearer token shaped like a JWT is checked locally as a gateway access
+    /// token; Kratos session tokens and cookies go through `whoami`.
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        jwt_signer: &JwtSigner,
+        bearer_token: Option<&str>,
+        cookie: Option<&str>,
+    ) -> AuthState {
+        let auth_state = Self::resolve(kratos_client, jwt_signer, bearer_token, cookie).await;
+
+        if let AuthState::Authenticated(auth) = &auth_state {
+            debug!(
+                identity_id = %auth.identity_id,
+                source = ?auth.source,
+                aal = %auth.aal,
+                email_verified = auth.email_verified,
+                "Caller resolved"
+            );
+        }
+
+        auth_state
+    }
+
+    async fn resolve(
+        kratos_client: &KratosClient,
+        jwt_signer: &JwtSigner,
+        bearer_token: Option<&str>,
+        cookie: Option<&str>,
+    ) -> AuthState {
+        if let Some(token) = bearer_token
+            && Self::is_access_token(token)
+        {
+            return match jwt_signer.verify(token) {
+                Ok(claims) => AuthState::Authenticated(AuthContext::from_claims(claims)),
+                Err(e) => {
+                    debug!(error = %e, "Rejected access token");
+                    AuthState::Anonymous
+                }
+            };
+        }
+
+        let (credentials, source) = match (bearer_token, cookie) {
+            (Some(token), _) => (SessionCredentials::Token(token), AuthSource::SessionToken),
+            (None, Some(cookie)) => (SessionCredentials::Cookie(cookie), AuthSource::Cookie),
+            (None, None) => return AuthState::Anonymous,
+        };
+
+        match kratos_client.get_session(credentials).await {
+            Ok(Some(session)) if session.active => {
+                AuthState::Authenticated(AuthContext::from_session(session, source))
+            }
+            // Sessions still waiting for their second factor count as anonymous
+            Ok(_) | Err(KratosError::Aal2Required) => AuthState::Anonymous,
+            // The error names Kratos' URL and body, so it stays in the log
+            Err(e) => {
+                error!(error = %e, "Failed to resolve caller session");
+                AuthState::Unavailable("identity service".to_string())
+            }
+        }
+    }
+
+    pub fn is_access_token(token: &str) -> bool {
+        token.split('.').count() == 3
+    }
+}
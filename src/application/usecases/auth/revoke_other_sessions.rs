@@ -0,0 +1,27 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::RevokeSessionsResponse;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct RevokeOtherSessionsUseCase;
+
+impl RevokeOtherSessionsUseCase {
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<RevokeSessionsResponse, AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let revoked_count = kratos_client
+            .revoke_other_sessions(credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to revoke other sessions");
+                AuthError::from(e)
+            })?;
+
+        info!(revoked_count = revoked_count, "Other sessions revoked");
+
+        Ok(RevokeSessionsResponse { revoked_count })
+    }
+}
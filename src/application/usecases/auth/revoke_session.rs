@@ -0,0 +1,33 @@
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct RevokeSessionUseCase;
+
+impl RevokeSessionUseCase {
+    pub async fn execute(
+        session_id: &str,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<bool, AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        if session_id.trim().is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Session ID cannot be empty".to_string(),
+            ));
+        }
+
+        kratos_client
+            .revoke_session(session_id.trim(), credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, session_id = session_id, "Failed to revoke session");
+                AuthError::from(e)
+            })?;
+
+        info!(session_id = session_id, "Session revoked");
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::domain::auth::context::AuthContext;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::{ClientType, SendVerificationInput};
+use crate::domain::auth::responses::VerificationRequestResponse;
+use crate::infrastructure::adapters::kratos::KratosError;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{info, warn};
+use validator::Validate;
+
+const VERIFICATION_MESSAGE: &str =
+    "If this address belongs to an account, a verification code has been sent to it.";
+
+#[derive(Validate)]
+struct SendVerificationValidation {
+    #[validate(email)]
+    email: String,
+}
+
+pub struct SendVerificationUseCase;
+
+impl SendVerificationUseCase {
+    pub async fn execute(
+        input: SendVerificationInput,
+        client_type: ClientType,
+        kratos_client: &KratosClient,
+        auth: Option<&AuthContext>,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(VerificationRequestResponse, Vec<String>), AuthError> {
+        let email = match input.email {
+            Some(email) => email,
+            None => auth.map(|auth| auth.traits.email.clone()).ok_or_else(|| {
+                AuthError::InvalidInput("Email is required when not logged in".to_string())
+            })?,
+        };
+
+        Self::validate_email(&email)?;
+
+        match kratos_client
+            .handle_send_verification(&email, client_type.into(), credentials)
+            .await
+        {
+            Ok((flow_id, cookies)) => {
+                info!(flow_id = %flow_id, "Verification flow started");
+                Ok((
+                    VerificationRequestResponse {
+                        flow_id: Some(flow_id),
+                        message: VERIFICATION_MESSAGE.to_string(),
+                    },
+                    cookies,
+                ))
+            }
+            Err(KratosError::Validation(messages)) => {
+                warn!(messages = ?messages, "Verification request rejected by Kratos");
+                Ok((
+                    VerificationRequestResponse {
+                        flow_id: None,
+                        message: VERIFICATION_MESSAGE.to_string(),
+                    },
+                    Vec::new(),
+                ))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn validate_email(email: &str) -> Result<(), AuthError> {
+        let validation = SendVerificationValidation {
+            email: email.to_string(),
+        };
+
+        validation
+            .validate()
+            .map_err(|e| AuthError::InvalidInput(format!("Validation error: {}", e)))?;
+        Ok(())
+    }
+}
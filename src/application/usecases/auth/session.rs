@@ -0,0 +1,45 @@
+use crate::domain::auth::context::AuthContext;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::SessionView;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, KratosSession};
+use tracing::{debug, error};
+
+pub struct CurrentSessionUseCase;
+
+impl CurrentSessionUseCase {
+    /// Returns `None` for anonymous visitors instead of an error.
+    pub async fn execute(
+        kratos_client: &KratosClient,
+        auth: Option<&AuthContext>,
+    ) -> Result<Option<SessionView>, AuthError> {
+        Ok(Self::kratos_session(kratos_client, auth)
+            .await?
+            .map(SessionView::from))
+    }
+
+    /// The caller's Kratos session: the one the middleware got from `whoami`,
+    /// or an admin API lookup for callers holding a gateway access token.
+    pub async fn kratos_session(
+        kratos_client: &KratosClient,
+        auth: Option<&AuthContext>,
+    ) -> Result<Option<KratosSession>, AuthError> {
+        let Some(auth) = auth else {
+            debug!("No authenticated caller, visitor is anonymous");
+            return Ok(None);
+        };
+
+        if let Some(session) = &auth.session {
+            return Ok(Some(session.as_ref().clone()));
+        }
+
+        let session = kratos_client
+            .admin_get_session(&auth.session_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, session_id = %auth.session_id, "Failed to fetch current session");
+                AuthError::from(e)
+            })?;
+
+        Ok(session.filter(|session| session.active))
+    }
+}
@@ -0,0 +1,39 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::responses::SocialLoginResponse;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct StartSocialLoginUseCase;
+
+impl StartSocialLoginUseCase {
+    pub async fn execute(
+        provider: &str,
+        return_to: Option<&str>,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(SocialLoginResponse, Vec<String>), AuthError> {
+        if provider.is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Provider cannot be empty".to_string(),
+            ));
+        }
+
+        let start = kratos_client
+            .handle_start_oidc_login(provider, return_to, credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, provider = provider, "Failed to start OIDC login");
+                AuthError::from(e)
+            })?;
+
+        info!(flow_id = %start.flow_id, provider = provider, "OIDC login started");
+
+        Ok((
+            SocialLoginResponse {
+                flow_id: start.flow_id,
+                redirect_url: start.redirect_url,
+            },
+            start.cookies,
+        ))
+    }
+}
@@ -0,0 +1,43 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::WebauthnMethod;
+use crate::domain::auth::responses::WebauthnChallengeResponse;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use async_graphql::Json;
+use tracing::{error, info};
+
+pub struct StartWebauthnLoginUseCase;
+
+impl StartWebauthnLoginUseCase {
+    /// `identifier` is required for `webauthn`; passkey logins don't take one.
+    pub async fn execute(
+        method: WebauthnMethod,
+        identifier: Option<&str>,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(WebauthnChallengeResponse, Vec<String>), AuthError> {
+        let identifier = identifier.map(str::trim).filter(|s| !s.is_empty());
+        if method == WebauthnMethod::Webauthn && identifier.is_none() {
+            return Err(AuthError::InvalidInput(
+                "Identifier cannot be empty".to_string(),
+            ));
+        }
+
+        let challenge = kratos_client
+            .handle_webauthn_login_start(method, identifier, credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to start WebAuthn login");
+                AuthError::from(e)
+            })?;
+
+        info!(flow_id = %challenge.flow_id, ?method, "WebAuthn login started");
+
+        Ok((
+            WebauthnChallengeResponse {
+                flow_id: challenge.flow_id,
+                options: Json(challenge.options),
+            },
+            challenge.cookies,
+        ))
+    }
+}
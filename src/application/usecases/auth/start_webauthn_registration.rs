@@ -0,0 +1,36 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::WebauthnMethod;
+use crate::domain::auth::responses::WebauthnChallengeResponse;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use async_graphql::Json;
+use tracing::{error, info};
+
+pub struct StartWebauthnRegistrationUseCase;
+
+impl StartWebauthnRegistrationUseCase {
+    pub async fn execute(
+        method: WebauthnMethod,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(WebauthnChallengeResponse, Vec<String>), AuthError> {
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let challenge = kratos_client
+            .handle_webauthn_registration_start(method, credentials)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to start WebAuthn registration");
+                AuthError::from(e)
+            })?;
+
+        info!(flow_id = %challenge.flow_id, ?method, "WebAuthn registration started");
+
+        Ok((
+            WebauthnChallengeResponse {
+                flow_id: challenge.flow_id,
+                options: Json(challenge.options),
+            },
+            challenge.cookies,
+        ))
+    }
+}
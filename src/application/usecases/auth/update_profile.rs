@@ -0,0 +1,63 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::UpdateProfileInput;
+use crate::domain::auth::responses::UserView;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+use validator::Validate;
+
+#[derive(Validate)]
+struct UpdateProfileValidation {
+    #[validate(email)]
+    email: Option<String>,
+    #[validate(length(min = 3, max = 20))]
+    username: Option<String>,
+}
+
+pub struct UpdateProfileUseCase;
+
+impl UpdateProfileUseCase {
+    pub async fn execute(
+        input: UpdateProfileInput,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(UserView, Vec<String>), AuthError> {
+        Self::validate_input(&input)?;
+
+        let credentials = credentials.ok_or(AuthError::NotAuthenticated)?;
+
+        let (identity, cookies) = kratos_client
+            .handle_update_profile(
+                input.email.as_deref(),
+                input.username.as_deref(),
+                input.geo_location.as_deref(),
+                credentials,
+            )
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Profile update failed");
+                AuthError::from(e)
+            })?;
+
+        info!(identity_id = %identity.id, "Profile updated");
+
+        Ok((UserView::from(identity), cookies))
+    }
+
+    fn validate_input(input: &UpdateProfileInput) -> Result<(), AuthError> {
+        if input.email.is_none() && input.username.is_none() && input.geo_location.is_none() {
+            return Err(AuthError::InvalidInput(
+                "At least one field must be provided".to_string(),
+            ));
+        }
+
+        let validation = UpdateProfileValidation {
+            email: input.email.clone(),
+            username: input.username.clone(),
+        };
+
+        validation
+            .validate()
+            .map_err(|e| AuthError::InvalidInput(format!("Validation error: {}", e)))?;
+        Ok(())
+    }
+}
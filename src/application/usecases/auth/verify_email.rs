@@ -0,0 +1,51 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::auth::inputs::{ClientType, VerifyEmailInput};
+use crate::domain::auth::responses::VerificationResponse;
+use crate::infrastructure::adapters::kratos::kratos_client::{KratosClient, SessionCredentials};
+use tracing::{error, info};
+
+pub struct VerifyEmailUseCase;
+
+impl VerifyEmailUseCase {
+    pub async fn execute(
+        input: VerifyEmailInput,
+        client_type: ClientType,
+        kratos_client: &KratosClient,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(VerificationResponse, Vec<String>), AuthError> {
+        Self::validate_input(&input)?;
+
+        let cookies = kratos_client
+            .handle_complete_verification(
+                &input.flow_id,
+                input.code.trim(),
+                client_type.into(),
+                credentials,
+            )
+            .await
+            .map_err(|e| {
+                error!(error = %e, flow_id = %input.flow_id, "Verification failed");
+                AuthError::from(e)
+            })?;
+
+        info!(flow_id = %input.flow_id, "Email verified");
+
+        Ok((VerificationResponse { verified: true }, cookies))
+    }
+
+    fn validate_input(input: &VerifyEmailInput) -> Result<(), AuthError> {
+        if input.flow_id.is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Flow ID cannot be empty".to_string(),
+            ));
+        }
+
+        if input.code.trim().is_empty() {
+            return Err(AuthError::InvalidInput(
+                "Verification code cannot be empty".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::domain::auth::errors::AuthError;
+use crate::domain::gateway::rate_limit::{RateLimitKey, RateLimitRule};
+use crate::domain::repositories::rate_limit_repository::RateLimitRepository;
+use tracing::{debug, error, warn};
+
+/// Who a request comes from, as far as rate limits are concerned.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RateLimitSubject<'a> {
+    pub ip: Option<&'a str>,
+    pub identifier: Option<&'a str>,
+    pub identity_id: Option<&'a str>,
+}
+
+pub struct CheckRateLimitUseCase;
+
+impl CheckRateLimitUseCase {
+    /// Counts a hit against every rule of `scope` that applies to the subject.
+    /// Fails with `RateLimited` at the first exceeded rule; a failing store
+    /// lets the request through.
+    pub async fn execute(
+        scope: &str,
+        rules: &[RateLimitRule],
+        subject: RateLimitSubject<'_>,
+        rate_limits: &dyn RateLimitRepository,
+    ) -> Result<(), AuthError> {
+        for rule in rules {
+            let value = match rule.key {
+                RateLimitKey::Ip => subject.ip,
+                RateLimitKey::Identifier => subject.identifier,
+                RateLimitKey::Identity => subject.identity_id.or(subject.ip),
+            };
+            let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+                continue;
+            };
+
+            let decision = match rate_limits.hit(&rule.storage_key(scope, value), rule).await {
+                Ok(decision) => decision,
+                Err(e) => {
+                    error!(error = %e, scope, "Rate limit check failed");
+                    continue;
+                }
+            };
+
+            if !decision.allowed {
+                warn!(scope, key = ?rule.key, retry_after = ?decision.retry_after, "Rate limited");
+                return Err(AuthError::RateLimited(decision.retry_after));
+            }
+            debug!(scope, key = ?rule.key, remaining = decision.remaining, "Rate limit hit");
+        }
+
+        Ok(())
+    }
+}
@@ -1,2 +1,4 @@
+pub mod admin;
 pub mod auth;
+pub mod check_rate_limit;
 pub mod health_check;
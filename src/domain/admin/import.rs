@@ -0,0 +1,321 @@
+use crate::infrastructure::adapters::kratos::kratos_client::{
+    IdentityTraits, KratosIdentity, NewIdentity,
+};
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ImportFormat {
+    Jsonl,
+    /// Header row with the `IdentityRecord` field names; `roles` are separated by `;`.
+    Csv,
+}
+
+impl ImportFormat {
+    /// Guesses the format from a file name, defaulting to JSONL.
+    pub fn from_file_name(file_name: &str) -> Self {
+        if file_name.to_ascii_lowercase().ends_with(".csv") {
+            ImportFormat::Csv
+        } else {
+            ImportFormat::Jsonl
+        }
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "jsonl" | "ndjson" => Ok(ImportFormat::Jsonl),
+            "csv" => Ok(ImportFormat::Csv),
+            other => Err(format!("Unknown format '{}', expected jsonl or csv", other)),
+        }
+    }
+}
+
+/// One identity per JSONL line / CSV row, shared by import and export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdentityRecord {
+    /// Only written by the export; Kratos assigns new ids on import.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub email: String,
+    pub username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo_location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub verified: bool,
+}
+
+impl IdentityRecord {
+    pub fn from_json_line(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line).map_err(|e| format!("Invalid JSON: {}", e))
+    }
+
+    pub fn from_csv_row(header: &[String], line: &str) -> Result<Self, String> {
+        let values = split_csv_line(line)?;
+        if values.len() != header.len() {
+            return Err(format!(
+                "Expected {} columns, found {}",
+                header.len(),
+                values.len()
+            ));
+        }
+
+        let mut record = IdentityRecord::default();
+        for (column, value) in header.iter().zip(values) {
+            let value = value.trim().to_string();
+            let optional = (!value.is_empty()).then(|| value.clone());
+
+            match column.as_str() {
+                "id" => {}
+                "email" => record.email = value,
+                "username" => record.username = value,
+                "geo_location" => record.geo_location = optional,
+                "password" => record.password = optional,
+                "password_hash" => record.password_hash = optional,
+                "state" => record.state = optional,
+                "roles" => {
+                    record.roles = value
+                        .split(';')
+                        .map(|role| role.trim().to_string())
+                        .filter(|role| !role.is_empty())
+                        .collect()
+                }
+                "verified" => {
+                    record.verified =
+                        matches!(value.to_ascii_lowercase().as_str(), "true" | "1" | "yes")
+                }
+                other => return Err(format!("Unknown column '{}'", other)),
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Identifier used in error reports.
+    pub fn identifier(&self) -> Option<String> {
+        [&self.email, &self.username]
+            .into_iter()
+            .find(|s| !s.is_empty())
+            .cloned()
+    }
+
+    pub fn into_new_identity(self) -> Result<NewIdentity, String> {
+        if self.email.is_empty() || self.username.is_empty() {
+            return Err("email and username are required".to_string());
+        }
+
+        if self.password.is_some() && self.password_hash.is_some() {
+            return Err("Provide either password or password_hash, not both".to_string());
+        }
+
+        if let Some(hash) = &self.password_hash {
+            validate_password_hash(hash)?;
+        }
+
+        if let Some(state) = &self.state
+            && !matches!(state.as_str(), "active" | "inactive")
+        {
+            return Err(format!("Unknown state '{}'", state));
+        }
+
+        Ok(NewIdentity {
+            traits: IdentityTraits {
+                email: self.email,
+                username: self.username,
+                geo_location: self.geo_location,
+            },
+            password: self.password,
+            password_hash: self.password_hash,
+            state: self.state,
+            roles: self.roles,
+            verified: self.verified,
+        })
+    }
+}
+
+impl From<KratosIdentity> for IdentityRecord {
+    fn from(identity: KratosIdentity) -> Self {
+        let verified = identity
+            .verifiable_addresses
+            .iter()
+            .any(|address| address.value == identity.traits.email && address.verified);
+
+        Self {
+            id: Some(identity.id),
+            email: identity.traits.email,
+            username: identity.traits.username,
+            geo_location: identity.traits.geo_location,
+            password: None,
+            password_hash: None,
+            state: identity.state,
+            roles: identity.roles,
+            verified,
+        }
+    }
+}
+
+/// Kratos only accepts hashes it can verify; reject malformed ones up front
+/// instead of failing the whole batch.
+fn validate_password_hash(hash: &str) -> Result<(), String> {
+    if hash.starts_with("$2") {
+        bcrypt::HashParts::from_str(hash)
+            .map(|_| ())
+            .map_err(|e| format!("Invalid bcrypt hash: {}", e))
+    } else if hash.starts_with("$argon2") {
+        argon2::PasswordHash::new(hash)
+            .map(|_| ())
+            .map_err(|e| format!("Invalid argon2 hash: {}", e))
+    } else {
+        Err("Unsupported password hash, expected bcrypt or argon2".to_string())
+    }
+}
+
+/// Splits one CSV line, honouring double-quoted fields and `""` escapes.
+/// Quoted fields spanning several lines are not supported.
+pub fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("Unterminated quoted field".to_string());
+    }
+
+    fields.push(field);
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BCRYPT_HASH: &str = "$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW";
+    const ARGON2_HASH: &str =
+        "$argon2id$v=19$m=65536,t=3,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG";
+
+    fn header(columns: &str) -> Vec<String> {
+        columns.split(',').map(|c| c.to_string()).collect()
+    }
+
+    fn record(hash: &str) -> IdentityRecord {
+        IdentityRecord {
+            email: "ada@example.com".to_string(),
+            username: "ada".to_string(),
+            password_hash: Some(hash.to_string()),
+            ..IdentityRecord::default()
+        }
+    }
+
+    #[test]
+    fn splits_quoted_fields_and_escaped_quotes() {
+        assert_eq!(split_csv_line("a,,c").unwrap(), ["a", "", "c"]);
+        assert_eq!(
+            split_csv_line(r#""Lovelace, Ada","say ""hi""",x"#).unwrap(),
+            ["Lovelace, Ada", r#"say "hi""#, "x"]
+        );
+        assert_eq!(split_csv_line(r#""""#).unwrap(), [""]);
+
+        // Quotes inside an unquoted field are kept as they are
+        assert_eq!(split_csv_line(r#"a"b,c"#).unwrap(), [r#"a"b"#, "c"]);
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes() {
+        assert_eq!(
+            split_csv_line(r#""Lovelace, Ada,ada"#).unwrap_err(),
+            "Unterminated quoted field"
+        );
+        assert!(split_csv_line(r#"a,"say ""hi"","#).is_err());
+    }
+
+    #[test]
+    fn reads_csv_rows_by_header() {
+        let header = header("id,email,username,geo_location,password,state,roles,verified");
+        let record = IdentityRecord::from_csv_row(
+            &header,
+            r#"old-id, ada@example.com ,ada,"London, UK",,inactive,"admin; ;ops",Yes"#,
+        )
+        .unwrap();
+
+        assert_eq!(record.id, None);
+        assert_eq!(record.email, "ada@example.com");
+        assert_eq!(record.username, "ada");
+        assert_eq!(record.geo_location.as_deref(), Some("London, UK"));
+        assert_eq!(record.password, None);
+        assert_eq!(record.state.as_deref(), Some("inactive"));
+        assert_eq!(record.roles, ["admin", "ops"]);
+        assert!(record.verified);
+    }
+
+    #[test]
+    fn rejects_column_count_mismatches_and_unknown_columns() {
+        let header = header("email,username");
+
+        assert_eq!(
+            IdentityRecord::from_csv_row(&header, "ada@example.com").unwrap_err(),
+            "Expected 2 columns, found 1"
+        );
+        assert_eq!(
+            IdentityRecord::from_csv_row(&header, r#"ada@example.com,ada,"x,y""#).unwrap_err(),
+            "Expected 2 columns, found 3"
+        );
+        assert_eq!(
+            IdentityRecord::from_csv_row(&self::header("email,nickname"), "ada@example.com,ada")
+                .unwrap_err(),
+            "Unknown column 'nickname'"
+        );
+    }
+
+    #[test]
+    fn accepts_bcrypt_and_argon2_hashes() {
+        for hash in [BCRYPT_HASH, ARGON2_HASH] {
+            let identity = record(hash).into_new_identity().unwrap();
+            assert_eq!(identity.password_hash.as_deref(), Some(hash));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_and_unsupported_hashes() {
+        let error = |hash: &str| record(hash).into_new_identity().unwrap_err();
+
+        assert!(error("$2b$12$too-short").starts_with("Invalid bcrypt hash"));
+        assert!(error("$argon2id$v=19$not-a-phc-string!").starts_with("Invalid argon2 hash"));
+        assert_eq!(
+            error("{SSHA}W6ph5Mm5Pz8GgiULbPgzG37mj9g="),
+            "Unsupported password hash, expected bcrypt or argon2"
+        );
+
+        let mut both = record(BCRYPT_HASH);
+        both.password = Some("hunter2-hunter2".to_string());
+        assert_eq!(
+            both.into_new_identity().unwrap_err(),
+            "Provide either password or password_hash, not both"
+        );
+    }
+}
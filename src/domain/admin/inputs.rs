@@ -0,0 +1,50 @@
+use async_graphql::{Enum, InputObject};
+
+/// Role that unlocks `AdminQuery` / `AdminMutation`, stored in the identity's
+/// `metadata_public.roles`.
+pub const ADMIN_ROLE: &str = "admin";
+
+/// Keto relation on `IDENTITIES_OBJECT` granting the same access as
+/// `ADMIN_ROLE`, for operators managed in Keto.
+pub const MANAGE_RELATION: &str = "manage";
+pub const IDENTITIES_OBJECT: &str = "gateway:identities";
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IdentityState {
+    Active,
+    Inactive,
+}
+
+impl IdentityState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdentityState::Active => "active",
+            IdentityState::Inactive => "inactive",
+        }
+    }
+}
+
+#[derive(InputObject, Clone)]
+pub struct CreateIdentityInput {
+    pub email: String,
+    pub username: String,
+    /// Without a password the user has to use recovery to set one.
+    pub password: Option<String>,
+    pub geo_location: Option<String>,
+    pub state: Option<IdentityState>,
+    #[graphql(default)]
+    pub roles: Vec<String>,
+    /// Mark the email address as verified.
+    #[graphql(default)]
+    pub verified: bool,
+}
+
+#[derive(InputObject, Clone, Default)]
+pub struct UpdateIdentityInput {
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub geo_location: Option<String>,
+    pub state: Option<IdentityState>,
+    /// Replaces the complete role list.
+    pub roles: Option<Vec<String>>,
+}
@@ -0,0 +1,75 @@
+use crate::domain::auth::responses::VerifiableAddressView;
+use crate::infrastructure::adapters::kratos::kratos_client::{IdentityPage, KratosIdentity};
+use async_graphql::SimpleObject;
+
+/// Full identity as seen by administrators.
+#[derive(SimpleObject, Clone)]
+pub struct IdentityView {
+    pub id: String,
+    pub schema_id: String,
+    pub state: Option<String>,
+    pub email: String,
+    pub login: String,
+    pub geo_location: Option<String>,
+    pub roles: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub verifiable_addresses: Vec<VerifiableAddressView>,
+}
+
+impl From<KratosIdentity> for IdentityView {
+    fn from(identity: KratosIdentity) -> Self {
+        Self {
+            id: identity.id,
+            schema_id: identity.schema_id,
+            state: identity.state,
+            email: identity.traits.email,
+            login: identity.traits.username,
+            geo_location: identity.traits.geo_location,
+            roles: identity.roles,
+            created_at: identity.created_at,
+            updated_at: identity.updated_at,
+            verifiable_addresses: identity
+                .verifiable_addresses
+                .into_iter()
+                .map(VerifiableAddressView::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct IdentityConnection {
+    pub identities: Vec<IdentityView>,
+    /// Pass as `pageToken` to fetch the next page; `None` on the last page.
+    pub next_page_token: Option<String>,
+}
+
+impl From<IdentityPage> for IdentityConnection {
+    fn from(page: IdentityPage) -> Self {
+        Self {
+            identities: page
+                .identities
+                .into_iter()
+                .map(IdentityView::from)
+                .collect(),
+            next_page_token: page.next_page_token,
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct ImportRowError {
+    /// 1-based line number in the imported file.
+    pub line: u64,
+    pub identifier: Option<String>,
+    pub message: String,
+}
+
+#[derive(SimpleObject, Clone, Default)]
+pub struct ImportReport {
+    pub total: u64,
+    pub imported: u64,
+    pub failed: u64,
+    pub errors: Vec<ImportRowError>,
+}
@@ -0,0 +1,89 @@
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::jwt::jwt_signer::AccessTokenClaims;
+use crate::infrastructure::adapters::kratos::kratos_client::{IdentityTraits, KratosSession};
+
+/// How the caller proved who they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthSource {
+    Cookie,
+    SessionToken,
+    AccessToken,
+}
+
+/// The authenticated caller, resolved once per request.
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    pub identity_id: String,
+    pub traits: IdentityTraits,
+    pub email_verified: bool,
+    pub session_id: String,
+    pub aal: String,
+    pub roles: Vec<String>,
+    pub source: AuthSource,
+    /// The whoami session, so resolvers need no second round trip; `None` for
+    /// callers authenticated by a gateway access token.
+    pub session: Option<Box<KratosSession>>,
+}
+
+impl AuthContext {
+    pub fn from_session(session: KratosSession, source: AuthSource) -> Self {
+        Self {
+            identity_id: session.identity.id.clone(),
+            traits: session.identity.traits.clone(),
+            email_verified: session.identity.is_email_verified(),
+            session_id: session.id.clone(),
+            aal: session
+                .authenticator_assurance_level
+                .clone()
+                .unwrap_or_else(|| "aal1".to_string()),
+            roles: session.identity.roles.clone(),
+            source,
+            session: Some(Box::new(session)),
+        }
+    }
+
+    pub fn from_claims(claims: AccessTokenClaims) -> Self {
+        Self {
+            identity_id: claims.sub,
+            traits: IdentityTraits {
+                email: claims.email,
+                username: claims.username,
+                geo_location: None,
+            },
+            email_verified: claims.email_verified,
+            session_id: claims.sid,
+            aal: claims.aal,
+            roles: claims.roles,
+            source: AuthSource::AccessToken,
+            session: None,
+        }
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+/// Outcome of resolving the caller. `Unavailable` keeps a Kratos outage from
+/// being reported as "not logged in".
+#[derive(Debug, Clone, Default)]
+pub enum AuthState {
+    #[default]
+    Anonymous,
+    Authenticated(AuthContext),
+    Unavailable(String),
+}
+
+impl AuthState {
+    pub fn context(&self) -> Result<Option<&AuthContext>, AuthError> {
+        match self {
+            AuthState::Anonymous => Ok(None),
+            AuthState::Authenticated(context) => Ok(Some(context)),
+            AuthState::Unavailable(reason) => Err(AuthError::ServiceUnavailable(reason.clone())),
+        }
+    }
+
+    pub fn require(&self) -> Result<&AuthContext, AuthError> {
+        self.context()?.ok_or(AuthError::NotAuthenticated)
+    }
+}
@@ -0,0 +1,122 @@
+use async_graphql::{Error, ErrorExtensions};
+use serde::Serialize;
+use std::time::Duration;
+use thiserror::Error;
+
+/// A single Kratos UI message, exposed to clients under `extensions.fieldErrors`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    /// Name of the form input the message belongs to, `None` for flow-level messages.
+    pub field: Option<String>,
+    pub id: u64,
+    pub text: String,
+    #[serde(rename = "type")]
+    pub message_type: String,
+}
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("Invalid credentials")]
+    InvalidCredentials(Vec<FieldError>),
+    #[error("Already logged in. Please logout first before logging in again.")]
+    AlreadyAuthenticated,
+    #[error("Not logged in: no active session")]
+    NotAuthenticated,
+    #[error("Session is no longer privileged, please log in again to continue")]
+    ReauthenticationRequired,
+    #[error("A second authentication factor is required")]
+    SecondFactorRequired,
+    #[error("Refresh token is invalid or expired")]
+    InvalidRefreshToken,
+    #[error("Refresh token was already used; all tokens of this login have been revoked")]
+    RefreshTokenReused,
+    #[error("Not allowed to perform this operation: {0}")]
+    Forbidden(String),
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("An account with the same identifier already exists")]
+    DuplicateIdentifier(Vec<FieldError>),
+    #[error("The self-service flow has expired, please try again")]
+    FlowExpired,
+    #[error("CSRF verification failed")]
+    CsrfViolation,
+    #[error("Validation failed: {}", join_field_errors(.0))]
+    Validation(Vec<FieldError>),
+    #[error("Too many requests, try again in {} seconds", retry_after_seconds(*.0))]
+    RateLimited(Duration),
+    #[error("Authentication service unavailable: {0}")]
+    ServiceUnavailable(String),
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl AuthError {
+    /// Stable value exposed as `extensions.code`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AuthError::InvalidInput(_) => "BAD_USER_INPUT",
+            AuthError::InvalidCredentials(_) => "INVALID_CREDENTIALS",
+            AuthError::AlreadyAuthenticated => "ALREADY_AUTHENTICATED",
+            AuthError::NotAuthenticated => "UNAUTHENTICATED",
+            AuthError::ReauthenticationRequired => "REAUTHENTICATION_REQUIRED",
+            AuthError::SecondFactorRequired => "AAL2_REQUIRED",
+            AuthError::InvalidRefreshToken => "INVALID_REFRESH_TOKEN",
+            AuthError::RefreshTokenReused => "REFRESH_TOKEN_REUSED",
+            AuthError::Forbidden(_) => "FORBIDDEN",
+            AuthError::NotFound(_) => "NOT_FOUND",
+            AuthError::DuplicateIdentifier(_) => "DUPLICATE_IDENTIFIER",
+            AuthError::FlowExpired => "FLOW_EXPIRED",
+            AuthError::CsrfViolation => "CSRF_VIOLATION",
+            AuthError::Validation(_) => "VALIDATION_FAILED",
+            AuthError::RateLimited(_) => "RATE_LIMITED",
+            AuthError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AuthError::Internal(_) => "INTERNAL_SERVER_ERROR",
+        }
+    }
+
+    pub fn field_errors(&self) -> &[FieldError] {
+        match self {
+            AuthError::InvalidCredentials(errors)
+            | AuthError::DuplicateIdentifier(errors)
+            | AuthError::Validation(errors) => errors,
+            _ => &[],
+        }
+    }
+}
+
+impl ErrorExtensions for AuthError {
+    fn extend(&self) -> Error {
+        Error::new(self.to_string()).extend_with(|_, e| {
+            e.set("code", self.code());
+
+            if let AuthError::RateLimited(retry_after) = self {
+                e.set("retryAfter", retry_after_seconds(*retry_after));
+            }
+
+            let field_errors = self.field_errors();
+            if !field_errors.is_empty()
+                && let Ok(value) = async_graphql::to_value(field_errors)
+            {
+                e.set("fieldErrors", value);
+            }
+        })
+    }
+}
+
+/// Whole seconds for `Retry-After`, rounded up and at least one.
+pub fn retry_after_seconds(retry_after: Duration) -> u64 {
+    retry_after.as_millis().div_ceil(1000).max(1) as u64
+}
+
+fn join_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| match &e.field {
+            Some(field) => format!("{}: {}", field, e.text),
+            None => e.text.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
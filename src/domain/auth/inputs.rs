@@ -1,4 +1,22 @@
-use async_graphql::InputObject;
+use async_graphql::{Enum, InputObject};
+
+/// Kind of client driving the auth flow: browsers get Kratos cookies,
+/// native apps get a session token.
+#[derive(Enum, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ClientType {
+    #[default]
+    Browser,
+    Native,
+}
+
+/// Kratos method driving a WebAuthn ceremony: `webauthn` for security keys
+/// (identifier first), `passkey` for discoverable credentials.
+#[derive(Enum, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum WebauthnMethod {
+    #[default]
+    Webauthn,
+    Passkey,
+}
 
 #[derive(InputObject, Clone)]
 pub struct RegisterInput {
@@ -14,3 +32,67 @@ pub struct LoginInput {
     pub username: Option<String>,
     pub password: String,
 }
+
+#[derive(Clone)]
+pub struct ChangePasswordInput {
+    pub password: String,
+}
+
+#[derive(Clone)]
+pub struct UpdateProfileInput {
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub geo_location: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct RequestRecoveryInput {
+    pub email: String,
+}
+
+#[derive(Clone)]
+pub struct CompleteRecoveryInput {
+    pub flow_id: String,
+    pub code: String,
+}
+
+#[derive(Clone)]
+pub struct SendVerificationInput {
+    /// Defaults to the email of the current session's identity.
+    pub email: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct VerifyEmailInput {
+    pub flow_id: String,
+    pub code: String,
+}
+
+#[derive(Clone)]
+pub struct ConfirmTotpInput {
+    pub flow_id: String,
+    pub code: String,
+}
+
+#[derive(Clone)]
+pub struct FinishWebauthnRegistrationInput {
+    pub method: WebauthnMethod,
+    pub flow_id: String,
+    pub display_name: Option<String>,
+    pub credential: String,
+}
+
+#[derive(Clone)]
+pub struct FinishWebauthnLoginInput {
+    pub method: WebauthnMethod,
+    pub flow_id: String,
+    pub identifier: Option<String>,
+    pub assertion: String,
+}
+
+#[derive(Clone)]
+pub struct LoginSecondFactorInput {
+    pub flow_id: String,
+    pub totp_code: Option<String>,
+    pub lookup_secret: Option<String>,
+}
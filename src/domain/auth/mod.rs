@@ -1,2 +1,4 @@
+pub mod context;
+pub mod errors;
 pub mod inputs;
 pub mod responses;
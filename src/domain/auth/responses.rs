@@ -1,32 +1,94 @@
-use crate::infrastructure::adapters::kratos::kratos_client::KratosIdentity;
-use async_graphql::SimpleObject;
+use crate::domain::auth::errors::AuthError;
+use crate::infrastructure::adapters::jwt::jwt_signer::IssuedToken;
+use crate::infrastructure::adapters::kratos::kratos_client::{
+    KratosClient, KratosIdentity, KratosSession, SecondFactorChallenge, SessionDevice,
+    VerifiableAddress, WebauthnCredential,
+};
+use async_graphql::{ComplexObject, Context, ErrorExtensions, Json, Result, SimpleObject};
 
 #[derive(SimpleObject, Clone)]
 pub struct AuthResponse {
-    pub session_token: String,
+    /// Kratos session token, only issued to native clients.
+    pub session_token: Option<String>,
     pub user: UserView,
+    /// The password was accepted but the session is only AAL1; finish the
+    /// login with `loginSecondFactor` using `secondFactorFlowId`.
+    pub requires_second_factor: bool,
+    pub second_factor_flow_id: Option<String>,
+    pub second_factor_methods: Vec<String>,
+    /// Gateway JWT for downstream services, only issued once the login is
+    /// complete (no second factor pending).
+    pub access_token: Option<AccessTokenView>,
 }
 
 impl AuthResponse {
-    pub fn from_kratos_identity(identity: KratosIdentity, session_token: String) -> Self {
+    pub fn from_kratos_identity(identity: KratosIdentity, session_token: Option<String>) -> Self {
         Self {
             session_token,
             user: UserView::from(identity),
+            requires_second_factor: false,
+            second_factor_flow_id: None,
+            second_factor_methods: Vec::new(),
+            access_token: None,
         }
     }
 
-    pub fn with_token(identity: KratosIdentity, token: String) -> Self {
-        Self::from_kratos_identity(identity, token)
+    pub fn with_second_factor(mut self, challenge: Option<SecondFactorChallenge>) -> Self {
+        if let Some(challenge) = challenge {
+            self.requires_second_factor = true;
+            self.second_factor_flow_id = Some(challenge.flow_id);
+            self.second_factor_methods = challenge.methods;
+        }
+        self
+    }
+
+    pub fn with_access_token(mut self, access_token: Option<AccessTokenView>) -> Self {
+        self.access_token = access_token;
+        self
     }
 }
 
 #[derive(SimpleObject, Clone)]
+pub struct AccessTokenView {
+    pub token: String,
+    pub token_type: String,
+    pub expires_at: String,
+    /// Lifetime in seconds, as in OAuth 2.0 token responses.
+    pub expires_in: i64,
+    /// Opaque single-use token for the `refreshToken` mutation.
+    pub refresh_token: Option<String>,
+}
+
+impl From<IssuedToken> for AccessTokenView {
+    fn from(issued: IssuedToken) -> Self {
+        Self {
+            token: issued.token,
+            token_type: "Bearer".to_string(),
+            expires_at: issued.expires_at.to_rfc3339(),
+            expires_in: (issued.expires_at - chrono::Utc::now())
+                .num_seconds()
+                .max(0),
+            refresh_token: None,
+        }
+    }
+}
+
+impl AccessTokenView {
+    pub fn with_refresh_token(mut self, refresh_token: String) -> Self {
+        self.refresh_token = Some(refresh_token);
+        self
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+#[graphql(complex)]
 pub struct UserView {
     pub id: String,
     pub email: String,
     pub login: String,
     pub created_at: String,
     pub updated_at: String,
+    pub verifiable_addresses: Vec<VerifiableAddressView>,
 }
 
 impl From<KratosIdentity> for UserView {
@@ -37,6 +99,238 @@ impl From<KratosIdentity> for UserView {
             login: identity.traits.username,
             created_at: identity.created_at,
             updated_at: identity.updated_at,
+            verifiable_addresses: identity
+                .verifiable_addresses
+                .into_iter()
+                .map(VerifiableAddressView::from)
+                .collect(),
+        }
+    }
+}
+
+#[ComplexObject]
+impl UserView {
+    /// Registered security keys and passkeys, loaded from the Kratos admin API
+    /// only when selected.
+    async fn webauthn_credentials(&self, ctx: &Context<'_>) -> Result<Vec<WebauthnCredentialView>> {
+        let kratos_client = ctx.data_unchecked::<KratosClient>();
+
+        let credentials = kratos_client
+            .get_webauthn_credentials(&self.id)
+            .await
+            .map_err(|e| AuthError::from(e).extend())?;
+
+        Ok(credentials
+            .into_iter()
+            .map(WebauthnCredentialView::from)
+            .collect())
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct WebauthnCredentialView {
+    pub id: String,
+    pub display_name: String,
+    pub added_at: Option<String>,
+    pub passwordless: bool,
+}
+
+impl From<WebauthnCredential> for WebauthnCredentialView {
+    fn from(credential: WebauthnCredential) -> Self {
+        Self {
+            id: credential.id,
+            display_name: credential.display_name,
+            added_at: credential.added_at,
+            passwordless: credential.is_passwordless,
         }
     }
 }
+
+#[derive(SimpleObject, Clone)]
+pub struct VerifiableAddressView {
+    pub value: String,
+    pub verified: bool,
+    pub verified_at: Option<String>,
+    pub status: String,
+}
+
+impl From<VerifiableAddress> for VerifiableAddressView {
+    fn from(address: VerifiableAddress) -> Self {
+        Self {
+            value: address.value,
+            verified: address.verified,
+            verified_at: address.verified_at,
+            status: address.status,
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct LogoutResponse {
+    pub logged_out: bool,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct SessionView {
+    pub id: String,
+    pub active: bool,
+    pub authenticated_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub aal: Option<String>,
+    pub authentication_methods: Vec<String>,
+    pub issued_at: Option<String>,
+    pub devices: Vec<SessionDeviceView>,
+    pub user: UserView,
+}
+
+impl From<KratosSession> for SessionView {
+    fn from(session: KratosSession) -> Self {
+        Self {
+            id: session.id,
+            active: session.active,
+            authenticated_at: session.authenticated_at,
+            expires_at: session.expires_at,
+            aal: session.authenticator_assurance_level,
+            authentication_methods: session.authentication_methods,
+            issued_at: session.issued_at,
+            devices: session
+                .devices
+                .into_iter()
+                .map(SessionDeviceView::from)
+                .collect(),
+            user: UserView::from(session.identity),
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct SessionDeviceView {
+    pub id: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub location: Option<String>,
+}
+
+impl From<SessionDevice> for SessionDeviceView {
+    fn from(device: SessionDevice) -> Self {
+        Self {
+            id: device.id,
+            ip_address: device.ip_address,
+            user_agent: device.user_agent,
+            location: device.location,
+        }
+    }
+}
+
+/// One entry of `mySessions`; `ipAddress` and `userAgent` are taken from the
+/// most recently seen device.
+#[derive(SimpleObject, Clone)]
+pub struct DeviceSessionView {
+    pub id: String,
+    pub current: bool,
+    pub active: bool,
+    pub authenticated_at: Option<String>,
+    pub issued_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub aal: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub location: Option<String>,
+    pub devices: Vec<SessionDeviceView>,
+}
+
+impl DeviceSessionView {
+    pub fn from_kratos_session(session: KratosSession, current: bool) -> Self {
+        let last_device = session.devices.last().cloned();
+
+        Self {
+            id: session.id,
+            current,
+            active: session.active,
+            authenticated_at: session.authenticated_at,
+            issued_at: session.issued_at,
+            expires_at: session.expires_at,
+            aal: session.authenticator_assurance_level,
+            ip_address: last_device.as_ref().and_then(|d| d.ip_address.clone()),
+            user_agent: last_device.as_ref().and_then(|d| d.user_agent.clone()),
+            location: last_device.and_then(|d| d.location),
+            devices: session
+                .devices
+                .into_iter()
+                .map(SessionDeviceView::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct RevokeSessionsResponse {
+    pub revoked_count: u64,
+}
+
+/// Identical for known and unknown addresses so the mutation cannot be used
+/// to probe which emails have an account.
+#[derive(SimpleObject, Clone)]
+pub struct RecoveryRequestResponse {
+    pub flow_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct RecoveryResponse {
+    pub recovered: bool,
+    /// Settings flow to continue with to choose a new password.
+    pub settings_flow_id: Option<String>,
+    /// Kratos session token, only issued to native clients.
+    pub session_token: Option<String>,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct VerificationRequestResponse {
+    pub flow_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct VerificationResponse {
+    pub verified: bool,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct TotpEnrollmentResponse {
+    /// Settings flow to confirm the enrollment on.
+    pub flow_id: String,
+    /// `data:image/png;base64,...` QR code for authenticator apps.
+    pub qr_code: Option<String>,
+    pub secret: Option<String>,
+    pub already_enabled: bool,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct LookupSecretsResponse {
+    /// Shown once; every code can be used a single time instead of TOTP.
+    pub codes: Vec<String>,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct SocialProvider {
+    pub id: String,
+    pub label: Option<String>,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct SocialLoginResponse {
+    pub flow_id: String,
+    /// Send the browser here; the provider redirects back to the gateway's
+    /// OIDC callback route, which sets the Kratos session cookie.
+    pub redirect_url: String,
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct WebauthnChallengeResponse {
+    /// Flow to send the signed credential back to.
+    pub flow_id: String,
+    /// Options for `navigator.credentials.create()` / `.get()`; binary fields
+    /// are base64url encoded.
+    pub options: Json<serde_json::Value>,
+}
@@ -0,0 +1,4 @@
+pub mod rate_limit;
+pub mod resilience;
+pub mod routes;
+pub mod subgraphs;
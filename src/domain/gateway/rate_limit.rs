@@ -0,0 +1,220 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAlgorithm {
+    /// At most `limit` hits in any `window_ms`.
+    #[default]
+    SlidingWindow,
+    /// Bursts of up to `limit`, refilled at `limit` per `window_ms`.
+    TokenBucket,
+}
+
+/// What a rule counts hits by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    #[default]
+    Ip,
+    /// The login identifier (email or username), or the flow a code is
+    /// entered for; GraphQL fields only.
+    Identifier,
+    /// The authenticated caller, by IP while anonymous. On `/graphql` and
+    /// proxied routes only gateway access tokens identify the caller.
+    Identity,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitRule {
+    #[serde(default)]
+    pub key: RateLimitKey,
+    #[serde(default)]
+    pub algorithm: RateLimitAlgorithm,
+    pub limit: u32,
+    pub window_ms: u64,
+}
+
+/// The rules the HTTP middleware applies to an endpoint or proxied route;
+/// `name` keeps its counters apart from other scopes.
+#[derive(Debug, Clone)]
+pub struct RateLimitScope {
+    pub name: String,
+    pub rules: Vec<RateLimitRule>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub remaining: u32,
+    /// Until the next hit would be allowed; zero when allowed.
+    pub retry_after: Duration,
+}
+
+/// `RATE_LIMITS_PATH`: a rule set for the `/graphql` endpoint and named rule
+/// sets for GraphQL fields. Proxied routes carry their own `rate_limit`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(default)]
+    pub graphql: Vec<RateLimitRule>,
+    #[serde(default = "default_fields")]
+    pub fields: HashMap<String, Vec<RateLimitRule>>,
+    /// Take the client IP from `Forwarded`/`X-Forwarded-For`; only behind a
+    /// proxy that overwrites them.
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            graphql: Vec::new(),
+            fields: default_fields(),
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+/// Brute-force protection for the credential mutations and the flows that
+/// take a one-time code.
+fn default_fields() -> HashMap<String, Vec<RateLimitRule>> {
+    let rule = |key, limit, window_ms| RateLimitRule {
+        key,
+        algorithm: RateLimitAlgorithm::SlidingWindow,
+        limit,
+        window_ms,
+    };
+
+    HashMap::from([
+        (
+            "login".to_string(),
+            vec![
+                rule(RateLimitKey::Ip, 20, 60_000),
+                rule(RateLimitKey::Identifier, 5, 300_000),
+            ],
+        ),
+        (
+            "login_second_factor".to_string(),
+            vec![
+                rule(RateLimitKey::Ip, 20, 60_000),
+                rule(RateLimitKey::Identifier, 5, 300_000),
+            ],
+        ),
+        (
+            "register".to_string(),
+            vec![rule(RateLimitKey::Ip, 10, 3_600_000)],
+        ),
+        (
+            "request_password_recovery".to_string(),
+            vec![
+                rule(RateLimitKey::Ip, 10, 3_600_000),
+                rule(RateLimitKey::Identifier, 3, 3_600_000),
+            ],
+        ),
+        (
+            "complete_recovery".to_string(),
+            vec![
+                rule(RateLimitKey::Ip, 20, 60_000),
+                rule(RateLimitKey::Identifier, 5, 300_000),
+            ],
+        ),
+        (
+            "verify_email".to_string(),
+            vec![
+                rule(RateLimitKey::Ip, 20, 60_000),
+                rule(RateLimitKey::Identifier, 5, 300_000),
+            ],
+        ),
+        (
+            "send_verification_email".to_string(),
+            vec![
+                rule(RateLimitKey::Ip, 10, 3_600_000),
+                rule(RateLimitKey::Identifier, 3, 3_600_000),
+            ],
+        ),
+        (
+            "webauthn_login".to_string(),
+            vec![
+                rule(RateLimitKey::Ip, 20, 60_000),
+                rule(RateLimitKey::Identifier, 5, 300_000),
+            ],
+        ),
+        (
+            "social_login".to_string(),
+            vec![rule(RateLimitKey::Ip, 20, 60_000)],
+        ),
+        (
+            "refresh_token".to_string(),
+            vec![rule(RateLimitKey::Ip, 60, 60_000)],
+        ),
+    ])
+}
+
+impl RateLimitRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.limit == 0 || self.window_ms == 0 {
+            return Err("rate limit and window_ms must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Rules for the HTTP middleware, which knows no login identifier.
+    pub fn validate_http(rules: &[RateLimitRule]) -> Result<(), String> {
+        for rule in rules {
+            rule.validate()?;
+            if rule.key == RateLimitKey::Identifier {
+                return Err("only GraphQL fields can be rate limited by identifier".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_millis(self.window_ms)
+    }
+
+    /// Storage key for `value` (an IP, identifier or identity id) in `scope`.
+    /// The rule's shape is part of the key so changed rules start afresh.
+    pub fn storage_key(&self, scope: &str, value: &str) -> String {
+        let key = match self.key {
+            RateLimitKey::Ip => "ip",
+            RateLimitKey::Identifier => "identifier",
+            RateLimitKey::Identity => "identity",
+        };
+        let algorithm = match self.algorithm {
+            RateLimitAlgorithm::SlidingWindow => "sw",
+            RateLimitAlgorithm::TokenBucket => "tb",
+        };
+        format!(
+            "{}:{}:{}:{}:{}:{}",
+            scope,
+            key,
+            algorithm,
+            self.limit,
+            self.window_ms,
+            value.trim().to_lowercase()
+        )
+    }
+}
+
+impl RateLimitConfig {
+    pub fn load(json: &str) -> Result<Self, String> {
+        let config: RateLimitConfig =
+            serde_json::from_str(json).map_err(|e| format!("Invalid rate limits: {}", e))?;
+
+        RateLimitRule::validate_http(&config.graphql).map_err(|e| format!("graphql: {}", e))?;
+        for (field, rules) in &config.fields {
+            for rule in rules {
+                rule.validate()
+                    .map_err(|e| format!("Field '{}': {}", field, e))?;
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn field(&self, name: &str) -> &[RateLimitRule] {
+        self.fields.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+}
@@ -0,0 +1,98 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Retries for idempotent calls, with jittered exponential backoff between
+/// attempts.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts, the first one included; `1` disables retries.
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+    #[serde(default = "default_base_delay_ms")]
+    pub base_delay_ms: u64,
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+}
+
+/// Fails fast once a dependency keeps failing, then lets a single trial call
+/// through after `open_ms` to see whether it recovered.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct CircuitBreakerPolicy {
+    /// Consecutive failures (errors, timeouts, 5xx) that open the circuit.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    #[serde(default = "default_open_ms")]
+    pub open_ms: u64,
+}
+
+fn default_attempts() -> u32 {
+    3
+}
+
+fn default_base_delay_ms() -> u64 {
+    100
+}
+
+fn default_max_delay_ms() -> u64 {
+    2_000
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_open_ms() -> u64 {
+    30_000
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: default_attempts(),
+            base_delay_ms: default_base_delay_ms(),
+            max_delay_ms: default_max_delay_ms(),
+        }
+    }
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_failure_threshold(),
+            open_ms: default_open_ms(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.attempts == 0 {
+            return Err("retry attempts must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Upper bound of the delay after the `attempt`-th failed attempt
+    /// (1-based): `base_delay_ms * 2^(attempt - 1)`, capped at `max_delay_ms`.
+    pub fn backoff_ceiling(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(20);
+        Duration::from_millis(
+            self.base_delay_ms
+                .saturating_mul(factor)
+                .min(self.max_delay_ms),
+        )
+    }
+}
+
+impl CircuitBreakerPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.failure_threshold == 0 {
+            return Err("circuit breaker failure_threshold must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn open_duration(&self) -> Duration {
+        Duration::from_millis(self.open_ms)
+    }
+}
@@ -0,0 +1,217 @@
+use crate::domain::gateway::rate_limit::RateLimitRule;
+use crate::domain::gateway::resilience::{CircuitBreakerPolicy, RetryPolicy};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How the caller's identity reaches the upstream service.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentityForwarding {
+    /// `X-User-Id`, `X-User-Email`, `X-Session-Id`, `X-User-Roles`, `X-User-Aal`.
+    #[default]
+    Headers,
+    /// A gateway access token in `Authorization: Bearer`.
+    Jwt,
+}
+
+/// How a request picks one of the route's upstream instances.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceStrategy {
+    #[default]
+    RoundRobin,
+    /// Fewest requests in flight.
+    LeastConnections,
+    /// Same identity, same instance while the pool is stable; anonymous
+    /// callers fall back to round-robin.
+    ConsistentHash,
+}
+
+/// Periodic `GET` probe; any 2xx marks the instance healthy.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HealthCheck {
+    pub path: String,
+    #[serde(default = "default_health_interval_ms")]
+    pub interval_ms: u64,
+    #[serde(default = "default_health_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// One entry of the reverse-proxy route table.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProxyRoute {
+    /// Matched on path segment boundaries, e.g. `/api/orders`.
+    pub prefix: String,
+    /// Shorthand for a single-instance `upstreams`.
+    #[serde(default)]
+    upstream: Option<String>,
+    #[serde(default)]
+    pub upstreams: Vec<String>,
+    #[serde(default)]
+    pub strip_prefix: bool,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Allowed methods; empty allows every method.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// Anonymous callers are rejected with 401 unless this is `false`.
+    #[serde(default = "default_require_auth")]
+    pub require_auth: bool,
+    #[serde(default)]
+    pub identity: IdentityForwarding,
+    #[serde(default)]
+    pub balance: BalanceStrategy,
+    #[serde(default)]
+    pub health_check: Option<HealthCheck>,
+    /// Consecutive failed requests (connect errors, timeouts, 502/503/504)
+    /// before an instance is taken out of rotation.
+    #[serde(default = "default_max_failures")]
+    pub max_failures: u32,
+    /// How long an instance stays out after `max_failures`.
+    #[serde(default = "default_ejection_ms")]
+    pub ejection_ms: u64,
+    /// Applies to `GET`, `HEAD` and `OPTIONS`; each retry picks an instance
+    /// again.
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Trips on failures across the whole route, after passive ejection.
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerPolicy,
+    /// Keyed by `ip` or `identity`.
+    #[serde(default)]
+    pub rate_limit: Vec<RateLimitRule>,
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_require_auth() -> bool {
+    true
+}
+
+fn default_max_failures() -> u32 {
+    3
+}
+
+fn default_ejection_ms() -> u64 {
+    30_000
+}
+
+fn default_health_interval_ms() -> u64 {
+    10_000
+}
+
+fn default_health_timeout_ms() -> u64 {
+    2_000
+}
+
+impl ProxyRoute {
+    /// Parses a JSON array of routes, longest prefix first so more specific
+    /// routes win.
+    pub fn load(json: &str) -> Result<Vec<ProxyRoute>, String> {
+        let mut routes: Vec<ProxyRoute> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid route table: {}", e))?;
+
+        for route in &mut routes {
+            route.validate()?;
+        }
+
+        routes.sort_by_key(|route| std::cmp::Reverse(route.prefix.len()));
+        Ok(routes)
+    }
+
+    fn validate(&mut self) -> Result<(), String> {
+        self.prefix = self.prefix.trim_end_matches('/').to_string();
+
+        if !self.prefix.starts_with('/') {
+            return Err(format!(
+                "Route prefix '{}' must start with '/' and not be the root",
+                self.prefix
+            ));
+        }
+
+        if let Some(upstream) = self.upstream.take() {
+            self.upstreams.insert(0, upstream);
+        }
+        if self.upstreams.is_empty() {
+            return Err(format!("Route '{}' has no upstream", self.prefix));
+        }
+
+        for upstream in &mut self.upstreams {
+            *upstream = upstream.trim_end_matches('/').to_string();
+            if !upstream.starts_with("http://") && !upstream.starts_with("https://") {
+                return Err(format!(
+                    "Upstream '{}' of route '{}' must be an http(s) URL",
+                    upstream, self.prefix
+                ));
+            }
+        }
+
+        if let Some(health_check) = &self.health_check
+            && !health_check.path.starts_with('/')
+        {
+            return Err(format!(
+                "Health check path '{}' of route '{}' must start with '/'",
+                health_check.path, self.prefix
+            ));
+        }
+
+        if self.max_failures == 0 {
+            return Err(format!(
+                "max_failures of route '{}' must be at least 1",
+                self.prefix
+            ));
+        }
+
+        self.retry
+            .validate()
+            .and_then(|_| self.circuit_breaker.validate())
+            .and_then(|_| RateLimitRule::validate_http(&self.rate_limit))
+            .map_err(|e| format!("Route '{}': {}", self.prefix, e))?;
+
+        for method in &mut self.methods {
+            *method = method.to_ascii_uppercase();
+        }
+
+        Ok(())
+    }
+
+    pub fn allows_method(&self, method: &str) -> bool {
+        self.methods.is_empty() || self.methods.iter().any(|m| m == method)
+    }
+
+    /// URL on `upstream` for a request path that starts with the route prefix.
+    pub fn upstream_url(&self, upstream: &str, path: &str, query: &str) -> String {
+        let path = if self.strip_prefix {
+            path.strip_prefix(&self.prefix).unwrap_or(path)
+        } else {
+            path
+        };
+
+        let mut url = format!("{}{}", upstream, path);
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(query);
+        }
+        url
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    pub fn ejection(&self) -> Duration {
+        Duration::from_millis(self.ejection_ms)
+    }
+}
+
+impl HealthCheck {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
@@ -0,0 +1,48 @@
+use crate::domain::gateway::routes::IdentityForwarding;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// A downstream GraphQL service composed into the gateway's supergraph.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubgraphConfig {
+    /// Unique name, used in logs and errors.
+    pub name: String,
+    /// GraphQL endpoint, e.g. `http://orders:4000/graphql`.
+    pub url: String,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub identity: IdentityForwarding,
+}
+
+fn default_timeout_ms() -> u64 {
+    10_000
+}
+
+impl SubgraphConfig {
+    /// Parses a JSON array of subgraphs.
+    pub fn load(json: &str) -> Result<Vec<SubgraphConfig>, String> {
+        let subgraphs: Vec<SubgraphConfig> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid subgraph list: {}", e))?;
+
+        let mut names = std::collections::HashSet::new();
+        for subgraph in &subgraphs {
+            if !names.insert(subgraph.name.as_str()) {
+                return Err(format!("Duplicate subgraph name '{}'", subgraph.name));
+            }
+
+            if !subgraph.url.starts_with("http://") && !subgraph.url.starts_with("https://") {
+                return Err(format!(
+                    "URL '{}' of subgraph '{}' must be an http(s) URL",
+                    subgraph.url, subgraph.name
+                ));
+            }
+        }
+
+        Ok(subgraphs)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
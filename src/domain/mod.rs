@@ -1,3 +1,5 @@
+pub mod admin;
 pub mod auth;
 pub mod entities;
+pub mod gateway;
 pub mod repositories;
@@ -1 +1,4 @@
+pub mod permission_repository;
+pub mod rate_limit_repository;
+pub mod refresh_token_repository;
 pub mod user_repository;
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// A Keto relation tuple: `namespace:object#relation@subject_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RelationTuple {
+    pub namespace: String,
+    pub object: String,
+    pub relation: String,
+    pub subject_id: String,
+}
+
+impl RelationTuple {
+    /// Builds the tuple to check for a subject, with the object written as
+    /// `namespace:id` (e.g. `projects:42`).
+    pub fn for_subject(object: &str, relation: &str, subject_id: &str) -> Result<Self, String> {
+        let (namespace, object_id) = object
+            .split_once(':')
+            .filter(|(namespace, object_id)| !namespace.is_empty() && !object_id.is_empty())
+            .ok_or_else(|| format!("Object '{}' must be written as namespace:id", object))?;
+
+        if relation.is_empty() {
+            return Err("Relation cannot be empty".to_string());
+        }
+
+        Ok(Self {
+            namespace: namespace.to_string(),
+            object: object_id.to_string(),
+            relation: relation.to_string(),
+            subject_id: subject_id.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+pub trait PermissionRepository: Send + Sync {
+    /// Whether the relation holds, directly or through the backend's rewrites.
+    async fn check(&self, tuple: &RelationTuple) -> Result<bool, String>;
+}
@@ -0,0 +1,8 @@
+use crate::domain::gateway::rate_limit::{RateLimitDecision, RateLimitRule};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait RateLimitRepository: Send + Sync {
+    /// Counts one hit on `key` under `rule`; rejected hits are not counted.
+    async fn hit(&self, key: &str, rule: &RateLimitRule) -> Result<RateLimitDecision, String>;
+}
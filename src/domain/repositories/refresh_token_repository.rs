@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+
+/// Refresh tokens minted from one login share a family; the family dies as a
+/// whole on logout or when a rotated-out token is presented again.
+#[derive(Debug, Clone)]
+pub struct RefreshTokenFamily {
+    pub family_id: String,
+    pub identity_id: String,
+    pub session_id: String,
+}
+
+#[derive(Debug)]
+pub enum RefreshTokenUse {
+    /// Unknown, expired or belonging to a revoked family.
+    Invalid,
+    /// First presentation of the token.
+    Valid(RefreshTokenFamily),
+    /// The token was already rotated out, i.e. it has been replayed.
+    Reused(RefreshTokenFamily),
+}
+
+#[async_trait]
+pub trait RefreshTokenRepository: Send + Sync {
+    /// Starts a new family for the session and returns its first token.
+    async fn create_family(
+        &self,
+        identity_id: &str,
+        session_id: &str,
+    ) -> Result<(RefreshTokenFamily, String), String>;
+    /// Stores the next token of an existing family and returns its opaque value.
+    async fn issue(&self, family: &RefreshTokenFamily) -> Result<String, String>;
+    /// Reports what `consume` would, without spending the token.
+    async fn peek(&self, token: &str) -> Result<RefreshTokenUse, String>;
+    /// Marks the token as spent, reporting whether it had been used before.
+    async fn consume(&self, token: &str) -> Result<RefreshTokenUse, String>;
+    async fn revoke_family(&self, family_id: &str) -> Result<(), String>;
+    /// Revokes every family started from the Kratos session.
+    async fn revoke_session(&self, session_id: &str) -> Result<(), String>;
+}
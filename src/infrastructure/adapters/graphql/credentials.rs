@@ -0,0 +1,49 @@
+use crate::domain::auth::context::AuthState;
+use crate::domain::auth::inputs::ClientType;
+use crate::infrastructure::adapters::kratos::kratos_client::SessionCredentials;
+use async_graphql::Context;
+
+/// Kratos session token sent by native clients via `Authorization: Bearer`
+/// or `X-Session-Token`.
+#[derive(Clone, Debug)]
+pub struct SessionToken(pub String);
+
+/// Picks the caller's Kratos credentials from the GraphQL context,
+/// preferring an explicit session token over the cookie header.
+pub fn session_credentials<'a>(ctx: &Context<'a>) -> Option<SessionCredentials<'a>> {
+    let token = ctx
+        .data_opt::<Option<SessionToken>>()
+        .and_then(|opt| opt.as_ref())
+        .map(|token| SessionCredentials::Token(token.0.as_str()));
+
+    let cookie = ctx
+        .data_opt::<Option<String>>()
+        .and_then(|opt| opt.as_ref())
+        .map(|s| SessionCredentials::Cookie(s.as_str()));
+
+    token.or(cookie)
+}
+
+/// The caller's address, resolved like the HTTP rate limiter does.
+#[derive(Clone, Debug)]
+pub struct ClientIp(pub String);
+
+pub fn client_ip<'a>(ctx: &Context<'a>) -> Option<&'a str> {
+    ctx.data_opt::<Option<ClientIp>>()
+        .and_then(|opt| opt.as_ref())
+        .map(|ip| ip.0.as_str())
+}
+
+static ANONYMOUS: AuthState = AuthState::Anonymous;
+
+/// The caller resolved by the HTTP middleware; anonymous when it did not run.
+pub fn auth_state<'a>(ctx: &Context<'a>) -> &'a AuthState {
+    ctx.data_opt::<AuthState>().unwrap_or(&ANONYMOUS)
+}
+
+/// An explicit `clientType` argument wins over the `X-Client-Type` header.
+pub fn resolve_client_type(ctx: &Context<'_>, client_type: Option<ClientType>) -> ClientType {
+    client_type
+        .or_else(|| ctx.data_opt::<ClientType>().copied())
+        .unwrap_or_default()
+}
@@ -0,0 +1,377 @@
+use crate::infrastructure::adapters::graphql::federation::planner::{
+    EntityTarget, Fetch, KEY_ALIAS_PREFIX, QueryPlan, TYPENAME_ALIAS,
+};
+use crate::infrastructure::adapters::graphql::federation::supergraph::Supergraph;
+use async_graphql::parser::types::{Selection, SelectionSet};
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use serde_json::{Map, Value, json};
+use tracing::error;
+
+/// Sends one query to one subgraph and returns its GraphQL response body.
+#[async_trait(?Send)]
+pub trait SubgraphTransport {
+    async fn execute(
+        &self,
+        subgraph: &str,
+        query: String,
+        variables: Map<String, Value>,
+    ) -> Result<Value, String>;
+}
+
+/// One hop from the data root to an object: an object key or a list index.
+#[derive(Clone)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+pub struct Executor<'a> {
+    pub supergraph: &'a Supergraph,
+    pub transport: &'a dyn SubgraphTransport,
+}
+
+impl Executor<'_> {
+    /// Runs the plan and returns the client's response: root fetches first
+    /// (concurrently for queries), then each level of entity fetches.
+    pub async fn execute(&self, plan: &QueryPlan) -> Value {
+        let variables = match serde_json::to_value(&plan.variables) {
+            Ok(Value::Object(variables)) => variables,
+            _ => Map::new(),
+        };
+
+        let mut data = Value::Object(Map::new());
+        let mut errors = Vec::new();
+
+        if plan.sequential {
+            for fetch in &plan.fetches {
+                let response = self.send(fetch, &variables, None).await;
+                merge_root_response(&mut data, &mut errors, fetch, response);
+                self.execute_entities(&fetch.children, &variables, &mut data, &mut errors)
+                    .await;
+            }
+        } else {
+            let responses = join_all(
+                plan.fetches
+                    .iter()
+                    .map(|fetch| self.send(fetch, &variables, None)),
+            )
+            .await;
+            for (fetch, response) in plan.fetches.iter().zip(responses) {
+                merge_root_response(&mut data, &mut errors, fetch, response);
+            }
+            for fetch in &plan.fetches {
+                self.execute_entities(&fetch.children, &variables, &mut data, &mut errors)
+                    .await;
+            }
+        }
+
+        let data = self.project(&plan.selection, plan.root_type, &data);
+        let mut response = json!({ "data": data });
+        if !errors.is_empty() {
+            response["errors"] = Value::Array(errors);
+        }
+        response
+    }
+
+    async fn execute_entities(
+        &self,
+        fetches: &[Fetch],
+        variables: &Map<String, Value>,
+        data: &mut Value,
+        errors: &mut Vec<Value>,
+    ) {
+        if fetches.is_empty() {
+            return;
+        }
+
+        // Representations are read for every sibling before any response is
+        // merged, so siblings can run concurrently
+        let mut batches = Vec::new();
+        for fetch in fetches {
+            let Some(target) = &fetch.entity else {
+                continue;
+            };
+
+            let mut locations = Vec::new();
+            collect_locations(data, &target.path, target, &mut Vec::new(), &mut locations);
+
+            let (locations, representations): (Vec<_>, Vec<_>) = locations
+                .into_iter()
+                .filter_map(|location| {
+                    let representation = representation(resolve(data, &location)?, target)?;
+                    Some((location, representation))
+                })
+                .unzip();
+
+            if !representations.is_empty() {
+                batches.push((fetch, locations, representations));
+            }
+        }
+
+        let responses = join_all(batches.iter().map(|(fetch, _, representations)| {
+            self.send(fetch, variables, Some(representations.clone()))
+        }))
+        .await;
+
+        for ((fetch, locations, _), response) in batches.iter().zip(responses) {
+            let response = match response {
+                Ok(response) => response,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+
+            if let Some(Value::Array(subgraph_errors)) = response.get("errors") {
+                // Paths point into `_entities`, which the client never sees
+                errors.extend(subgraph_errors.iter().cloned().map(|mut error| {
+                    if let Some(error) = error.as_object_mut() {
+                        error.remove("path");
+                    }
+                    error
+                }));
+            }
+
+            let Some(Value::Array(entities)) = response.pointer("/data/_entities") else {
+                continue;
+            };
+            for (location, entity) in locations.iter().zip(entities) {
+                if let Some(target) = resolve_mut(data, location) {
+                    merge(target, entity.clone());
+                }
+            }
+
+            Box::pin(self.execute_entities(&fetch.children, variables, data, errors)).await;
+        }
+    }
+
+    async fn send(
+        &self,
+        fetch: &Fetch,
+        variables: &Map<String, Value>,
+        representations: Option<Vec<Value>>,
+    ) -> Result<Value, Value> {
+        let mut fetch_variables: Map<String, Value> = fetch
+            .variables
+            .iter()
+            .filter_map(|name| Some((name.clone(), variables.get(name)?.clone())))
+            .collect();
+        if let Some(representations) = representations {
+            fetch_variables.insert(
+                "_representations".to_string(),
+                Value::Array(representations),
+            );
+        }
+
+        self.transport
+            .execute(&fetch.subgraph, fetch.query.clone(), fetch_variables)
+            .await
+            .map_err(|e| {
+                // Transport errors carry the subgraph's URL and response body
+                error!(error = %e, subgraph = %fetch.subgraph, "Subgraph request failed");
+                json!({
+                    "message": format!("Subgraph '{}' failed", fetch.subgraph),
+                    "extensions": { "code": "SUBGRAPH_UNAVAILABLE" }
+                })
+            })
+    }
+
+    /// Shapes merged data into exactly what the client selected, dropping
+    /// the planner's helper fields and fragments whose type does not match.
+    fn project(&self, selection: &SelectionSet, type_name: &str, value: &Value) -> Value {
+        match value {
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.project(selection, type_name, item))
+                    .collect(),
+            ),
+            Value::Object(object) => {
+                let mut out = Map::new();
+                self.project_into(selection, type_name, object, &mut out);
+                Value::Object(out)
+            }
+            other => other.clone(),
+        }
+    }
+
+    fn project_into(
+        &self,
+        selection: &SelectionSet,
+        type_name: &str,
+        object: &Map<String, Value>,
+        out: &mut Map<String, Value>,
+    ) {
+        let concrete = object
+            .get(TYPENAME_ALIAS)
+            .and_then(Value::as_str)
+            .unwrap_or(type_name);
+
+        for item in &selection.items {
+            match &item.node {
+                Selection::Field(field) => {
+                    let field = &field.node;
+                    let key = field.response_key().node.to_string();
+                    let name = field.name.node.as_str();
+
+                    let value = if name == "__typename" {
+                        Value::String(concrete.to_string())
+                    } else {
+                        let value = object.get(&key).cloned().unwrap_or(Value::Null);
+                        match self.supergraph.field(type_name, name) {
+                            Some(info) if !field.selection_set.node.items.is_empty() => {
+                                self.project(&field.selection_set.node, &info.type_name, &value)
+                            }
+                            _ => value,
+                        }
+                    };
+
+                    match out.get_mut(&key) {
+                        Some(existing) => merge(existing, value),
+                        None => {
+                            out.insert(key, value);
+                        }
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    let condition = fragment
+                        .node
+                        .type_condition
+                        .as_ref()
+                        .map(|condition| condition.node.on.node.as_str());
+                    if condition
+                        .is_none_or(|condition| self.supergraph.type_matches(condition, concrete))
+                    {
+                        self.project_into(
+                            &fragment.node.selection_set.node,
+                            condition.unwrap_or(type_name),
+                            object,
+                            out,
+                        );
+                    }
+                }
+                Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+}
+
+fn merge_root_response(
+    data: &mut Value,
+    errors: &mut Vec<Value>,
+    fetch: &Fetch,
+    response: Result<Value, Value>,
+) {
+    let mut response = match response {
+        Ok(response) => response,
+        Err(error) => {
+            errors.push(error);
+            return;
+        }
+    };
+
+    if let Some(Value::Array(subgraph_errors)) = response.get_mut("errors") {
+        errors.append(subgraph_errors);
+    }
+
+    match response.get_mut("data").map(Value::take) {
+        Some(subgraph_data @ Value::Object(_)) => merge(data, subgraph_data),
+        Some(_) => {}
+        None if response.get("errors").is_none() => errors.push(json!({
+            "message": format!("Subgraph '{}' returned no data", fetch.subgraph),
+            "extensions": { "code": "SERVICE_UNAVAILABLE" }
+        })),
+        None => {}
+    }
+}
+
+/// Finds every object of the target type under `path`, flattening lists.
+fn collect_locations(
+    value: &Value,
+    path: &[String],
+    target: &EntityTarget,
+    current: &mut Vec<Step>,
+    out: &mut Vec<Vec<Step>>,
+) {
+    match value {
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                current.push(Step::Index(index));
+                collect_locations(item, path, target, current, out);
+                current.pop();
+            }
+        }
+        Value::Object(object) => match path.split_first() {
+            Some((key, rest)) => {
+                if let Some(child) = object.get(key) {
+                    current.push(Step::Key(key.clone()));
+                    collect_locations(child, rest, target, current, out);
+                    current.pop();
+                }
+            }
+            None => {
+                if object.get(TYPENAME_ALIAS).and_then(Value::as_str)
+                    == Some(target.type_name.as_str())
+                {
+                    out.push(current.clone());
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+/// `{ "__typename": T, key: value, ... }` from the aliased key fields, or
+/// `None` when a key is missing (e.g. the object resolved to an error).
+fn representation(object: &Value, target: &EntityTarget) -> Option<Value> {
+    let mut representation = Map::new();
+    representation.insert(
+        "__typename".to_string(),
+        Value::String(target.type_name.clone()),
+    );
+    for key_field in &target.key_fields {
+        let value = object.get(format!("{}{}", KEY_ALIAS_PREFIX, key_field))?;
+        if value.is_null() {
+            return None;
+        }
+        representation.insert(key_field.clone(), value.clone());
+    }
+    Some(Value::Object(representation))
+}
+
+fn resolve<'a>(value: &'a Value, steps: &[Step]) -> Option<&'a Value> {
+    steps.iter().try_fold(value, |value, step| match step {
+        Step::Key(key) => value.get(key),
+        Step::Index(index) => value.get(*index),
+    })
+}
+
+fn resolve_mut<'a>(value: &'a mut Value, steps: &[Step]) -> Option<&'a mut Value> {
+    steps.iter().try_fold(value, |value, step| match step {
+        Step::Key(key) => value.get_mut(key),
+        Step::Index(index) => value.get_mut(*index),
+    })
+}
+
+/// Deep-merges objects; anything else is replaced by `source`.
+fn merge(target: &mut Value, source: Value) {
+    match (target, source) {
+        (Value::Object(target), Value::Object(source)) => {
+            for (key, value) in source {
+                match target.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(source)) if target.len() == source.len() => {
+            for (existing, value) in target.iter_mut().zip(source) {
+                merge(existing, value);
+            }
+        }
+        (target, source) => *target = source,
+    }
+}
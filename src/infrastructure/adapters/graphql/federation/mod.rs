@@ -0,0 +1,435 @@
+//! Supergraph over downstream GraphQL subgraphs (Apollo Federation v2
+//! entities). The gateway's own schema joins as the `gateway` subgraph for
+//! its root fields; operations it answers alone skip planning entirely.
+//!
+//! Introspection is not composed: `__schema` and `__type` are answered by
+//! the gateway's own schema, so they describe neither subgraph-only types
+//! nor fields that subgraphs add to shared ones. Clients should take the
+//! composed schema from the subgraphs' SDL instead.
+//!
+//! Also not supported: subscriptions, `@requires`/`@provides`, nested `@key`
+//! field sets and client aliases starting with `_gw_`, which the planner
+//! reserves for its own fields.
+
+pub mod executor;
+pub mod planner;
+mod printer;
+pub mod supergraph;
+
+use crate::domain::gateway::subgraphs::SubgraphConfig;
+use crate::infrastructure::adapters::graphql::federation::executor::{Executor, SubgraphTransport};
+use crate::infrastructure::adapters::graphql::federation::planner::QueryPlan;
+use crate::infrastructure::adapters::graphql::federation::supergraph::{
+    LOCAL_SUBGRAPH, Supergraph,
+};
+use crate::infrastructure::adapters::http::upstream_client::UpstreamClient;
+use async_graphql::Variables;
+use tracing::info;
+
+const SDL_QUERY: &str = r#"{"query":"{ _service { sdl } }"}"#;
+
+pub struct Federation {
+    supergraph: Supergraph,
+    subgraphs: Vec<SubgraphConfig>,
+}
+
+impl Federation {
+    /// Fetches every subgraph's SDL and composes it with the gateway's own.
+    pub async fn load(
+        subgraphs: Vec<SubgraphConfig>,
+        local_sdl: String,
+        upstream_client: &UpstreamClient,
+    ) -> Result<Self, String> {
+        let mut sdls = vec![(LOCAL_SUBGRAPH.to_string(), local_sdl)];
+
+        for subgraph in &subgraphs {
+            if subgraph.name == LOCAL_SUBGRAPH {
+                return Err(format!("Subgraph name '{}' is reserved", LOCAL_SUBGRAPH));
+            }
+
+            let sdl = fetch_sdl(subgraph, upstream_client)
+                .await
+                .map_err(|e| format!("Subgraph '{}': {}", subgraph.name, e))?;
+            info!(subgraph = %subgraph.name, url = %subgraph.url, "Subgraph schema loaded");
+            sdls.push((subgraph.name.clone(), sdl));
+        }
+
+        Ok(Self {
+            supergraph: Supergraph::compose(&sdls)?,
+            subgraphs,
+        })
+    }
+
+    /// `Ok(None)` when the gateway's schema can run the operation by itself.
+    pub fn plan(
+        &self,
+        query: &str,
+        operation_name: Option<&str>,
+        variables: &Variables,
+    ) -> Result<Option<QueryPlan>, String> {
+        let plan = planner::plan(&self.supergraph, query, operation_name, variables)?;
+        Ok(plan.filter(|plan| !plan.is_local()))
+    }
+
+    pub async fn execute(
+        &self,
+        plan: &QueryPlan,
+        transport: &dyn SubgraphTransport,
+    ) -> serde_json::Value {
+        Executor {
+            supergraph: &self.supergraph,
+            transport,
+        }
+        .execute(plan)
+        .await
+    }
+
+    pub fn subgraph(&self, name: &str) -> Option<&SubgraphConfig> {
+        self.subgraphs.iter().find(|subgraph| subgraph.name == name)
+    }
+}
+
+async fn fetch_sdl(
+    subgraph: &SubgraphConfig,
+    upstream_client: &UpstreamClient,
+) -> Result<String, String> {
+    let response = upstream_client
+        .send(
+            "POST",
+            &subgraph.url,
+            vec![("content-type".to_string(), b"application/json".to_vec())],
+            SDL_QUERY.as_bytes().to_vec(),
+            subgraph.timeout(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status != 200 {
+        return Err(format!("SDL query answered {}", response.status));
+    }
+
+    let body: serde_json::Value = serde_json::from_slice(&response.body)
+        .map_err(|e| format!("Invalid SDL response: {}", e))?;
+    body.pointer("/data/_service/sdl")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| "Response has no `_service.sdl`; is federation enabled?".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{
+        ComplexObject, EmptyMutation, EmptySubscription, ID, Object, Request, Schema, SimpleObject,
+    };
+    use async_trait::async_trait;
+    use serde_json::{Map, Value, json};
+    use std::cell::RefCell;
+
+    const USERS: [(&str, &str); 2] = [("1", "Ada"), ("2", "Grace")];
+    const REVIEWS: [(&str, &str, &str); 2] = [("10", "Great", "1"), ("11", "Solid", "2")];
+
+    #[derive(SimpleObject)]
+    #[graphql(complex)]
+    struct User {
+        id: ID,
+        name: String,
+    }
+
+    #[ComplexObject]
+    impl User {
+        async fn email(&self) -> async_graphql::Result<Option<String>> {
+            Err("Email is private".into())
+        }
+    }
+
+    struct AccountsQuery;
+
+    #[Object(name = "Query")]
+    impl AccountsQuery {
+        async fn me(&self) -> User {
+            User {
+                id: ID::from("1"),
+                name: "Ada".to_string(),
+            }
+        }
+
+        #[graphql(entity)]
+        async fn find_user_by_id(&self, id: ID) -> async_graphql::Result<User> {
+            let (_, name) = USERS
+                .into_iter()
+                .find(|(user, _)| *user == id.as_str())
+                .ok_or("No such user")?;
+            Ok(User {
+                id,
+                name: name.to_string(),
+            })
+        }
+    }
+
+    #[derive(SimpleObject)]
+    struct Review {
+        id: ID,
+        body: String,
+        author: ReviewAuthor,
+    }
+
+    #[derive(SimpleObject)]
+    #[graphql(name = "User", complex)]
+    struct ReviewAuthor {
+        id: ID,
+    }
+
+    #[ComplexObject]
+    impl ReviewAuthor {
+        async fn reviews(&self) -> Vec<Review> {
+            reviews()
+                .into_iter()
+                .filter(|review| review.author.id == self.id)
+                .collect()
+        }
+    }
+
+    fn reviews() -> Vec<Review> {
+        REVIEWS
+            .into_iter()
+            .map(|(id, body, author)| Review {
+                id: ID::from(id),
+                body: body.to_string(),
+                author: ReviewAuthor {
+                    id: ID::from(author),
+                },
+            })
+            .collect()
+    }
+
+    struct ReviewsQuery;
+
+    #[Object(name = "Query")]
+    impl ReviewsQuery {
+        async fn top_reviews(&self) -> Vec<Review> {
+            reviews()
+        }
+
+        async fn ratings(&self) -> async_graphql::Result<Option<String>> {
+            Err("Ratings are down".into())
+        }
+
+        #[graphql(entity)]
+        async fn find_author_by_id(&self, id: ID) -> ReviewAuthor {
+            ReviewAuthor { id }
+        }
+    }
+
+    type AccountsSchema = Schema<AccountsQuery, EmptyMutation, EmptySubscription>;
+    type ReviewsSchema = Schema<ReviewsQuery, EmptyMutation, EmptySubscription>;
+    type Call = (String, String, Map<String, Value>);
+
+    /// Runs `accounts` and `reviews` in-process and records every request.
+    struct InProcess {
+        accounts: AccountsSchema,
+        reviews: ReviewsSchema,
+        down: Option<&'static str>,
+        calls: RefCell<Vec<Call>>,
+    }
+
+    #[async_trait(?Send)]
+    impl SubgraphTransport for InProcess {
+        async fn execute(
+            &self,
+            subgraph: &str,
+            query: String,
+            variables: Map<String, Value>,
+        ) -> Result<Value, String> {
+            self.calls
+                .borrow_mut()
+                .push((subgraph.to_string(), query.clone(), variables.clone()));
+            if self.down == Some(subgraph) {
+                return Err("connection refused".to_string());
+            }
+
+            let request =
+                Request::new(query).variables(Variables::from_json(Value::Object(variables)));
+            let response = match subgraph {
+                "accounts" => self.accounts.execute(request).await,
+                "reviews" => self.reviews.execute(request).await,
+                _ => return Err(format!("Unknown subgraph '{}'", subgraph)),
+            };
+            serde_json::to_value(response).map_err(|e| e.to_string())
+        }
+    }
+
+    fn subgraphs(down: Option<&'static str>) -> (Federation, InProcess) {
+        let accounts = Schema::build(AccountsQuery, EmptyMutation, EmptySubscription)
+            .enable_federation()
+            .finish();
+        let reviews = Schema::build(ReviewsQuery, EmptyMutation, EmptySubscription)
+            .enable_federation()
+            .finish();
+
+        let options = async_graphql::SDLExportOptions::new().federation();
+        let supergraph = Supergraph::compose(&[
+            ("accounts".to_string(), accounts.sdl_with_options(options)),
+            ("reviews".to_string(), reviews.sdl_with_options(options)),
+        ])
+        .unwrap();
+
+        let federation = Federation {
+            supergraph,
+            subgraphs: Vec::new(),
+        };
+        let transport = InProcess {
+            accounts,
+            reviews,
+            down,
+            calls: RefCell::new(Vec::new()),
+        };
+        (federation, transport)
+    }
+
+    async fn run(federation: &Federation, transport: &InProcess, query: &str) -> Value {
+        let plan = federation
+            .plan(query, None, &Variables::default())
+            .unwrap()
+            .expect("operation needs subgraphs");
+        federation.execute(&plan, transport).await
+    }
+
+    #[actix_web::test]
+    async fn joins_entities_across_subgraphs_in_both_directions() {
+        let (federation, transport) = subgraphs(None);
+
+        let response = run(&federation, &transport, "{ me { name reviews { body } } }").await;
+        assert_eq!(
+            response,
+            json!({ "data": { "me": { "name": "Ada", "reviews": [{ "body": "Great" }] } } })
+        );
+
+        let response = run(
+            &federation,
+            &transport,
+            "{ topReviews { body author { name } } }",
+        )
+        .await;
+        assert_eq!(
+            response["data"]["topReviews"],
+            json!([
+                { "body": "Great", "author": { "name": "Ada" } },
+                { "body": "Solid", "author": { "name": "Grace" } },
+            ])
+        );
+    }
+
+    #[actix_web::test]
+    async fn resolves_entities_by_their_key_in_one_batch() {
+        let (federation, transport) = subgraphs(None);
+
+        run(
+            &federation,
+            &transport,
+            "{ topReviews { author { name } } }",
+        )
+        .await;
+
+        let calls = transport.calls.borrow();
+        assert_eq!(calls.len(), 2);
+        let (subgraph, query, variables) = &calls[1];
+        assert_eq!(subgraph, "accounts");
+        assert!(query.contains("_entities(representations: $_representations)"));
+        assert_eq!(
+            variables["_representations"],
+            json!([
+                { "__typename": "User", "id": "1" },
+                { "__typename": "User", "id": "2" },
+            ])
+        );
+    }
+
+    #[actix_web::test]
+    async fn keeps_client_aliases_and_fragments() {
+        let (federation, transport) = subgraphs(None);
+
+        let response = run(
+            &federation,
+            &transport,
+            "query {
+                first: topReviews { ...ReviewFields }
+                viewer: me { ... on User { handle: name } }
+            }
+            fragment ReviewFields on Review {
+                text: body
+                writer: author { handle: name __typename }
+            }",
+        )
+        .await;
+
+        assert_eq!(
+            response["data"]["first"][0],
+            json!({ "text": "Great", "writer": { "handle": "Ada", "__typename": "User" } })
+        );
+        assert_eq!(response["data"]["viewer"], json!({ "handle": "Ada" }));
+        assert!(response.get("errors").is_none());
+    }
+
+    #[actix_web::test]
+    async fn reports_subgraph_errors_next_to_the_data_that_resolved() {
+        let (federation, transport) = subgraphs(None);
+
+        let response = run(&federation, &transport, "{ me { name } ratings }").await;
+        assert_eq!(
+            response["data"],
+            json!({ "me": { "name": "Ada" }, "ratings": null })
+        );
+        assert_eq!(response["errors"][0]["message"], "Ratings are down");
+        assert_eq!(response["errors"][0]["path"], json!(["ratings"]));
+
+        let response = run(
+            &federation,
+            &transport,
+            "{ topReviews { body author { email } } }",
+        )
+        .await;
+        assert_eq!(
+            response["data"]["topReviews"][0],
+            json!({ "body": "Great", "author": { "email": null } })
+        );
+        // Entity errors point into `_entities`, so they lose their path
+        assert_eq!(response["errors"][0]["message"], "Email is private");
+        assert!(response["errors"][0].get("path").is_none());
+    }
+
+    #[actix_web::test]
+    async fn reports_an_unreachable_subgraph() {
+        let (federation, transport) = subgraphs(Some("accounts"));
+
+        let response = run(
+            &federation,
+            &transport,
+            "{ topReviews { body author { name } } }",
+        )
+        .await;
+
+        assert_eq!(response["data"]["topReviews"][0]["body"], "Great");
+        assert_eq!(
+            response["errors"][0],
+            json!({
+                "message": "Subgraph 'accounts' failed",
+                "extensions": { "code": "SUBGRAPH_UNAVAILABLE" }
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_aliases_in_the_reserved_namespace() {
+        let (federation, _) = subgraphs(None);
+
+        let error = federation
+            .plan(
+                "{ topReviews { _gw_typename: body } }",
+                None,
+                &Variables::default(),
+            )
+            .unwrap_err();
+        assert!(error.contains("reserved"));
+    }
+}
@@ -0,0 +1,492 @@
+use crate::infrastructure::adapters::graphql::federation::printer::{
+    print_entities_operation, print_operation,
+};
+use crate::infrastructure::adapters::graphql::federation::supergraph::{
+    LOCAL_SUBGRAPH, MUTATION_TYPE, QUERY_TYPE, Supergraph,
+};
+use async_graphql::parser::parse_query;
+use async_graphql::parser::types::{
+    Directive, DocumentOperations, ExecutableDocument, Field, InlineFragment, OperationDefinition,
+    OperationType, Selection, SelectionSet, VariableDefinition,
+};
+use async_graphql::{Name, Pos, Positioned, Value, Variables};
+
+/// Response keys the planner adds to subgraph queries start with this;
+/// clients may not use it, so their fields never collide with ours.
+pub const RESERVED_ALIAS_PREFIX: &str = "_gw_";
+
+/// Alias of the `__typename` added to every composite selection sent to a
+/// subgraph, used to match type conditions and entity fetches.
+pub const TYPENAME_ALIAS: &str = "_gw_typename";
+
+/// Prefix of the aliases under which entity key fields are fetched.
+pub const KEY_ALIAS_PREFIX: &str = "_gw_key_";
+
+/// A subgraph request and the requests that need its result.
+#[derive(Debug)]
+pub struct Fetch {
+    pub subgraph: String,
+    pub query: String,
+    /// Client variables the query uses.
+    pub variables: Vec<String>,
+    /// Set for `_entities` fetches.
+    pub entity: Option<EntityTarget>,
+    pub children: Vec<Fetch>,
+}
+
+/// Where an entity fetch takes its representations from: every object of
+/// `type_name` at `path` (response keys, lists flattened) in the merged data.
+#[derive(Debug)]
+pub struct EntityTarget {
+    pub path: Vec<String>,
+    pub type_name: String,
+    pub key_fields: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct QueryPlan {
+    pub root_type: &'static str,
+    /// Mutation fields run in order, so their fetches do too.
+    pub sequential: bool,
+    pub fetches: Vec<Fetch>,
+    /// The client's selection with fragments inlined and `@skip`/`@include`
+    /// applied, which shapes the merged response.
+    pub selection: SelectionSet,
+    /// Client variables with the operation's defaults filled in.
+    pub variables: Variables,
+}
+
+impl QueryPlan {
+    /// Whether the gateway's own schema answers the whole operation.
+    pub fn is_local(&self) -> bool {
+        matches!(
+            self.fetches.as_slice(),
+            [fetch] if fetch.subgraph == LOCAL_SUBGRAPH && fetch.children.is_empty()
+        )
+    }
+}
+
+/// Plans `query` over the supergraph. `Ok(None)` means the document could
+/// not be read, which the gateway's schema reports in its usual form.
+pub fn plan(
+    supergraph: &Supergraph,
+    query: &str,
+    operation_name: Option<&str>,
+    variables: &Variables,
+) -> Result<Option<QueryPlan>, String> {
+    let Ok(document) = parse_query(query) else {
+        return Ok(None);
+    };
+    let Some(operation) = find_operation(&document, operation_name) else {
+        return Ok(None);
+    };
+    let operation = &operation.node;
+
+    let (root_type, sequential, keyword) = match operation.ty {
+        OperationType::Query => (QUERY_TYPE, false, "query"),
+        OperationType::Mutation => (MUTATION_TYPE, true, "mutation"),
+        OperationType::Subscription => {
+            return Err("Subscriptions are not supported by the gateway".to_string());
+        }
+    };
+
+    let mut variables = variables.clone();
+    for definition in &operation.variable_definitions {
+        let name = &definition.node.name.node;
+        if let Some(default) = &definition.node.default_value
+            && !variables.contains_key(name)
+        {
+            variables.insert(name.clone(), default.node.clone());
+        }
+    }
+
+    let normalizer = Normalizer {
+        document: &document,
+        variables: &variables,
+    };
+    let selection = normalizer.normalize(&operation.selection_set.node, &mut Vec::new())?;
+
+    let mut root_fields = Vec::new();
+    flatten_root(&selection, &mut root_fields);
+
+    // Queries batch every field of a subgraph into one fetch; mutations only
+    // batch consecutive fields so the execution order is kept
+    let mut groups: Vec<(String, Vec<Positioned<Selection>>)> = Vec::new();
+    for field in root_fields {
+        let Selection::Field(inner) = &field.node else {
+            continue;
+        };
+        let owner = root_owner(supergraph, root_type, &inner.node)?;
+
+        let group = if sequential {
+            groups.last_mut().filter(|(subgraph, _)| *subgraph == owner)
+        } else {
+            groups.iter_mut().find(|(subgraph, _)| *subgraph == owner)
+        };
+        match group {
+            Some((_, items)) => items.push(field),
+            None => groups.push((owner, vec![field])),
+        }
+    }
+
+    let planner = Planner {
+        supergraph,
+        variable_definitions: &operation.variable_definitions,
+    };
+    let fetches = groups
+        .into_iter()
+        .map(|(subgraph, items)| {
+            let (selection, children) =
+                planner.plan_selection(&SelectionSet { items }, root_type, &subgraph, &[])?;
+            let (query, variables) =
+                print_operation(keyword, &operation.variable_definitions, &selection);
+            Ok(Fetch {
+                subgraph,
+                query,
+                variables,
+                entity: None,
+                children,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Some(QueryPlan {
+        root_type,
+        sequential,
+        fetches,
+        selection,
+        variables,
+    }))
+}
+
+fn find_operation<'a>(
+    document: &'a ExecutableDocument,
+    operation_name: Option<&str>,
+) -> Option<&'a Positioned<OperationDefinition>> {
+    match (&document.operations, operation_name) {
+        (DocumentOperations::Single(operation), _) => Some(operation),
+        (DocumentOperations::Multiple(operations), Some(name)) => operations.get(name),
+        (DocumentOperations::Multiple(operations), None) if operations.len() == 1 => {
+            operations.values().next()
+        }
+        (DocumentOperations::Multiple(_), None) => None,
+    }
+}
+
+/// Root fields go to the gateway whenever it can answer them, and otherwise
+/// to the first subgraph defining them. `__schema` and `__type` are answered
+/// from the gateway's own schema, so subgraph-only types are not in them.
+fn root_owner(supergraph: &Supergraph, root_type: &str, field: &Field) -> Result<String, String> {
+    let name = field.name.node.as_str();
+    if name.starts_with("__") {
+        return Ok(LOCAL_SUBGRAPH.to_string());
+    }
+
+    let info = supergraph
+        .field(root_type, name)
+        .ok_or_else(|| unknown_field(name, root_type))?;
+
+    info.owners
+        .iter()
+        .find(|owner| *owner == LOCAL_SUBGRAPH)
+        .or_else(|| info.owners.first())
+        .cloned()
+        .ok_or_else(|| format!("No subgraph resolves '{}.{}'", root_type, name))
+}
+
+/// Root type conditions always hold, so root fragments are flattened.
+fn flatten_root(selection: &SelectionSet, out: &mut Vec<Positioned<Selection>>) {
+    for item in &selection.items {
+        match &item.node {
+            Selection::InlineFragment(fragment) => {
+                flatten_root(&fragment.node.selection_set.node, out)
+            }
+            _ => out.push(item.clone()),
+        }
+    }
+}
+
+fn unknown_field(field: &str, type_name: &str) -> String {
+    format!("Cannot query field '{}' on type '{}'", field, type_name)
+}
+
+/// Inlines named fragments and drops selections excluded by `@skip` or
+/// `@include`.
+struct Normalizer<'a> {
+    document: &'a ExecutableDocument,
+    variables: &'a Variables,
+}
+
+impl Normalizer<'_> {
+    fn normalize(
+        &self,
+        selection: &SelectionSet,
+        spreads: &mut Vec<Name>,
+    ) -> Result<SelectionSet, String> {
+        let mut items = Vec::new();
+
+        for item in &selection.items {
+            if !self.is_included(item.node.directives())? {
+                continue;
+            }
+
+            let normalized = match &item.node {
+                Selection::Field(field) => {
+                    let key = field.node.response_key().node.as_str();
+                    if key.starts_with(RESERVED_ALIAS_PREFIX) {
+                        return Err(format!(
+                            "Alias '{}' is reserved: '{}' names are the gateway's own",
+                            key, RESERVED_ALIAS_PREFIX
+                        ));
+                    }
+
+                    let mut field = field.clone();
+                    field.node.directives = without_conditions(&field.node.directives);
+                    field.node.selection_set.node =
+                        self.normalize(&field.node.selection_set.node, spreads)?;
+                    Selection::Field(field)
+                }
+                Selection::InlineFragment(fragment) => {
+                    let mut fragment = fragment.clone();
+                    fragment.node.directives = without_conditions(&fragment.node.directives);
+                    fragment.node.selection_set.node =
+                        self.normalize(&fragment.node.selection_set.node, spreads)?;
+                    Selection::InlineFragment(fragment)
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = &spread.node.fragment_name.node;
+                    let definition = self
+                        .document
+                        .fragments
+                        .get(name)
+                        .ok_or_else(|| format!("Unknown fragment '{}'", name))?;
+                    if spreads.contains(name) {
+                        return Err(format!("Fragment '{}' spreads itself", name));
+                    }
+
+                    spreads.push(name.clone());
+                    let selection_set =
+                        self.normalize(&definition.node.selection_set.node, spreads)?;
+                    spreads.pop();
+
+                    Selection::InlineFragment(Positioned::new(
+                        InlineFragment {
+                            type_condition: Some(definition.node.type_condition.clone()),
+                            directives: without_conditions(&spread.node.directives),
+                            selection_set: Positioned::new(selection_set, spread.pos),
+                        },
+                        spread.pos,
+                    ))
+                }
+            };
+            items.push(Positioned::new(normalized, item.pos));
+        }
+
+        Ok(SelectionSet { items })
+    }
+
+    fn is_included(&self, directives: &[Positioned<Directive>]) -> Result<bool, String> {
+        for directive in directives {
+            let directive = &directive.node;
+            let skip_when = match directive.name.node.as_str() {
+                "skip" => true,
+                "include" => false,
+                _ => continue,
+            };
+
+            let condition = directive
+                .get_argument("if")
+                .ok_or_else(|| format!("@{} needs an `if` argument", directive.name.node))?
+                .node
+                .clone()
+                .into_const_with(|name| {
+                    self.variables
+                        .get(&name)
+                        .cloned()
+                        .ok_or_else(|| format!("Variable '${}' is not defined", name))
+                })?;
+
+            match condition {
+                Value::Boolean(value) if value == skip_when => return Ok(false),
+                Value::Boolean(_) => {}
+                _ => {
+                    return Err(format!("@{}(if:) must be a Boolean", directive.name.node));
+                }
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+fn without_conditions(directives: &[Positioned<Directive>]) -> Vec<Positioned<Directive>> {
+    directives
+        .iter()
+        .filter(|directive| !matches!(directive.node.name.node.as_str(), "skip" | "include"))
+        .cloned()
+        .collect()
+}
+
+/// Splits a normalized selection between the subgraph that resolves the
+/// parent object and entity fetches for the fields it does not own.
+struct Planner<'a> {
+    supergraph: &'a Supergraph,
+    variable_definitions: &'a [Positioned<VariableDefinition>],
+}
+
+impl Planner<'_> {
+    fn plan_selection(
+        &self,
+        selection: &SelectionSet,
+        parent_type: &str,
+        subgraph: &str,
+        path: &[String],
+    ) -> Result<(SelectionSet, Vec<Fetch>), String> {
+        let mut items = Vec::new();
+        let mut children = Vec::new();
+        let mut deferred: Vec<(String, Vec<Positioned<Selection>>)> = Vec::new();
+
+        for item in &selection.items {
+            match &item.node {
+                Selection::Field(field) => {
+                    let name = field.node.name.node.as_str();
+                    // Introspection only reaches the gateway's own schema
+                    if name.starts_with("__") {
+                        items.push(item.clone());
+                        continue;
+                    }
+
+                    let info = self
+                        .supergraph
+                        .field(parent_type, name)
+                        .ok_or_else(|| unknown_field(name, parent_type))?;
+
+                    if !info.owners.iter().any(|owner| owner == subgraph) {
+                        let owner = self.entity_owner(parent_type, &info.owners, name)?;
+                        match deferred.iter_mut().find(|(other, _)| *other == owner) {
+                            Some((_, fields)) => fields.push(item.clone()),
+                            None => deferred.push((owner, vec![item.clone()])),
+                        }
+                        continue;
+                    }
+
+                    if !self.supergraph.is_composite(&info.type_name) {
+                        items.push(item.clone());
+                        continue;
+                    }
+
+                    let mut field_path = path.to_vec();
+                    field_path.push(field.node.response_key().node.to_string());
+                    let (mut nested, nested_children) = self.plan_selection(
+                        &field.node.selection_set.node,
+                        &info.type_name,
+                        subgraph,
+                        &field_path,
+                    )?;
+                    nested
+                        .items
+                        .push(helper_field(TYPENAME_ALIAS, "__typename"));
+                    children.extend(nested_children);
+
+                    let mut field = field.clone();
+                    field.node.selection_set.node = nested;
+                    items.push(Positioned::new(Selection::Field(field), item.pos));
+                }
+                Selection::InlineFragment(fragment) => {
+                    let condition = fragment
+                        .node
+                        .type_condition
+                        .as_ref()
+                        .map_or(parent_type, |condition| condition.node.on.node.as_str());
+                    let (nested, nested_children) = self.plan_selection(
+                        &fragment.node.selection_set.node,
+                        condition,
+                        subgraph,
+                        path,
+                    )?;
+                    children.extend(nested_children);
+
+                    let mut fragment = fragment.clone();
+                    fragment.node.selection_set.node = nested;
+                    items.push(Positioned::new(
+                        Selection::InlineFragment(fragment),
+                        item.pos,
+                    ));
+                }
+                Selection::FragmentSpread(spread) => {
+                    return Err(format!(
+                        "Fragment '{}' was not inlined",
+                        spread.node.fragment_name.node
+                    ));
+                }
+            }
+        }
+
+        for (owner, fields) in deferred {
+            let key_fields = self
+                .supergraph
+                .entity_key(parent_type, &owner)
+                .ok_or_else(|| {
+                    format!(
+                        "'{}' is not an entity in subgraph '{}', so its fields there cannot be joined",
+                        parent_type, owner
+                    )
+                })?
+                .to_vec();
+
+            for key_field in &key_fields {
+                let alias = format!("{}{}", KEY_ALIAS_PREFIX, key_field);
+                items.push(helper_field(&alias, key_field));
+            }
+
+            let (nested, nested_children) =
+                self.plan_selection(&SelectionSet { items: fields }, parent_type, &owner, path)?;
+
+            let (query, variables) =
+                print_entities_operation(parent_type, self.variable_definitions, &nested);
+
+            children.push(Fetch {
+                subgraph: owner,
+                query,
+                variables,
+                entity: Some(EntityTarget {
+                    path: path.to_vec(),
+                    type_name: parent_type.to_string(),
+                    key_fields,
+                }),
+                children: nested_children,
+            });
+        }
+
+        Ok((SelectionSet { items }, children))
+    }
+
+    /// Prefers a subgraph that can resolve `parent_type` as an entity.
+    fn entity_owner(
+        &self,
+        parent_type: &str,
+        owners: &[String],
+        field: &str,
+    ) -> Result<String, String> {
+        owners
+            .iter()
+            .find(|owner| self.supergraph.entity_key(parent_type, owner).is_some())
+            .or_else(|| owners.first())
+            .cloned()
+            .ok_or_else(|| format!("No subgraph resolves '{}.{}'", parent_type, field))
+    }
+}
+
+fn helper_field(alias: &str, name: &str) -> Positioned<Selection> {
+    positioned(Selection::Field(positioned(Field {
+        alias: Some(positioned(Name::new(alias))),
+        name: positioned(Name::new(name)),
+        arguments: Vec::new(),
+        directives: Vec::new(),
+        selection_set: positioned(SelectionSet::default()),
+    })))
+}
+
+fn positioned<T>(node: T) -> Positioned<T> {
+    Positioned::new(node, Pos::default())
+}
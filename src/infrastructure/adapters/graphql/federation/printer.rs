@@ -0,0 +1,163 @@
+use async_graphql::Positioned;
+use async_graphql::parser::types::{Directive, Selection, SelectionSet, VariableDefinition};
+use std::convert::Infallible;
+use std::fmt::Write;
+
+/// Prints `keyword($a: T, ...) { selection }`, declaring only the variables
+/// the selection uses. Returns the query and those variable names.
+pub fn print_operation(
+    keyword: &str,
+    variable_definitions: &[Positioned<VariableDefinition>],
+    selection: &SelectionSet,
+) -> (String, Vec<String>) {
+    let mut body = String::new();
+    print_selection_set(selection, &mut body);
+    wrap_operation(keyword, Vec::new(), variable_definitions, selection, body)
+}
+
+/// Prints an `_entities` query resolving `selection` on `type_name`.
+pub fn print_entities_operation(
+    type_name: &str,
+    variable_definitions: &[Positioned<VariableDefinition>],
+    selection: &SelectionSet,
+) -> (String, Vec<String>) {
+    let mut body = format!(
+        "{{ _entities(representations: $_representations) {{ ... on {} ",
+        type_name
+    );
+    print_selection_set(selection, &mut body);
+    body.push_str(" } }");
+
+    let declarations = vec!["$_representations: [_Any!]!".to_string()];
+    wrap_operation("query", declarations, variable_definitions, selection, body)
+}
+
+fn wrap_operation(
+    keyword: &str,
+    mut declarations: Vec<String>,
+    variable_definitions: &[Positioned<VariableDefinition>],
+    selection: &SelectionSet,
+    body: String,
+) -> (String, Vec<String>) {
+    let mut used = Vec::new();
+    collect_variables(selection, &mut used);
+
+    for definition in variable_definitions {
+        let definition = &definition.node;
+        if !used
+            .iter()
+            .any(|name| name == definition.name.node.as_str())
+        {
+            continue;
+        }
+
+        let mut declaration = format!("${}: {}", definition.name.node, definition.var_type.node);
+        if let Some(default) = &definition.default_value {
+            write!(declaration, " = {}", default.node).ok();
+        }
+        declarations.push(declaration);
+    }
+
+    let mut query = keyword.to_string();
+    if !declarations.is_empty() {
+        write!(query, "({})", declarations.join(", ")).ok();
+    }
+    query.push(' ');
+    query.push_str(&body);
+
+    (query, used)
+}
+
+fn print_selection_set(selection: &SelectionSet, out: &mut String) {
+    out.push('{');
+    for item in &selection.items {
+        out.push(' ');
+        match &item.node {
+            Selection::Field(field) => {
+                let field = &field.node;
+                if let Some(alias) = &field.alias {
+                    write!(out, "{}: ", alias.node).ok();
+                }
+                out.push_str(&field.name.node);
+                if !field.arguments.is_empty() {
+                    let arguments: Vec<String> = field
+                        .arguments
+                        .iter()
+                        .map(|(name, value)| format!("{}: {}", name.node, value.node))
+                        .collect();
+                    write!(out, "({})", arguments.join(", ")).ok();
+                }
+                print_directives(&field.directives, out);
+                if !field.selection_set.node.items.is_empty() {
+                    out.push(' ');
+                    print_selection_set(&field.selection_set.node, out);
+                }
+            }
+            Selection::InlineFragment(fragment) => {
+                let fragment = &fragment.node;
+                out.push_str("...");
+                if let Some(condition) = &fragment.type_condition {
+                    write!(out, " on {}", condition.node.on.node).ok();
+                }
+                print_directives(&fragment.directives, out);
+                out.push(' ');
+                print_selection_set(&fragment.selection_set.node, out);
+            }
+            Selection::FragmentSpread(spread) => {
+                write!(out, "...{}", spread.node.fragment_name.node).ok();
+                print_directives(&spread.node.directives, out);
+            }
+        }
+    }
+    out.push_str(" }");
+}
+
+fn print_directives(directives: &[Positioned<Directive>], out: &mut String) {
+    for directive in directives {
+        let directive = &directive.node;
+        write!(out, " @{}", directive.name.node).ok();
+        if !directive.arguments.is_empty() {
+            let arguments: Vec<String> = directive
+                .arguments
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name.node, value.node))
+                .collect();
+            write!(out, "({})", arguments.join(", ")).ok();
+        }
+    }
+}
+
+/// Names of the variables referenced by arguments in `selection`.
+fn collect_variables(selection: &SelectionSet, out: &mut Vec<String>) {
+    for item in &selection.items {
+        let (arguments, directives, nested) = match &item.node {
+            Selection::Field(field) => (
+                field.node.arguments.as_slice(),
+                &field.node.directives,
+                Some(&field.node.selection_set.node),
+            ),
+            Selection::InlineFragment(fragment) => (
+                &[][..],
+                &fragment.node.directives,
+                Some(&fragment.node.selection_set.node),
+            ),
+            Selection::FragmentSpread(spread) => (&[][..], &spread.node.directives, None),
+        };
+
+        let directive_arguments = directives
+            .iter()
+            .flat_map(|directive| directive.node.arguments.iter());
+        for (_, value) in arguments.iter().chain(directive_arguments) {
+            let _ = value.node.clone().into_const_with(|name| {
+                if !out.iter().any(|existing| existing == name.as_str()) {
+                    out.push(name.to_string());
+                }
+                Ok::<_, Infallible>(async_graphql::Value::Null)
+            });
+        }
+
+        if let Some(nested) = nested {
+            collect_variables(nested, out);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use async_graphql::parser::parse_schema;
+use async_graphql::parser::types::{
+    BaseType, ConstDirective, FieldDefinition, Type, TypeKind, TypeSystemDefinition,
+};
+use async_graphql::{Positioned, Value};
+use std::collections::HashMap;
+
+/// Name under which the gateway's own schema takes part in the supergraph.
+pub const LOCAL_SUBGRAPH: &str = "gateway";
+
+pub const QUERY_TYPE: &str = "Query";
+pub const MUTATION_TYPE: &str = "Mutation";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeCategory {
+    Object,
+    Interface,
+    Union,
+    /// Scalars, enums and input objects.
+    Leaf,
+}
+
+#[derive(Debug)]
+pub struct FieldInfo {
+    /// Named type of the field, without list and non-null wrappers.
+    pub type_name: String,
+    /// Subgraphs that resolve the field, in composition order.
+    pub owners: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct TypeInfo {
+    pub category: TypeCategory,
+    pub fields: HashMap<String, FieldInfo>,
+    /// Resolvable `@key` field set per subgraph.
+    pub keys: HashMap<String, Vec<String>>,
+    /// Object types of an interface or union.
+    pub possible_types: Vec<String>,
+}
+
+impl TypeInfo {
+    fn new(category: TypeCategory) -> Self {
+        Self {
+            category,
+            fields: HashMap::new(),
+            keys: HashMap::new(),
+            possible_types: Vec::new(),
+        }
+    }
+}
+
+/// Types of every subgraph merged by name. Each subgraph's root types are
+/// folded into `Query` and `Mutation` whatever they are called there.
+#[derive(Debug, Default)]
+pub struct Supergraph {
+    types: HashMap<String, TypeInfo>,
+}
+
+impl Supergraph {
+    /// Composes `(name, sdl)` pairs. Supports Federation v2 entities with
+    /// simple `@key` field sets and `@external`; other federation directives
+    /// are ignored.
+    pub fn compose(subgraphs: &[(String, String)]) -> Result<Self, String> {
+        let mut supergraph = Supergraph::default();
+
+        for (name, sdl) in subgraphs {
+            supergraph
+                .add_subgraph(name, sdl)
+                .map_err(|e| format!("Subgraph '{}': {}", name, e))?;
+        }
+
+        if !supergraph.types.contains_key(QUERY_TYPE) {
+            return Err("No subgraph defines a query type".to_string());
+        }
+
+        Ok(supergraph)
+    }
+
+    fn add_subgraph(&mut self, subgraph: &str, sdl: &str) -> Result<(), String> {
+        let document = parse_schema(sdl).map_err(|e| format!("Invalid SDL: {}", e))?;
+
+        let mut query_type = QUERY_TYPE.to_string();
+        let mut mutation_type = MUTATION_TYPE.to_string();
+        let mut subscription_type = "Subscription".to_string();
+        for definition in &document.definitions {
+            if let TypeSystemDefinition::Schema(schema) = definition {
+                if let Some(name) = &schema.node.query {
+                    query_type = name.node.to_string();
+                }
+                if let Some(name) = &schema.node.mutation {
+                    mutation_type = name.node.to_string();
+                }
+                if let Some(name) = &schema.node.subscription {
+                    subscription_type = name.node.to_string();
+                }
+            }
+        }
+
+        for definition in &document.definitions {
+            let TypeSystemDefinition::Type(definition) = definition else {
+                continue;
+            };
+            let definition = &definition.node;
+            let source_name = definition.name.node.as_str();
+
+            if source_name.starts_with('_')
+                || source_name.starts_with("link__")
+                || source_name == subscription_type
+            {
+                continue;
+            }
+
+            let is_root = source_name == query_type || source_name == mutation_type;
+            let type_name = if source_name == query_type {
+                QUERY_TYPE
+            } else if source_name == mutation_type {
+                MUTATION_TYPE
+            } else {
+                source_name
+            };
+
+            let category = match &definition.kind {
+                TypeKind::Object(_) => TypeCategory::Object,
+                TypeKind::Interface(_) => TypeCategory::Interface,
+                TypeKind::Union(_) => TypeCategory::Union,
+                _ => TypeCategory::Leaf,
+            };
+
+            let info = self
+                .types
+                .entry(type_name.to_string())
+                .or_insert_with(|| TypeInfo::new(category));
+            if info.category != category {
+                return Err(format!(
+                    "Type '{}' is a {:?} here but a {:?} elsewhere",
+                    type_name, category, info.category
+                ));
+            }
+
+            if let Some(key) = resolvable_key(&definition.directives)? {
+                info.keys.insert(subgraph.to_string(), key);
+            }
+
+            let fields: &[Positioned<FieldDefinition>] = match &definition.kind {
+                TypeKind::Object(object) => &object.fields,
+                TypeKind::Interface(interface) => &interface.fields,
+                _ => &[],
+            };
+            if let TypeKind::Union(union) = &definition.kind {
+                for member in &union.members {
+                    push_unique(&mut info.possible_types, member.node.as_str());
+                }
+            }
+
+            for field in fields {
+                let field = &field.node;
+                // `_service` and `_entities` belong to each subgraph, not the supergraph
+                if is_root && field.name.node.starts_with('_') {
+                    continue;
+                }
+
+                let entry = info
+                    .fields
+                    .entry(field.name.node.to_string())
+                    .or_insert_with(|| FieldInfo {
+                        type_name: named_type(&field.ty.node).to_string(),
+                        owners: Vec::new(),
+                    });
+                if !has_directive(&field.directives, "external") {
+                    push_unique(&mut entry.owners, subgraph);
+                }
+            }
+
+            if let TypeKind::Object(object) = &definition.kind {
+                for interface in &object.implements {
+                    let interface = self
+                        .types
+                        .entry(interface.node.to_string())
+                        .or_insert_with(|| TypeInfo::new(TypeCategory::Interface));
+                    push_unique(&mut interface.possible_types, type_name);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn field(&self, type_name: &str, field_name: &str) -> Option<&FieldInfo> {
+        self.types.get(type_name)?.fields.get(field_name)
+    }
+
+    pub fn is_composite(&self, type_name: &str) -> bool {
+        self.types
+            .get(type_name)
+            .is_some_and(|info| info.category != TypeCategory::Leaf)
+    }
+
+    /// Whether an object of type `concrete` satisfies `... on condition`.
+    pub fn type_matches(&self, condition: &str, concrete: &str) -> bool {
+        condition == concrete
+            || self
+                .types
+                .get(condition)
+                .is_some_and(|info| info.possible_types.iter().any(|t| t == concrete))
+    }
+
+    /// Key fields `subgraph` resolves `type_name` entities by, if any.
+    pub fn entity_key(&self, type_name: &str, subgraph: &str) -> Option<&[String]> {
+        self.types
+            .get(type_name)?
+            .keys
+            .get(subgraph)
+            .map(Vec::as_slice)
+    }
+}
+
+/// First `@key` without `resolvable: false`.
+fn resolvable_key(
+    directives: &[Positioned<ConstDirective>],
+) -> Result<Option<Vec<String>>, String> {
+    for directive in directives {
+        let directive = &directive.node;
+        if directive.name.node != "key" {
+            continue;
+        }
+
+        if let Some(resolvable) = directive.get_argument("resolvable")
+            && resolvable.node == Value::Boolean(false)
+        {
+            continue;
+        }
+
+        let fields = match directive.get_argument("fields").map(|value| &value.node) {
+            Some(Value::String(fields)) => fields,
+            _ => return Err("@key needs a `fields` string".to_string()),
+        };
+        if fields.contains('{') {
+            return Err(format!(
+                "Nested @key field set '{}' is not supported",
+                fields
+            ));
+        }
+
+        return Ok(Some(
+            fields.split_whitespace().map(str::to_string).collect(),
+        ));
+    }
+
+    Ok(None)
+}
+
+fn has_directive(directives: &[Positioned<ConstDirective>], name: &str) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.node.name.node == name)
+}
+
+fn named_type(ty: &Type) -> &str {
+    match &ty.base {
+        BaseType::Named(name) => name.as_str(),
+        BaseType::List(inner) => named_type(inner),
+    }
+}
+
+fn push_unique(items: &mut Vec<String>, item: &str) {
+    if !items.iter().any(|existing| existing == item) {
+        items.push(item.to_string());
+    }
+}
@@ -0,0 +1,430 @@
+//! Field guards reading the caller resolved by the HTTP middleware, e.g.
+//! `#[graphql(guard = "RequireRole(ADMIN_ROLE)")]` or
+//! `#[graphql(guard = "RequireOwner(&id).or(RequireRole(ADMIN_ROLE))")]`.
+//! Anonymous callers get `UNAUTHENTICATED`, everyone else `FORBIDDEN`.
+//! `RateLimit` answers `RATE_LIMITED` instead.
+
+use crate::application::usecases::auth::check_permission::CheckPermissionUseCase;
+use crate::application::usecases::check_rate_limit::{CheckRateLimitUseCase, RateLimitSubject};
+use crate::domain::admin::inputs::{ADMIN_ROLE, IDENTITIES_OBJECT, MANAGE_RELATION};
+use crate::domain::auth::context::AuthContext;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::gateway::rate_limit::RateLimitConfig;
+use crate::domain::repositories::permission_repository::PermissionRepository;
+use crate::domain::repositories::rate_limit_repository::RateLimitRepository;
+use crate::infrastructure::adapters::graphql::credentials::{auth_state, client_ip};
+use async_graphql::{Context, ErrorExtensions, Guard, Result};
+use std::sync::Arc;
+
+fn caller<'a>(ctx: &Context<'a>) -> Result<&'a AuthContext> {
+    auth_state(ctx).require().map_err(|e| e.extend())
+}
+
+fn forbidden(reason: &str) -> async_graphql::Error {
+    AuthError::Forbidden(reason.to_string()).extend()
+}
+
+/// Any authenticated caller.
+pub struct RequireAuth;
+
+impl Guard for RequireAuth {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        caller(ctx).map(|_| ())
+    }
+}
+
+/// Callers whose session was completed with a second factor.
+pub struct RequireAal2;
+
+impl Guard for RequireAal2 {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        if !matches!(caller(ctx)?.aal.as_str(), "aal2" | "aal3") {
+            return Err(forbidden("a second authentication factor is required"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Callers holding the given role.
+#[allow(unused)]
+pub struct RequireRole(pub &'static str);
+
+impl Guard for RequireRole {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        if !caller(ctx)?.has_role(self.0) {
+            return Err(forbidden(&format!("the '{}' role is required", self.0)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Callers whose email address has been verified.
+pub struct RequireVerifiedEmail;
+
+impl Guard for RequireVerifiedEmail {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        if !caller(ctx)?.email_verified {
+            return Err(forbidden("a verified email address is required"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Callers whose identity id equals the given field argument.
+pub struct RequireOwner<'a>(pub &'a str);
+
+impl Guard for RequireOwner<'_> {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        if caller(ctx)?.identity_id != self.0 {
+            return Err(forbidden("only the owner may access this resource"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Callers holding a relation on an object written as `namespace:id`, e.g.
+/// `RequirePermission("edit", format!("projects:{}", id))`.
+#[allow(unused)]
+pub struct RequirePermission(pub &'static str, pub String);
+
+impl Guard for RequirePermission {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let auth = caller(ctx)?;
+        let permissions = ctx.data_unchecked::<Arc<dyn PermissionRepository>>();
+
+        let allowed =
+            CheckPermissionUseCase::execute(self.0, &self.1, Some(auth), permissions.as_ref())
+                .await
+                .map_err(|e| e.extend())?;
+
+        if !allowed {
+            return Err(forbidden(&format!(
+                "'{}' on '{}' is required",
+                self.0, self.1
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Identity administration: the admin role, or the `manage` relation on
+/// `gateway:identities`.
+pub fn identity_admin() -> impl Guard + Send + Sync {
+    IdentityAdmin
+}
+
+struct IdentityAdmin;
+
+impl Guard for IdentityAdmin {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let auth = caller(ctx)?;
+        let permissions = ctx.data_unchecked::<Arc<dyn PermissionRepository>>();
+
+        let allowed = CheckPermissionUseCase::identity_admin(auth, permissions.as_ref())
+            .await
+            .map_err(|e| e.extend())?;
+
+        if !allowed {
+            return Err(forbidden(&format!(
+                "the '{}' role or '{}' on '{}' is required",
+                ADMIN_ROLE, MANAGE_RELATION, IDENTITIES_OBJECT
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts the call against the `field:<name>` rules of the rate limit
+/// config. The second argument keys the identifier rules: the login email
+/// or username, or the flow whose code is being guessed. Open to everyone,
+/// authenticated or not.
+pub struct RateLimit<'a>(pub &'static str, pub Option<&'a str>);
+
+impl Guard for RateLimit<'_> {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let config = ctx.data_unchecked::<Arc<RateLimitConfig>>();
+        let rate_limits = ctx.data_unchecked::<Arc<dyn RateLimitRepository>>();
+
+        let subject = RateLimitSubject {
+            ip: client_ip(ctx),
+            identifier: self.1,
+            identity_id: auth_state(ctx)
+                .context()
+                .ok()
+                .flatten()
+                .map(|auth| auth.identity_id.as_str()),
+        };
+
+        CheckRateLimitUseCase::execute(
+            &format!("field:{}", self.0),
+            config.field(self.0),
+            subject,
+            rate_limits.as_ref(),
+        )
+        .await
+        .map_err(|e| e.extend())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::auth::context::{AuthSource, AuthState};
+    use crate::domain::repositories::permission_repository::RelationTuple;
+    use crate::infrastructure::adapters::graphql::credentials::ClientIp;
+    use crate::infrastructure::adapters::kratos::kratos_client::IdentityTraits;
+    use crate::infrastructure::adapters::memory::{
+        InMemoryPermissionRepository, InMemoryRateLimitRepository,
+    };
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Request, Schema, Value};
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        #[graphql(guard = "RequirePermission(\"edit\", format!(\"projects:{}\", id))")]
+        async fn edit_project(&self, id: String) -> String {
+            id
+        }
+
+        #[graphql(guard = "identity_admin()")]
+        async fn manage_identities(&self) -> bool {
+            true
+        }
+
+        #[graphql(guard = "RequireOwner(&id).or(RequireRole(ADMIN_ROLE))")]
+        async fn identity(&self, id: String) -> String {
+            id
+        }
+
+        #[graphql(guard = "RequireAal2")]
+        async fn second_factor(&self) -> bool {
+            true
+        }
+
+        #[graphql(guard = "RequireVerifiedEmail")]
+        async fn verified(&self) -> bool {
+            true
+        }
+
+        #[graphql(guard = "RateLimit(\"verify_email\", Some(&flow_id))")]
+        async fn verify_code(&self, flow_id: String) -> String {
+            flow_id
+        }
+    }
+
+    fn caller(identity_id: &str) -> AuthContext {
+        AuthContext {
+            identity_id: identity_id.to_string(),
+            traits: IdentityTraits {
+                email: format!("{}@example.com", identity_id),
+                username: identity_id.to_string(),
+                geo_location: None,
+            },
+            email_verified: false,
+            session_id: format!("session-{}", identity_id),
+            aal: "aal1".to_string(),
+            roles: Vec::new(),
+            source: AuthSource::AccessToken,
+            session: None,
+        }
+    }
+
+    fn tuple(object: &str, relation: &str, subject_id: &str) -> RelationTuple {
+        RelationTuple::for_subject(object, relation, subject_id).unwrap()
+    }
+
+    /// Runs `query` as `state` against these tuples; `None` when it passed,
+    /// the error code otherwise.
+    async fn run(query: &str, state: AuthState, tuples: Vec<RelationTuple>) -> Option<String> {
+        let permissions: Arc<dyn PermissionRepository> =
+            Arc::new(InMemoryPermissionRepository::new(tuples));
+        execute(Request::new(query).data(state).data(permissions)).await
+    }
+
+    async fn execute(request: Request) -> Option<String> {
+        let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+        let response = schema.execute(request).await;
+
+        response.errors.first().map(|error| {
+            match error
+                .extensions
+                .as_ref()
+                .and_then(|extensions| extensions.get("code"))
+            {
+                Some(Value::String(code)) => code.clone(),
+                _ => error.message.clone(),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn permission_guard_checks_the_relation_tuple() {
+        let tuples = || vec![tuple("projects:42", "edit", "alice")];
+        let alice = || AuthState::Authenticated(caller("alice"));
+
+        let query = r#"{ editProject(id: "42") }"#;
+        assert_eq!(run(query, alice(), tuples()).await.as_deref(), None);
+        assert_eq!(
+            run(query, AuthState::Authenticated(caller("bob")), tuples())
+                .await
+                .as_deref(),
+            Some("FORBIDDEN")
+        );
+        assert_eq!(
+            run(query, AuthState::Anonymous, tuples()).await.as_deref(),
+            Some("UNAUTHENTICATED")
+        );
+
+        // Other objects and relations don't inherit the tuple
+        let other_project = r#"{ editProject(id: "43") }"#;
+        assert_eq!(
+            run(other_project, alice(), tuples()).await.as_deref(),
+            Some("FORBIDDEN")
+        );
+        let view_only = vec![tuple("projects:42", "view", "alice")];
+        assert_eq!(
+            run(query, alice(), view_only).await.as_deref(),
+            Some("FORBIDDEN")
+        );
+    }
+
+    #[tokio::test]
+    async fn identity_admin_accepts_the_role_or_the_keto_relation() {
+        let query = "{ manageIdentities }";
+
+        let mut admin = caller("admin");
+        admin.roles = vec![ADMIN_ROLE.to_string()];
+        assert_eq!(
+            run(query, AuthState::Authenticated(admin), Vec::new())
+                .await
+                .as_deref(),
+            None
+        );
+
+        let operator = vec![tuple(IDENTITIES_OBJECT, MANAGE_RELATION, "operator")];
+        assert_eq!(
+            run(
+                query,
+                AuthState::Authenticated(caller("operator")),
+                operator
+            )
+            .await
+            .as_deref(),
+            None
+        );
+
+        assert_eq!(
+            run(query, AuthState::Authenticated(caller("user")), Vec::new())
+                .await
+                .as_deref(),
+            Some("FORBIDDEN")
+        );
+    }
+
+    #[tokio::test]
+    async fn owner_guard_falls_back_to_the_role() {
+        let query = r#"{ identity(id: "alice") }"#;
+
+        assert_eq!(
+            run(query, AuthState::Authenticated(caller("alice")), Vec::new())
+                .await
+                .as_deref(),
+            None
+        );
+        assert_eq!(
+            run(query, AuthState::Authenticated(caller("bob")), Vec::new())
+                .await
+                .as_deref(),
+            Some("FORBIDDEN")
+        );
+
+        let mut admin = caller("bob");
+        admin.roles = vec![ADMIN_ROLE.to_string()];
+        assert_eq!(
+            run(query, AuthState::Authenticated(admin), Vec::new())
+                .await
+                .as_deref(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn aal2_and_verified_email_guards_read_the_caller() {
+        let mut caller = caller("alice");
+        assert_eq!(
+            run(
+                "{ secondFactor }",
+                AuthState::Authenticated(caller.clone()),
+                Vec::new()
+            )
+            .await
+            .as_deref(),
+            Some("FORBIDDEN")
+        );
+        assert_eq!(
+            run(
+                "{ verified }",
+                AuthState::Authenticated(caller.clone()),
+                Vec::new()
+            )
+            .await
+            .as_deref(),
+            Some("FORBIDDEN")
+        );
+
+        caller.aal = "aal2".to_string();
+        caller.email_verified = true;
+        let state = || AuthState::Authenticated(caller.clone());
+        assert_eq!(
+            run("{ secondFactor }", state(), Vec::new())
+                .await
+                .as_deref(),
+            None
+        );
+        assert_eq!(
+            run("{ verified }", state(), Vec::new()).await.as_deref(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn guards_report_an_unavailable_auth_backend() {
+        let state = AuthState::Unavailable("Kratos unreachable".to_string());
+        assert_eq!(
+            run(r#"{ editProject(id: "42") }"#, state, Vec::new())
+                .await
+                .as_deref(),
+            Some("SERVICE_UNAVAILABLE")
+        );
+    }
+
+    #[tokio::test]
+    async fn rate_limit_guard_counts_code_attempts_per_flow() {
+        let config = Arc::new(RateLimitConfig::default());
+        let rate_limits: Arc<dyn RateLimitRepository> =
+            Arc::new(InMemoryRateLimitRepository::default());
+        let attempt = |flow_id: &str| {
+            execute(
+                Request::new(format!(r#"{{ verifyCode(flowId: "{}") }}"#, flow_id))
+                    .data(config.clone())
+                    .data(rate_limits.clone())
+                    .data(Some(ClientIp("203.0.113.7".to_string()))),
+            )
+        };
+
+        for _ in 0..5 {
+            assert_eq!(attempt("flow-1").await.as_deref(), None);
+        }
+        assert_eq!(attempt("flow-1").await.as_deref(), Some("RATE_LIMITED"));
+
+        // Another flow from the same address has its own budget
+        assert_eq!(attempt("flow-2").await.as_deref(), None);
+    }
+}
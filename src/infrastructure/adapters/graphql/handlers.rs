@@ -1,45 +1,232 @@
+use crate::application::usecases::auth::resolve_caller::ResolveCallerUseCase;
+use crate::domain::auth::context::AuthState;
+use crate::domain::auth::inputs::ClientType;
+use crate::domain::gateway::rate_limit::RateLimitConfig;
+use crate::infrastructure::adapters::graphql::credentials::{ClientIp, SessionToken};
+use crate::infrastructure::adapters::graphql::federation::Federation;
+use crate::infrastructure::adapters::graphql::federation::executor::SubgraphTransport;
+use crate::infrastructure::adapters::graphql::federation::supergraph::LOCAL_SUBGRAPH;
 use crate::infrastructure::adapters::graphql::response_cookies::ResponseCookies;
 use crate::infrastructure::adapters::graphql::schema::AppSchema;
-use actix_web::{HttpRequest, HttpResponse, Result, web};
+use crate::infrastructure::adapters::http::auth_middleware::extract_bearer_token;
+use crate::infrastructure::adapters::http::identity_headers::identity_headers;
+use crate::infrastructure::adapters::http::rate_limit_middleware::client_ip;
+use crate::infrastructure::adapters::http::upstream_client::UpstreamClient;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, Result, web};
 use async_graphql::http::GraphiQLSource;
-use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{Request, Variables};
+use async_graphql_actix_web::GraphQLRequest;
+use async_trait::async_trait;
 
 pub async fn graphql_handler(
     schema: web::Data<AppSchema>,
+    federation: web::Data<Option<Federation>>,
+    upstream_client: web::Data<UpstreamClient>,
+    jwt_signer: web::Data<JwtSigner>,
     req: GraphQLRequest,
     http_req: HttpRequest,
 ) -> Result<HttpResponse> {
     let response_cookies = ResponseCookies::new();
+    let request_data = RequestData::from_http(&http_req, response_cookies.clone());
+    let request = req.into_inner();
 
-    // ✅ Извлекаем cookies из HTTP заголовка
-    let cookie_header = http_req
-        .headers()
-        .get(actix_web::http::header::COOKIE)
-        .and_then(|value| value.to_str().ok())
-        .map(|s| s.to_string());
-
-    let mut request = req.into_inner();
-
-    // ✅ Добавляем cookies из запроса в контекст
-    request = request.data(cookie_header);
+    let plan = match federation.as_ref() {
+        Some(federation) => federation.plan(
+            &request.query,
+            request.operation_name.as_deref(),
+            &request.variables,
+        ),
+        None => Ok(None),
+    };
 
-    // ✅ Добавляем ResponseCookies для установки новых cookies
-    request = request.data(response_cookies.clone());
-
-    let response = schema.execute(request).await;
+    let response = match (federation.as_ref(), plan) {
+        (Some(federation), Ok(Some(plan))) => {
+            let transport = SubgraphRequests {
+                schema: &schema,
+                federation,
+                request_data: &request_data,
+                upstream_client: &upstream_client,
+                jwt_signer: &jwt_signer,
+                bearer_token: extract_bearer_token(http_req.headers()),
+            };
+            federation.execute(&plan, &transport).await
+        }
+        (_, Err(e)) => serde_json::json!({
+            "errors": [{ "message": e, "extensions": { "code": "QUERY_PLANNING_FAILED" } }]
+        }),
+        _ => serde_json::to_value(schema.execute(request_data.attach(request)).await)?,
+    };
 
     let cookies = response_cookies.get_cookies().await;
 
-    let mut http_response = HttpResponse::Ok();
+    // A rate-limited field makes the whole response a 429
+    let mut http_response = match rate_limited_for(&response) {
+        Some(retry_after) => {
+            let mut builder = HttpResponse::TooManyRequests();
+            builder.insert_header((RETRY_AFTER, retry_after));
+            builder
+        }
+        None => HttpResponse::Ok(),
+    };
 
     // ✅ Устанавливаем все cookies в ответ
     for cookie in cookies {
-        http_response.insert_header(("Set-Cookie", cookie));
+        http_response.append_header(("Set-Cookie", cookie));
     }
 
     Ok(http_response.json(response))
 }
 
+/// Longest `retryAfter` among `RATE_LIMITED` errors, if there are any.
+fn rate_limited_for(response: &serde_json::Value) -> Option<u64> {
+    response
+        .get("errors")?
+        .as_array()?
+        .iter()
+        .filter(|error| error.pointer("/extensions/code") == Some(&"RATE_LIMITED".into()))
+        .filter_map(|error| error.pointer("/extensions/retryAfter")?.as_u64())
+        .max()
+}
+
+/// Per-request data the resolvers read, attached to every execution of the
+/// gateway's schema, including the ones a federated plan makes.
+struct RequestData {
+    cookie_header: Option<String>,
+    session_token: Option<SessionToken>,
+    auth_state: AuthState,
+    client_type: Option<ClientType>,
+    client_ip: Option<ClientIp>,
+    response_cookies: ResponseCookies,
+}
+
+impl RequestData {
+    fn from_http(http_req: &HttpRequest, response_cookies: ResponseCookies) -> Self {
+        // ✅ Извлекаем cookies из HTTP заголовка
+        let cookie_header = http_req
+            .headers()
+            .get(actix_web::http::header::COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .map(|s| s.to_string());
+
+        // Native clients authenticate with a Kratos session token instead of cookies;
+        // gateway access tokens mean nothing to Kratos and are not forwarded
+        let session_token = extract_bearer_token(http_req.headers())
+            .filter(|token| !ResolveCallerUseCase::is_access_token(token))
+            .map(SessionToken);
+
+        // Resolved by the `resolve_caller` middleware
+        let auth_state = http_req
+            .extensions()
+            .get::<AuthState>()
+            .cloned()
+            .unwrap_or_default();
+
+        let trust_forwarded_for = http_req
+            .app_data::<web::Data<RateLimitConfig>>()
+            .is_some_and(|config| config.trust_forwarded_for);
+
+        Self {
+            cookie_header,
+            session_token,
+            auth_state,
+            client_type: extract_client_type(http_req),
+            client_ip: client_ip(&http_req.connection_info(), trust_forwarded_for).map(ClientIp),
+            response_cookies,
+        }
+    }
+
+    fn attach(&self, mut request: Request) -> Request {
+        // ✅ Добавляем cookies из запроса в контекст
+        request = request.data(self.cookie_header.clone());
+        request = request.data(self.session_token.clone());
+        request = request.data(self.auth_state.clone());
+        request = request.data(self.client_ip.clone());
+
+        if let Some(client_type) = self.client_type {
+            request = request.data(client_type);
+        }
+
+        // ✅ Добавляем ResponseCookies для установки новых cookies
+        request.data(self.response_cookies.clone())
+    }
+}
+
+/// Runs a plan's fetches: `gateway` in-process with the caller's request
+/// data, subgraphs over HTTP with the caller's identity attached.
+struct SubgraphRequests<'a> {
+    schema: &'a AppSchema,
+    federation: &'a Federation,
+    request_data: &'a RequestData,
+    upstream_client: &'a UpstreamClient,
+    jwt_signer: &'a JwtSigner,
+    bearer_token: Option<String>,
+}
+
+#[async_trait(?Send)]
+impl SubgraphTransport for SubgraphRequests<'_> {
+    async fn execute(
+        &self,
+        subgraph: &str,
+        query: String,
+        variables: serde_json::Map<String, serde_json::Value>,
+    ) -> std::result::Result<serde_json::Value, String> {
+        if subgraph == LOCAL_SUBGRAPH {
+            let request = Request::new(query)
+                .variables(Variables::from_json(serde_json::Value::Object(variables)));
+            let response = self.schema.execute(self.request_data.attach(request)).await;
+            return serde_json::to_value(response).map_err(|e| e.to_string());
+        }
+
+        let config = self
+            .federation
+            .subgraph(subgraph)
+            .ok_or_else(|| format!("Unknown subgraph '{}'", subgraph))?;
+
+        let mut headers = vec![("content-type".to_string(), b"application/json".to_vec())];
+        // Subgraphs decide for themselves what anonymous callers may see
+        if let Ok(Some(auth)) = self.request_data.auth_state.context() {
+            let identity = identity_headers(
+                auth,
+                config.identity,
+                self.jwt_signer,
+                self.bearer_token.clone(),
+            )
+            .map_err(|e| e.to_string())?;
+            headers.extend(identity);
+        }
+
+        let body = serde_json::to_vec(&serde_json::json!({
+            "query": query,
+            "variables": variables,
+        }))
+        .map_err(|e| e.to_string())?;
+
+        let response = self
+            .upstream_client
+            .send("POST", &config.url, headers, body, config.timeout())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        serde_json::from_slice(&response.body)
+            .map_err(|e| format!("Invalid response (HTTP {}): {}", response.status, e))
+    }
+}
+
+fn extract_client_type(http_req: &HttpRequest) -> Option<ClientType> {
+    let value = http_req
+        .headers()
+        .get("X-Client-Type")
+        .and_then(|value| value.to_str().ok())?;
+
+    match value.to_ascii_lowercase().as_str() {
+        "browser" => Some(ClientType::Browser),
+        "native" | "api" | "mobile" => Some(ClientType::Native),
+        _ => None,
+    }
+}
+
 pub async fn graphql_playground() -> Result<HttpResponse> {
     let html = GraphiQLSource::build().endpoint("/graphql").finish();
     Ok(HttpResponse::Ok()
@@ -1,3 +1,6 @@
+pub mod credentials;
+pub mod federation;
+pub mod guards;
 pub mod handlers;
 pub mod response_cookies;
 pub mod schema;
@@ -21,8 +21,4 @@ impl ResponseCookies {
     pub async fn get_cookies(&self) -> Vec<String> {
         self.cookies.lock().await.clone()
     }
-
-    pub async fn clear(&self) {
-        self.cookies.lock().await.clear();
-    }
 }
@@ -1,29 +1,74 @@
+use crate::application::graphql::mutations::admin_mutation::AdminMutation;
 use crate::application::graphql::mutations::login_mutation::LoginMutation;
+use crate::application::graphql::mutations::logout_mutation::LogoutMutation;
+use crate::application::graphql::mutations::recovery_mutation::RecoveryMutation;
 use crate::application::graphql::mutations::register_mutation::RegisterMutation;
+use crate::application::graphql::mutations::sessions_mutation::SessionsMutation;
+use crate::application::graphql::mutations::settings_mutation::SettingsMutation;
+use crate::application::graphql::mutations::social_login_mutation::SocialLoginMutation;
+use crate::application::graphql::mutations::token_mutation::TokenMutation;
+use crate::application::graphql::mutations::two_factor_mutation::TwoFactorMutation;
+use crate::application::graphql::mutations::verification_mutation::VerificationMutation;
+use crate::application::graphql::mutations::webauthn_mutation::WebauthnMutation;
+use crate::application::graphql::queries::admin_query::AdminQuery;
 use crate::application::graphql::queries::health_query::HealthQuery;
+use crate::application::graphql::queries::permission_query::PermissionQuery;
+use crate::application::graphql::queries::session_query::SessionQuery;
+use crate::application::graphql::queries::social_query::SocialQuery;
+use crate::domain::gateway::rate_limit::RateLimitConfig;
+use crate::domain::repositories::permission_repository::PermissionRepository;
+use crate::domain::repositories::rate_limit_repository::RateLimitRepository;
+use crate::domain::repositories::refresh_token_repository::RefreshTokenRepository;
+use crate::infrastructure::adapters::jwt::JwtSigner;
 use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
 use async_graphql::{EmptySubscription, MergedObject, Schema};
+use std::sync::Arc;
 
 #[derive(MergedObject, Default)]
-pub struct QueryRoot(HealthQuery);
+pub struct QueryRoot(
+    HealthQuery,
+    SessionQuery,
+    SocialQuery,
+    AdminQuery,
+    PermissionQuery,
+);
 
 #[derive(MergedObject, Default)]
-pub struct MutationRoot(RegisterMutation, LoginMutation);
+pub struct MutationRoot(
+    RegisterMutation,
+    LoginMutation,
+    LogoutMutation,
+    SettingsMutation,
+    RecoveryMutation,
+    VerificationMutation,
+    TwoFactorMutation,
+    SocialLoginMutation,
+    WebauthnMutation,
+    SessionsMutation,
+    AdminMutation,
+    TokenMutation,
+);
 
 pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
 
-pub fn create_schema(jwt_secret: String) -> AppSchema {
-    let kratos_client = KratosClient::new(
-        "http://localhost:4434".to_string(), // admin URL
-        "http://localhost:4433".to_string(), // public URL
-    );
-
+pub fn create_schema(
+    jwt_signer: JwtSigner,
+    kratos_client: KratosClient,
+    refresh_tokens: Arc<dyn RefreshTokenRepository>,
+    permissions: Arc<dyn PermissionRepository>,
+    rate_limits: Arc<dyn RateLimitRepository>,
+    rate_limit_config: Arc<RateLimitConfig>,
+) -> AppSchema {
     Schema::build(
         QueryRoot::default(),
         MutationRoot::default(),
         EmptySubscription,
     )
-    .data(jwt_secret)
+    .data(jwt_signer)
     .data(kratos_client) // Add KratosClient to schema data
+    .data(refresh_tokens)
+    .data(permissions)
+    .data(rate_limits)
+    .data(rate_limit_config)
     .finish()
 }
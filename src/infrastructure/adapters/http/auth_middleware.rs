@@ -0,0 +1,56 @@
+use crate::application::usecases::auth::resolve_caller::ResolveCallerUseCase;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderMap};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage, web};
+
+/// Resolves the caller once per request and stores the resulting `AuthState`
+/// in the request extensions.
+pub async fn resolve_caller(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let kratos_client = req.app_data::<web::Data<KratosClient>>().cloned();
+    let jwt_signer = req.app_data::<web::Data<JwtSigner>>().cloned();
+
+    if let (Some(kratos_client), Some(jwt_signer)) = (kratos_client, jwt_signer) {
+        let bearer_token = extract_bearer_token(req.headers());
+        let cookie = req
+            .headers()
+            .get(header::COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let auth_state = ResolveCallerUseCase::execute(
+            &kratos_client,
+            &jwt_signer,
+            bearer_token.as_deref(),
+            cookie.as_deref(),
+        )
+        .await;
+
+        req.extensions_mut().insert(auth_state);
+    }
+
+    next.call(req).await
+}
+
+/// Token from `X-Session-Token` or `Authorization: Bearer`: a Kratos session
+/// token or a gateway access token.
+pub fn extract_bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("X-Session-Token")
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| {
+            headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+        })
+        .map(|token| token.trim())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_string())
+}
@@ -0,0 +1,15 @@
+use crate::domain::auth::errors::AuthError;
+use actix_web::HttpResponse;
+use actix_web::http::StatusCode;
+
+/// `{"error": {"code", "message"}}`, the body of every non-GraphQL error the
+/// gateway answers itself.
+pub fn error_response(status: StatusCode, code: &str, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "error": { "code": code, "message": message }
+    }))
+}
+
+pub fn auth_error_response(status: StatusCode, e: AuthError) -> HttpResponse {
+    error_response(status, e.code(), &e.to_string())
+}
@@ -0,0 +1,57 @@
+use crate::domain::auth::context::AuthContext;
+use crate::domain::auth::errors::AuthError;
+use crate::domain::gateway::routes::IdentityForwarding;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+
+/// Identity headers the gateway sets; copies sent by clients are dropped.
+pub const IDENTITY_HEADERS: [&str; 5] = [
+    "x-user-id",
+    "x-user-email",
+    "x-session-id",
+    "x-user-roles",
+    "x-user-aal",
+];
+
+/// Headers carrying the caller's identity to an upstream service.
+/// `bearer_token` is the caller's own gateway token, reused in JWT mode when
+/// there is no Kratos session to sign a new one from.
+pub fn identity_headers(
+    auth: &AuthContext,
+    forwarding: IdentityForwarding,
+    jwt_signer: &JwtSigner,
+    bearer_token: Option<String>,
+) -> Result<Vec<(String, Vec<u8>)>, AuthError> {
+    match forwarding {
+        IdentityForwarding::Headers => Ok(vec![
+            (
+                "x-user-id".to_string(),
+                auth.identity_id.clone().into_bytes(),
+            ),
+            (
+                "x-user-email".to_string(),
+                auth.traits.email.clone().into_bytes(),
+            ),
+            (
+                "x-session-id".to_string(),
+                auth.session_id.clone().into_bytes(),
+            ),
+            (
+                "x-user-roles".to_string(),
+                auth.roles.join(",").into_bytes(),
+            ),
+            ("x-user-aal".to_string(), auth.aal.clone().into_bytes()),
+        ]),
+        IdentityForwarding::Jwt => {
+            // Callers that already hold a gateway token keep it; Kratos
+            // sessions get a freshly signed one
+            let token = match &auth.session {
+                Some(session) => jwt_signer.issue(session)?.token,
+                None => bearer_token.ok_or(AuthError::NotAuthenticated)?,
+            };
+            Ok(vec![(
+                "authorization".to_string(),
+                format!("Bearer {}", token).into_bytes(),
+            )])
+        }
+    }
+}
@@ -1 +1,8 @@
+pub mod auth_middleware;
+pub mod error_response;
+pub mod identity_headers;
+pub mod rate_limit_middleware;
+pub mod resilience;
 pub mod server;
+pub mod upstream_client;
+pub mod upstream_pool;
@@ -0,0 +1,186 @@
+use crate::application::usecases::auth::resolve_caller::ResolveCallerUseCase;
+use crate::application::usecases::check_rate_limit::{CheckRateLimitUseCase, RateLimitSubject};
+use crate::domain::auth::errors::{AuthError, retry_after_seconds};
+use crate::domain::gateway::rate_limit::{RateLimitConfig, RateLimitScope};
+use crate::domain::repositories::rate_limit_repository::RateLimitRepository;
+use crate::infrastructure::adapters::http::auth_middleware::extract_bearer_token;
+use crate::infrastructure::adapters::http::error_response::auth_error_response;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ConnectionInfo, ServiceRequest, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpResponse, web};
+
+/// Applies the `RateLimitScope` registered on the resource or scope. Runs
+/// before `resolve_caller`, so rejected requests never reach Kratos; rules
+/// keyed by identity count gateway access tokens by their subject, which
+/// needs no Kratos call, and everyone else by IP.
+pub async fn rate_limit(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let scope = req.app_data::<web::Data<RateLimitScope>>().cloned();
+    let rate_limits = req
+        .app_data::<web::Data<dyn RateLimitRepository>>()
+        .cloned();
+    let config = req.app_data::<web::Data<RateLimitConfig>>().cloned();
+
+    if let (Some(scope), Some(rate_limits), Some(config)) = (scope, rate_limits, config)
+        && !scope.rules.is_empty()
+    {
+        let ip = client_ip(&req.connection_info(), config.trust_forwarded_for);
+        let identity_id = access_token_subject(&req);
+
+        let subject = RateLimitSubject {
+            ip: ip.as_deref(),
+            identifier: None,
+            identity_id: identity_id.as_deref(),
+        };
+        if let Err(e) =
+            CheckRateLimitUseCase::execute(&scope.name, &scope.rules, subject, rate_limits.as_ref())
+                .await
+        {
+            let response = rate_limited_response(e);
+            return Ok(req.into_response(response).map_into_right_body());
+        }
+    }
+
+    Ok(next.call(req).await?.map_into_left_body())
+}
+
+/// Subject of a valid gateway access token in the request, if any.
+fn access_token_subject(req: &ServiceRequest) -> Option<String> {
+    let jwt_signer = req.app_data::<web::Data<JwtSigner>>()?;
+    let token = extract_bearer_token(req.headers())
+        .filter(|token| ResolveCallerUseCase::is_access_token(token))?;
+    jwt_signer.verify(&token).ok().map(|claims| claims.sub)
+}
+
+/// 429 with `Retry-After` for `RateLimited`, the usual error body otherwise.
+pub fn rate_limited_response(e: AuthError) -> HttpResponse {
+    let AuthError::RateLimited(retry_after) = e else {
+        return auth_error_response(StatusCode::INTERNAL_SERVER_ERROR, e);
+    };
+
+    let mut response = auth_error_response(StatusCode::TOO_MANY_REQUESTS, e);
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, retry_after_seconds(retry_after).into());
+    response
+}
+
+/// The peer's address, or the forwarded client address when the gateway
+/// runs behind a trusted proxy.
+pub fn client_ip(connection_info: &ConnectionInfo, trust_forwarded_for: bool) -> Option<String> {
+    let address = if trust_forwarded_for {
+        connection_info.realip_remote_addr()
+    } else {
+        connection_info.peer_addr()
+    };
+    address.map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::gateway::rate_limit::{RateLimitAlgorithm, RateLimitKey, RateLimitRule};
+    use crate::infrastructure::adapters::http::auth_middleware::resolve_caller;
+    use crate::infrastructure::adapters::jwt::jwt_signer::{JwtAlgorithm, JwtConfig, JwtKeyConfig};
+    use crate::infrastructure::adapters::kratos::kratos_client::KratosSession;
+    use crate::infrastructure::adapters::memory::InMemoryRateLimitRepository;
+    use actix_web::App;
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::middleware::from_fn;
+    use actix_web::test::{TestRequest, call_service, init_service};
+    use std::sync::Arc;
+
+    fn jwt_signer() -> JwtSigner {
+        JwtSigner::new(JwtConfig {
+            algorithm: JwtAlgorithm::Hs256,
+            keys: vec![JwtKeyConfig {
+                kid: None,
+                material: "a-test-secret-that-is-long-enough!".to_string(),
+            }],
+            rotated_at: None,
+            issuer: "rust-gateway".to_string(),
+            audience: None,
+            ttl: chrono::Duration::minutes(15),
+        })
+        .unwrap()
+    }
+
+    fn access_token(jwt_signer: &JwtSigner, identity_id: &str) -> String {
+        let session: KratosSession = serde_json::from_value(serde_json::json!({
+            "id": format!("session-{}", identity_id),
+            "active": true,
+            "identity": {
+                "id": identity_id,
+                "schema_id": "default",
+                "traits": { "email": format!("{}@example.com", identity_id), "username": identity_id },
+                "created_at": "",
+                "updated_at": "",
+            },
+            "authentication_methods": [],
+        }))
+        .unwrap();
+        jwt_signer.issue(&session).unwrap().token
+    }
+
+    #[actix_web::test]
+    async fn counts_access_tokens_by_subject_and_everyone_else_by_ip() {
+        let jwt_signer = jwt_signer();
+        let rate_limits: Arc<dyn RateLimitRepository> =
+            Arc::new(InMemoryRateLimitRepository::default());
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(jwt_signer.clone()))
+                .app_data(web::Data::from(rate_limits))
+                .app_data(web::Data::new(RateLimitConfig::default()))
+                .service(
+                    web::resource("/graphql")
+                        .app_data(web::Data::new(RateLimitScope {
+                            name: "graphql".to_string(),
+                            rules: vec![RateLimitRule {
+                                key: RateLimitKey::Identity,
+                                algorithm: RateLimitAlgorithm::SlidingWindow,
+                                limit: 1,
+                                window_ms: 60_000,
+                            }],
+                        }))
+                        .wrap(from_fn(resolve_caller))
+                        .wrap(from_fn(rate_limit))
+                        .to(HttpResponse::Ok),
+                ),
+        )
+        .await;
+
+        let status = |bearer: Option<String>| {
+            let mut request = TestRequest::post()
+                .uri("/graphql")
+                .peer_addr("203.0.113.7:41000".parse().unwrap());
+            if let Some(bearer) = bearer {
+                request = request.insert_header((AUTHORIZATION, format!("Bearer {}", bearer)));
+            }
+            call_service(&app, request.to_request())
+        };
+
+        let alice = access_token(&jwt_signer, "alice");
+        assert_eq!(status(Some(alice.clone())).await.status(), StatusCode::OK);
+        assert_eq!(
+            status(Some(alice)).await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+
+        // Same address, different subject
+        let bob = access_token(&jwt_signer, "bob");
+        assert_eq!(status(Some(bob)).await.status(), StatusCode::OK);
+
+        // Session tokens would need Kratos, so they share the IP's budget
+        assert_eq!(status(None).await.status(), StatusCode::OK);
+        let response = status(Some("kratos-session-token".to_string())).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(RETRY_AFTER));
+    }
+}
@@ -0,0 +1,320 @@
+use crate::domain::gateway::resilience::{CircuitBreakerPolicy, RetryPolicy};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    /// Open long enough; one trial call decides whether it closes.
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+struct BreakerInner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+/// Circuit breaker around one dependency (Kratos, a proxied route).
+pub struct CircuitBreaker {
+    name: String,
+    policy: CircuitBreakerPolicy,
+    inner: Mutex<BreakerInner>,
+    failures: AtomicU64,
+    rejected: AtomicU64,
+    opened: AtomicU64,
+    retries: AtomicU64,
+}
+
+/// Permission for one call; report its outcome with [`BreakerPermit::record`].
+/// Dropping it unrecorded (e.g. the client went away) only frees the
+/// half-open trial slot, if it held it.
+pub struct BreakerPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    /// Whether this is the half-open trial call.
+    trial: bool,
+    recorded: bool,
+}
+
+#[derive(Serialize)]
+pub struct BreakerStatus {
+    pub name: String,
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    pub failures: u64,
+    pub rejected: u64,
+    pub opened: u64,
+    pub retries: u64,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            name: name.into(),
+            policy,
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                trial_in_flight: false,
+            }),
+            failures: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            opened: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+        }
+    }
+
+    /// `None` while the circuit is open: the caller should fail fast.
+    pub fn try_acquire(&self) -> Option<BreakerPermit<'_>> {
+        let mut inner = self.lock();
+
+        // `None`: rejected; otherwise whether the call is the trial
+        let admitted = match inner.state {
+            BreakerState::Closed => Some(false),
+            BreakerState::Open => {
+                let cooled_down = inner
+                    .opened_at
+                    .is_none_or(|opened_at| opened_at.elapsed() >= self.policy.open_duration());
+                if cooled_down {
+                    inner.state = BreakerState::HalfOpen;
+                    inner.trial_in_flight = true;
+                }
+                cooled_down.then_some(true)
+            }
+            BreakerState::HalfOpen if inner.trial_in_flight => None,
+            BreakerState::HalfOpen => {
+                inner.trial_in_flight = true;
+                Some(true)
+            }
+        };
+
+        let Some(trial) = admitted else {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        Some(BreakerPermit {
+            breaker: self,
+            trial,
+            recorded: false,
+        })
+    }
+
+    /// Counts a retry for the metrics.
+    pub fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.lock();
+        BreakerStatus {
+            name: self.name.clone(),
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            failures: self.failures.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            opened: self.opened.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record(&self, success: bool, trial: bool) {
+        let mut inner = self.lock();
+        let was = inner.state;
+        if trial {
+            inner.trial_in_flight = false;
+        } else if was == BreakerState::HalfOpen {
+            // Only the trial decides; this call was admitted before the circuit opened
+            if !success {
+                self.failures.fetch_add(1, Ordering::Relaxed);
+            }
+            return;
+        }
+
+        if success {
+            inner.consecutive_failures = 0;
+            inner.state = BreakerState::Closed;
+            inner.opened_at = None;
+            if was != BreakerState::Closed {
+                info!(breaker = %self.name, "Circuit closed");
+            }
+            return;
+        }
+
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        inner.consecutive_failures += 1;
+
+        let trips = match was {
+            BreakerState::HalfOpen => true,
+            BreakerState::Closed => inner.consecutive_failures >= self.policy.failure_threshold,
+            // A call admitted before the circuit opened finished late
+            BreakerState::Open => false,
+        };
+        if trips {
+            inner.state = BreakerState::Open;
+            inner.opened_at = Some(Instant::now());
+            self.opened.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                breaker = %self.name,
+                failures = inner.consecutive_failures,
+                open_ms = self.policy.open_ms,
+                "Circuit opened"
+            );
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        self.inner.lock().expect("circuit breaker lock poisoned")
+    }
+}
+
+impl BreakerPermit<'_> {
+    pub fn record(mut self, success: bool) {
+        self.recorded = true;
+        self.breaker.record(success, self.trial);
+    }
+}
+
+impl Drop for BreakerPermit<'_> {
+    fn drop(&mut self) {
+        if self.trial && !self.recorded {
+            self.breaker.lock().trial_in_flight = false;
+        }
+    }
+}
+
+/// "Full jitter" backoff: a random delay up to the policy's ceiling for
+/// `attempt`, so clients retrying together spread out.
+pub fn backoff_delay(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let ceiling = policy.backoff_ceiling(attempt).as_millis() as u64;
+    let mut bytes = [0u8; 8];
+    if ceiling == 0 || SystemRandom::new().fill(&mut bytes).is_err() {
+        return Duration::from_millis(ceiling);
+    }
+    Duration::from_millis(u64::from_be_bytes(bytes) % (ceiling + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(open_ms: u64) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "test",
+            CircuitBreakerPolicy {
+                failure_threshold: 2,
+                open_ms,
+            },
+        )
+    }
+
+    fn fail(breaker: &CircuitBreaker, times: u32) {
+        for _ in 0..times {
+            breaker.try_acquire().unwrap().record(false);
+        }
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures_and_fails_fast() {
+        let breaker = breaker(60_000);
+
+        fail(&breaker, 1);
+        breaker.try_acquire().unwrap().record(true);
+        fail(&breaker, 1);
+        assert_eq!(breaker.status().state, BreakerState::Closed);
+
+        fail(&breaker, 1);
+        assert_eq!(breaker.status().state, BreakerState::Open);
+        assert!(breaker.try_acquire().is_none());
+
+        let status = breaker.status();
+        assert_eq!(status.opened, 1);
+        assert_eq!(status.rejected, 1);
+        assert_eq!(status.failures, 3);
+    }
+
+    #[test]
+    fn half_open_trial_closes_or_reopens_the_circuit() {
+        let breaker = breaker(0);
+        fail(&breaker, 2);
+
+        let trial = breaker.try_acquire().unwrap();
+        assert_eq!(breaker.status().state, BreakerState::HalfOpen);
+        assert!(breaker.try_acquire().is_none());
+        trial.record(false);
+        assert_eq!(breaker.status().state, BreakerState::Open);
+        assert_eq!(breaker.status().opened, 2);
+
+        breaker.try_acquire().unwrap().record(true);
+        assert_eq!(breaker.status().state, BreakerState::Closed);
+        assert!(breaker.try_acquire().is_some());
+    }
+
+    #[test]
+    fn only_the_trial_permit_frees_the_trial_slot() {
+        let breaker = breaker(0);
+        let early = breaker.try_acquire().unwrap();
+        let late = breaker.try_acquire().unwrap();
+        fail(&breaker, 2);
+
+        let trial = breaker.try_acquire().unwrap();
+        // Permits from before the circuit opened don't free the trial slot
+        drop(early);
+        assert!(breaker.try_acquire().is_none());
+        late.record(false);
+        assert!(breaker.try_acquire().is_none());
+
+        // An abandoned trial does, so the circuit can't get stuck half-open
+        drop(trial);
+        assert!(breaker.try_acquire().is_some());
+    }
+
+    #[test]
+    fn backoff_stays_under_the_exponential_ceiling() {
+        let policy = RetryPolicy {
+            attempts: 5,
+            base_delay_ms: 100,
+            max_delay_ms: 1_000,
+        };
+
+        for (attempt, ceiling) in [
+            (1, 100),
+            (2, 200),
+            (3, 400),
+            (4, 800),
+            (5, 1_000),
+            (30, 1_000),
+        ] {
+            assert_eq!(
+                policy.backoff_ceiling(attempt),
+                Duration::from_millis(ceiling)
+            );
+            for _ in 0..50 {
+                assert!(backoff_delay(&policy, attempt) <= Duration::from_millis(ceiling));
+            }
+        }
+
+        let no_delay = RetryPolicy {
+            base_delay_ms: 0,
+            ..policy
+        };
+        assert_eq!(backoff_delay(&no_delay, 3), Duration::ZERO);
+    }
+}
@@ -1,16 +1,70 @@
 use actix_cors::Cors;
+use actix_web::middleware::from_fn;
 use actix_web::{App, HttpServer, web};
 use std::sync::Arc;
 use tracing::info;
 use tracing_actix_web::TracingLogger;
 
 use crate::application::handlers::health_check as handlers;
+use crate::application::handlers::jwks;
+use crate::application::handlers::metrics;
+use crate::application::handlers::oidc_callback;
+use crate::application::handlers::proxy;
+use crate::domain::gateway::rate_limit::{RateLimitConfig, RateLimitScope};
+use crate::domain::gateway::routes::ProxyRoute;
+use crate::domain::repositories::permission_repository::PermissionRepository;
+use crate::domain::repositories::rate_limit_repository::RateLimitRepository;
+use crate::infrastructure::adapters::graphql::federation::Federation;
 use crate::infrastructure::adapters::graphql::handlers::{graphql_handler, graphql_playground};
 use crate::infrastructure::adapters::graphql::schema::AppSchema;
+use crate::infrastructure::adapters::http::auth_middleware::resolve_caller;
+use crate::infrastructure::adapters::http::rate_limit_middleware::rate_limit;
+use crate::infrastructure::adapters::http::upstream_client::UpstreamClient;
+use crate::infrastructure::adapters::http::upstream_pool::UpstreamPools;
+use crate::infrastructure::adapters::jwt::JwtSigner;
+use crate::infrastructure::adapters::kratos::kratos_client::KratosClient;
 
-pub async fn start(schema: Arc<AppSchema>) -> std::io::Result<()> {
+/// The clients and repositories the HTTP handlers and middleware read.
+pub struct Services {
+    pub kratos_client: KratosClient,
+    pub jwt_signer: JwtSigner,
+    pub permissions: Arc<dyn PermissionRepository>,
+    pub rate_limits: Arc<dyn RateLimitRepository>,
+    pub rate_limit_config: Arc<RateLimitConfig>,
+}
+
+pub async fn start(
+    schema: Arc<AppSchema>,
+    services: Services,
+    routes: Vec<ProxyRoute>,
+    federation: Option<Federation>,
+) -> std::io::Result<()> {
+    let Services {
+        kratos_client,
+        jwt_signer,
+        permissions,
+        rate_limits,
+        rate_limit_config,
+    } = services;
     info!("Booting HTTP server at http://127.0.0.1:8080");
 
+    for route in &routes {
+        info!(
+            prefix = %route.prefix,
+            upstreams = ?route.upstreams,
+            balance = ?route.balance,
+            "Proxy route registered"
+        );
+    }
+    let upstream_client = UpstreamClient::new();
+    let pools = UpstreamPools::new(routes);
+    pools.spawn_health_checks(upstream_client.clone());
+    let federation = web::Data::new(federation);
+    let graphql_rate_limit = web::Data::new(RateLimitScope {
+        name: "graphql".to_string(),
+        rules: rate_limit_config.graphql.clone(),
+    });
+
     let server = HttpServer::new(move || {
         App::new()
             .wrap(TracingLogger::default())
@@ -21,12 +75,27 @@ pub async fn start(schema: Arc<AppSchema>) -> std::io::Result<()> {
                     .allow_any_header(),
             )
             .app_data(web::Data::from(schema.clone()))
+            .app_data(web::Data::new(kratos_client.clone()))
+            .app_data(web::Data::new(jwt_signer.clone()))
+            .app_data(web::Data::new(upstream_client.clone()))
+            .app_data(federation.clone())
+            .app_data(web::Data::new(pools.clone()))
+            .app_data(web::Data::from(permissions.clone()))
+            .app_data(web::Data::from(rate_limits.clone()))
+            .app_data(web::Data::from(rate_limit_config.clone()))
             .service(
                 web::resource("/graphql")
+                    .app_data(graphql_rate_limit.clone())
+                    .wrap(from_fn(resolve_caller))
+                    .wrap(from_fn(rate_limit))
                     .route(web::post().to(graphql_handler))
                     .route(web::get().to(graphql_playground)),
             )
             .configure(handlers::configure)
+            .configure(oidc_callback::configure)
+            .configure(jwks::configure)
+            .configure(metrics::configure)
+            .configure(|cfg| proxy::configure(cfg, &pools))
     })
     .bind(("127.0.0.1", 8080))?;
 
@@ -0,0 +1,97 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Method};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum UpstreamError {
+    #[error("Upstream did not answer within {0:?}")]
+    Timeout(Duration),
+    #[error("Upstream unreachable: {0}")]
+    Connect(String),
+    #[error("Upstream request failed: {0}")]
+    Request(String),
+}
+
+pub struct UpstreamResponse {
+    pub status: u16,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Vec<u8>,
+}
+
+/// Forwards proxied requests; redirects and cookies are passed through
+/// untouched for the client to handle.
+#[derive(Clone)]
+pub struct UpstreamClient {
+    client: Client,
+}
+
+impl UpstreamClient {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .cookie_store(false)
+            .redirect(reqwest::redirect::Policy::none())
+            .connect_timeout(Duration::from_secs(5))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self { client }
+    }
+
+    pub async fn send(
+        &self,
+        method: &str,
+        url: &str,
+        headers: Vec<(String, Vec<u8>)>,
+        body: Vec<u8>,
+        timeout: Duration,
+    ) -> Result<UpstreamResponse, UpstreamError> {
+        let method = Method::from_bytes(method.as_bytes())
+            .map_err(|e| UpstreamError::Request(e.to_string()))?;
+
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_bytes(&value),
+            ) {
+                header_map.append(name, value);
+            }
+        }
+
+        let map_err = |e: reqwest::Error| {
+            if e.is_timeout() {
+                UpstreamError::Timeout(timeout)
+            } else if e.is_connect() {
+                UpstreamError::Connect(e.to_string())
+            } else {
+                UpstreamError::Request(e.to_string())
+            }
+        };
+
+        let response = self
+            .client
+            .request(method, url)
+            .headers(header_map)
+            .body(body)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(map_err)?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.as_str().to_string(), value.as_bytes().to_vec()))
+            .collect();
+        let body = response.bytes().await.map_err(map_err)?.to_vec();
+
+        Ok(UpstreamResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
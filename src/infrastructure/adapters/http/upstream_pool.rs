@@ -0,0 +1,454 @@
+use crate::domain::gateway::routes::{BalanceStrategy, HealthCheck, ProxyRoute};
+use crate::infrastructure::adapters::http::resilience::{BreakerStatus, CircuitBreaker};
+use crate::infrastructure::adapters::http::upstream_client::UpstreamClient;
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{info, warn};
+
+struct Instance {
+    url: String,
+    /// Result of the last active health check; healthy until the first one.
+    healthy: AtomicBool,
+    consecutive_failures: AtomicU32,
+    /// Set by passive ejection; the instance rejoins once it has passed.
+    ejected_until: Mutex<Option<Instant>>,
+    in_flight: AtomicUsize,
+    requests: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl Instance {
+    fn is_ejected(&self, now: Instant) -> bool {
+        self.ejected_until
+            .lock()
+            .expect("ejection lock poisoned")
+            .is_some_and(|until| until > now)
+    }
+
+    fn is_available(&self, now: Instant) -> bool {
+        self.healthy.load(Ordering::Relaxed) && !self.is_ejected(now)
+    }
+}
+
+/// The upstream instances behind one proxy route.
+pub struct UpstreamPool {
+    route: ProxyRoute,
+    instances: Vec<Instance>,
+    next: AtomicUsize,
+    breaker: CircuitBreaker,
+}
+
+/// An instance picked for one request; it counts as in flight until dropped.
+pub struct SelectedUpstream<'a> {
+    pool: &'a UpstreamPool,
+    index: usize,
+}
+
+#[derive(Serialize)]
+pub struct PoolStatus {
+    pub prefix: String,
+    pub balance: BalanceStrategy,
+    pub breaker: BreakerStatus,
+    pub instances: Vec<InstanceStatus>,
+}
+
+#[derive(Serialize)]
+pub struct InstanceStatus {
+    pub url: String,
+    pub healthy: bool,
+    pub ejected: bool,
+    pub in_flight: usize,
+    pub consecutive_failures: u32,
+    pub requests: u64,
+    pub failures: u64,
+}
+
+impl UpstreamPool {
+    pub fn new(route: ProxyRoute) -> Self {
+        let instances = route
+            .upstreams
+            .iter()
+            .map(|url| Instance {
+                url: url.clone(),
+                healthy: AtomicBool::new(true),
+                consecutive_failures: AtomicU32::new(0),
+                ejected_until: Mutex::new(None),
+                in_flight: AtomicUsize::new(0),
+                requests: AtomicU64::new(0),
+                failures: AtomicU64::new(0),
+            })
+            .collect();
+
+        Self {
+            breaker: CircuitBreaker::new(route.prefix.clone(), route.circuit_breaker),
+            route,
+            instances,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn route(&self) -> &ProxyRoute {
+        &self.route
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    /// Picks a healthy, non-ejected instance, or `None` when there is none.
+    /// `identity_id` keys the consistent-hash strategy.
+    pub fn select(&self, identity_id: Option<&str>) -> Option<SelectedUpstream<'_>> {
+        let now = Instant::now();
+        let available: Vec<usize> = (0..self.instances.len())
+            .filter(|&index| self.instances[index].is_available(now))
+            .collect();
+        if available.is_empty() {
+            return None;
+        }
+
+        let index = match (self.route.balance, identity_id) {
+            (BalanceStrategy::ConsistentHash, Some(identity_id)) => {
+                self.highest_score(&available, identity_id)
+            }
+            (BalanceStrategy::LeastConnections, _) => self.least_in_flight(&available),
+            _ => available[self.next.fetch_add(1, Ordering::Relaxed) % available.len()],
+        };
+
+        self.instances[index]
+            .in_flight
+            .fetch_add(1, Ordering::Relaxed);
+        Some(SelectedUpstream { pool: self, index })
+    }
+
+    fn highest_score(&self, available: &[usize], identity_id: &str) -> usize {
+        available
+            .iter()
+            .copied()
+            .max_by_key(|&index| rendezvous_score(identity_id, &self.instances[index].url))
+            .expect("available is not empty")
+    }
+
+    fn least_in_flight(&self, available: &[usize]) -> usize {
+        // Start at a rotating offset so ties spread out
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..available.len())
+            .map(|offset| available[(start + offset) % available.len()])
+            .min_by_key(|&index| self.instances[index].in_flight.load(Ordering::Relaxed))
+            .expect("available is not empty")
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        let now = Instant::now();
+        PoolStatus {
+            prefix: self.route.prefix.clone(),
+            balance: self.route.balance,
+            breaker: self.breaker.status(),
+            instances: self
+                .instances
+                .iter()
+                .map(|instance| InstanceStatus {
+                    url: instance.url.clone(),
+                    healthy: instance.healthy.load(Ordering::Relaxed),
+                    ejected: instance.is_ejected(now),
+                    in_flight: instance.in_flight.load(Ordering::Relaxed),
+                    consecutive_failures: instance.consecutive_failures.load(Ordering::Relaxed),
+                    requests: instance.requests.load(Ordering::Relaxed),
+                    failures: instance.failures.load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
+
+    async fn check_health(&self, client: &UpstreamClient, health_check: &HealthCheck) {
+        let probes = self.instances.iter().map(|instance| async move {
+            let url = format!("{}{}", instance.url, health_check.path);
+            let healthy = client
+                .send("GET", &url, Vec::new(), Vec::new(), health_check.timeout())
+                .await
+                .is_ok_and(|response| (200..300).contains(&response.status));
+
+            let was_healthy = instance.healthy.swap(healthy, Ordering::Relaxed);
+            let (route, upstream) = (&self.route.prefix, &instance.url);
+            match (was_healthy, healthy) {
+                (true, false) => warn!(%route, %upstream, "Upstream failed its health check"),
+                (false, true) => info!(%route, %upstream, "Upstream is healthy again"),
+                _ => {}
+            }
+        });
+
+        join_all(probes).await;
+    }
+}
+
+impl SelectedUpstream<'_> {
+    pub fn url(&self) -> &str {
+        &self.instance().url
+    }
+
+    /// Records the outcome of the request for passive ejection.
+    pub fn report(&self, success: bool) {
+        let instance = self.instance();
+        instance.requests.fetch_add(1, Ordering::Relaxed);
+
+        if success {
+            instance.consecutive_failures.store(0, Ordering::Relaxed);
+            return;
+        }
+
+        instance.failures.fetch_add(1, Ordering::Relaxed);
+        let failures = instance
+            .consecutive_failures
+            .fetch_add(1, Ordering::Relaxed)
+            + 1;
+        if failures >= self.pool.route.max_failures {
+            instance.consecutive_failures.store(0, Ordering::Relaxed);
+            *instance
+                .ejected_until
+                .lock()
+                .expect("ejection lock poisoned") =
+                Some(Instant::now() + self.pool.route.ejection());
+            warn!(
+                route = %self.pool.route.prefix,
+                upstream = %instance.url,
+                failures,
+                ejection_ms = self.pool.route.ejection_ms,
+                "Upstream ejected after consecutive failures"
+            );
+        }
+    }
+
+    fn instance(&self) -> &Instance {
+        &self.pool.instances[self.index]
+    }
+}
+
+impl Drop for SelectedUpstream<'_> {
+    fn drop(&mut self) {
+        self.instance().in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Rendezvous hashing: every caller ranks instances by their own score, so
+/// an instance leaving the pool only moves the callers that were on it.
+fn rendezvous_score(key: &str, url: &str) -> u64 {
+    let digest = ring::digest::digest(
+        &ring::digest::SHA256,
+        format!("{}\n{}", key, url).as_bytes(),
+    );
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest.as_ref()[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+/// Every route's pool, shared by the proxy and the status endpoint.
+#[derive(Clone)]
+pub struct UpstreamPools(Arc<Vec<Arc<UpstreamPool>>>);
+
+impl UpstreamPools {
+    pub fn new(routes: Vec<ProxyRoute>) -> Self {
+        Self(Arc::new(
+            routes
+                .into_iter()
+                .map(|route| Arc::new(UpstreamPool::new(route)))
+                .collect(),
+        ))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<UpstreamPool>> {
+        self.0.iter()
+    }
+
+    pub fn status(&self) -> Vec<PoolStatus> {
+        self.0.iter().map(|pool| pool.status()).collect()
+    }
+
+    /// Probes every route with a `health_check` on its interval, for the
+    /// lifetime of the server.
+    pub fn spawn_health_checks(&self, client: UpstreamClient) {
+        for pool in self.0.iter() {
+            let Some(health_check) = pool.route.health_check.clone() else {
+                continue;
+            };
+            let pool = pool.clone();
+            let client = client.clone();
+
+            actix_web::rt::spawn(async move {
+                let mut interval = actix_web::rt::time::interval(health_check.interval());
+                loop {
+                    interval.tick().await;
+                    pool.check_health(&client, &health_check).await;
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, HttpResponse, HttpServer, web};
+    use std::collections::HashMap;
+
+    const UPSTREAMS: [&str; 3] = ["http://a:8000", "http://b:8000", "http://c:8000"];
+
+    fn pool(balance: &str, upstreams: &[&str]) -> UpstreamPool {
+        pool_ejecting_for(balance, upstreams, 60_000)
+    }
+
+    fn pool_ejecting_for(balance: &str, upstreams: &[&str], ejection_ms: u64) -> UpstreamPool {
+        let route: ProxyRoute = serde_json::from_value(serde_json::json!({
+            "prefix": "/api",
+            "upstreams": upstreams,
+            "balance": balance,
+            "max_failures": 2,
+            "ejection_ms": ejection_ms,
+        }))
+        .unwrap();
+        UpstreamPool::new(route)
+    }
+
+    fn pick(pool: &UpstreamPool, identity_id: Option<&str>) -> String {
+        pool.select(identity_id).unwrap().url().to_string()
+    }
+
+    fn eject(pool: &UpstreamPool, url: &str) {
+        let upstream = std::iter::repeat_with(|| pool.select(None).unwrap())
+            .find(|upstream| upstream.url() == url)
+            .unwrap();
+        for _ in 0..pool.route().max_failures {
+            upstream.report(false);
+        }
+    }
+
+    #[test]
+    fn round_robin_cycles_through_the_instances() {
+        let pool = pool("round_robin", &UPSTREAMS);
+
+        let picks: Vec<String> = (0..6).map(|_| pick(&pool, Some("identity-1"))).collect();
+        assert_eq!(picks[..3], UPSTREAMS);
+        assert_eq!(picks[3..], UPSTREAMS);
+    }
+
+    #[test]
+    fn least_connections_prefers_the_idlest_instance() {
+        let pool = pool("least_connections", &UPSTREAMS);
+
+        let first = pool.select(None).unwrap();
+        let second = pool.select(None).unwrap();
+        let third = pool.select(None).unwrap();
+        let mut held = vec![first.url(), second.url(), third.url()];
+        held.sort();
+        assert_eq!(held, UPSTREAMS);
+
+        // Only the instance whose request finished is idle
+        let idle = second.url().to_string();
+        drop(second);
+        assert_eq!(pick(&pool, None), idle);
+        drop((first, third));
+    }
+
+    #[test]
+    fn consistent_hash_only_moves_callers_of_a_removed_instance() {
+        let pool = pool("consistent_hash", &UPSTREAMS);
+        let identities: Vec<String> = (0..60).map(|i| format!("identity-{}", i)).collect();
+
+        let before: HashMap<&str, String> = identities
+            .iter()
+            .map(|identity| (identity.as_str(), pick(&pool, Some(identity))))
+            .collect();
+        for identity in &identities {
+            assert_eq!(pick(&pool, Some(identity)), before[identity.as_str()]);
+        }
+        // Every instance gets a share
+        for upstream in UPSTREAMS {
+            assert!(before.values().any(|url| url == upstream));
+        }
+
+        eject(&pool, UPSTREAMS[1]);
+        for identity in &identities {
+            let after = pick(&pool, Some(identity));
+            if before[identity.as_str()] == UPSTREAMS[1] {
+                assert_ne!(after, UPSTREAMS[1]);
+            } else {
+                assert_eq!(after, before[identity.as_str()]);
+            }
+        }
+    }
+
+    #[test]
+    fn ejects_after_consecutive_failures_until_the_ejection_ends() {
+        let pool = pool_ejecting_for("round_robin", &UPSTREAMS[..2], 50);
+
+        // A success in between resets the count
+        let upstream = pool.select(None).unwrap();
+        assert_eq!(upstream.url(), UPSTREAMS[0]);
+        upstream.report(false);
+        upstream.report(true);
+        upstream.report(false);
+        assert!(!pool.status().instances[0].ejected);
+
+        upstream.report(false);
+        drop(upstream);
+        let status = pool.status();
+        assert!(status.instances[0].ejected);
+        assert_eq!(status.instances[0].failures, 3);
+        for _ in 0..4 {
+            assert_eq!(pick(&pool, None), UPSTREAMS[1]);
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(60));
+        let picks: Vec<String> = (0..2).map(|_| pick(&pool, None)).collect();
+        assert!(picks.iter().any(|url| url == UPSTREAMS[0]));
+    }
+
+    #[test]
+    fn no_instance_is_selected_once_all_are_out() {
+        let pool = pool("round_robin", &UPSTREAMS[..1]);
+        eject(&pool, UPSTREAMS[0]);
+        assert!(pool.select(Some("identity-1")).is_none());
+    }
+
+    #[actix_web::test]
+    async fn health_checks_take_instances_out_and_back_in() {
+        let healthy = Arc::new(AtomicBool::new(false));
+        let server = {
+            let healthy = healthy.clone();
+            HttpServer::new(move || {
+                let healthy = healthy.clone();
+                App::new().route(
+                    "/health",
+                    web::get().to(move || {
+                        let healthy = healthy.load(Ordering::SeqCst);
+                        async move {
+                            if healthy {
+                                HttpResponse::Ok().finish()
+                            } else {
+                                HttpResponse::ServiceUnavailable().finish()
+                            }
+                        }
+                    }),
+                )
+            })
+            .workers(1)
+            .bind(("127.0.0.1", 0))
+            .unwrap()
+        };
+        let url = format!("http://{}", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+
+        let pool = pool("round_robin", &[&url]);
+        let health_check: HealthCheck =
+            serde_json::from_value(serde_json::json!({ "path": "/health" })).unwrap();
+        let client = UpstreamClient::new();
+
+        pool.check_health(&client, &health_check).await;
+        assert!(pool.select(None).is_none());
+
+        healthy.store(true, Ordering::SeqCst);
+        pool.check_health(&client, &health_check).await;
+        assert_eq!(pick(&pool, None), url);
+    }
+}
@@ -0,0 +1,22 @@
+use crate::domain::auth::errors::AuthError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum JwtError {
+    #[error("Unsupported JWT algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("Invalid JWT signing key: {0}")]
+    InvalidKey(String),
+    #[error("Failed to sign JWT")]
+    Signing,
+    #[error("Failed to encode JWT claims: {0}")]
+    Encoding(String),
+    #[error("Invalid access token: {0}")]
+    InvalidToken(String),
+}
+
+impl From<JwtError> for AuthError {
+    fn from(e: JwtError) -> Self {
+        AuthError::Internal(e.to_string())
+    }
+}
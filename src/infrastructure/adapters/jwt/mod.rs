@@ -0,0 +1,5 @@
+pub mod error;
+pub mod jwt_signer;
+
+pub use error::JwtError;
+pub use jwt_signer::JwtSigner;
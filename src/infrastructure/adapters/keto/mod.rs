@@ -0,0 +1,3 @@
+pub mod permission_repository;
+
+pub use permission_repository::KetoPermissionRepository;
@@ -0,0 +1,63 @@
+use crate::domain::repositories::permission_repository::{PermissionRepository, RelationTuple};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Deserialize)]
+struct CheckResponse {
+    allowed: bool,
+}
+
+/// Checks permissions against the read API of Ory Keto (or anything speaking
+/// its REST protocol).
+#[derive(Clone)]
+pub struct KetoPermissionRepository {
+    client: Client,
+    read_url: String,
+}
+
+impl KetoPermissionRepository {
+    pub fn new(read_url: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            client,
+            read_url: read_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl PermissionRepository for KetoPermissionRepository {
+    async fn check(&self, tuple: &RelationTuple) -> Result<bool, String> {
+        // The `/openapi` variant answers denials with 200 instead of 403
+        let url = format!("{}/relation-tuples/check/openapi", self.read_url)
+            .replace("localhost", "127.0.0.1");
+
+        let response = self
+            .client
+            .post(url)
+            .json(tuple)
+            .send()
+            .await
+            .map_err(|e| format!("Keto check endpoint: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Keto returned {}: {}", status, error_text));
+        }
+
+        let check: CheckResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Keto check response: {}", e))?;
+
+        Ok(check.allowed)
+    }
+}
@@ -0,0 +1,190 @@
+use crate::domain::auth::errors::{AuthError, FieldError};
+use reqwest::StatusCode;
+use thiserror::Error;
+use tracing::error;
+
+// Kratos UI message ids, see https://www.ory.sh/docs/kratos/concepts/ui-messages
+const MESSAGE_INVALID_CREDENTIALS: u64 = 4000006;
+const MESSAGE_DUPLICATE_IDENTIFIER: u64 = 4000007;
+const MESSAGE_FLOW_EXPIRED: u64 = 4010001;
+
+#[derive(Debug, Error)]
+pub enum KratosError {
+    #[error("Failed to connect to Kratos: {0}")]
+    Network(String),
+    #[error("Kratos is failing, requests are rejected until it recovers")]
+    CircuitOpen,
+    #[error("Self-service flow expired")]
+    FlowExpired,
+    #[error("CSRF verification failed")]
+    CsrfViolation,
+    #[error("Invalid credentials")]
+    InvalidCredentials(Vec<FieldError>),
+    #[error("An account with the same identifier already exists")]
+    DuplicateIdentifier(Vec<FieldError>),
+    #[error("Validation failed")]
+    Validation(Vec<FieldError>),
+    #[error("Already logged in")]
+    AlreadyAuthenticated,
+    #[error("Privileged session expired, re-authentication required")]
+    ReauthenticationRequired,
+    #[error("Second authentication factor required")]
+    Aal2Required,
+    #[error("Not logged in")]
+    Unauthorized,
+    #[error("Resource not found")]
+    NotFound,
+    #[error("Kratos upstream error (status {status}): {message}")]
+    Upstream { status: u16, message: String },
+    #[error("Unexpected Kratos response: {0}")]
+    InvalidResponse(String),
+}
+
+impl KratosError {
+    /// Classifies a non-success Kratos response by its status and body.
+    pub fn from_response(status: StatusCode, body: &str) -> Self {
+        if status.is_server_error() {
+            return KratosError::Upstream {
+                status: status.as_u16(),
+                message: body.to_string(),
+            };
+        }
+
+        let json: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+
+        // Generic error payloads: {"error": {"id": "...", "reason": "..."}}
+        if let Some(error_id) = json["error"]["id"].as_str() {
+            match error_id {
+                "self_service_flow_expired" => return KratosError::FlowExpired,
+                "security_csrf_violation" => return KratosError::CsrfViolation,
+                "session_already_available" => return KratosError::AlreadyAuthenticated,
+                "session_refresh_required" => return KratosError::ReauthenticationRequired,
+                "session_aal2_required" => return KratosError::Aal2Required,
+                "session_inactive" | "no_active_session" => return KratosError::Unauthorized,
+                _ => {}
+            }
+        }
+
+        if status == StatusCode::GONE {
+            return KratosError::FlowExpired;
+        }
+
+        if status == StatusCode::UNAUTHORIZED {
+            return KratosError::Unauthorized;
+        }
+
+        if status == StatusCode::NOT_FOUND {
+            return KratosError::NotFound;
+        }
+
+        // Admin API: an identity with the same identifier already exists
+        if status == StatusCode::CONFLICT {
+            return KratosError::DuplicateIdentifier(Vec::new());
+        }
+
+        // Flow payloads: {"ui": {"messages": [...], "nodes": [...]}}
+        if json.get("ui").is_some() {
+            let messages = Self::parse_flow_messages(&json);
+            let has_message = |id| messages.iter().any(|m| m.id == id);
+
+            if has_message(MESSAGE_FLOW_EXPIRED) {
+                return KratosError::FlowExpired;
+            }
+            if has_message(MESSAGE_INVALID_CREDENTIALS) {
+                return KratosError::InvalidCredentials(messages);
+            }
+            if has_message(MESSAGE_DUPLICATE_IDENTIFIER) {
+                return KratosError::DuplicateIdentifier(messages);
+            }
+            if messages.iter().any(|m| m.message_type == "error") {
+                return KratosError::Validation(messages);
+            }
+        }
+
+        let message = json["error"]["reason"]
+            .as_str()
+            .or_else(|| json["error"]["message"].as_str())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| body.to_string());
+
+        KratosError::InvalidResponse(format!("status {}: {}", status, message))
+    }
+
+    /// Collects `ui.messages` and every `ui.nodes[*].messages` entry of a flow.
+    pub fn parse_flow_messages(flow: &serde_json::Value) -> Vec<FieldError> {
+        let to_field_error = |message: &serde_json::Value, field: Option<&str>| FieldError {
+            field: field.map(|s| s.to_string()),
+            id: message["id"].as_u64().unwrap_or_default(),
+            text: message["text"].as_str().unwrap_or_default().to_string(),
+            message_type: message["type"].as_str().unwrap_or("error").to_string(),
+        };
+
+        let mut messages: Vec<FieldError> = flow["ui"]["messages"]
+            .as_array()
+            .map(|ui_messages| {
+                ui_messages
+                    .iter()
+                    .map(|m| to_field_error(m, None))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if let Some(nodes) = flow["ui"]["nodes"].as_array() {
+            for node in nodes {
+                let field = node["attributes"]["name"].as_str();
+                if let Some(node_messages) = node["messages"].as_array() {
+                    messages.extend(node_messages.iter().map(|m| to_field_error(m, field)));
+                }
+            }
+        }
+
+        messages
+    }
+}
+
+impl From<reqwest::Error> for KratosError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            KratosError::InvalidResponse(e.to_string())
+        } else {
+            KratosError::Network(e.to_string())
+        }
+    }
+}
+
+impl From<&str> for KratosError {
+    fn from(message: &str) -> Self {
+        KratosError::InvalidResponse(message.to_string())
+    }
+}
+
+impl From<KratosError> for AuthError {
+    fn from(e: KratosError) -> Self {
+        // Kratos bodies and URLs stay in the logs, clients only get the status
+        match e {
+            KratosError::Network(message) => {
+                error!(error = %message, "Kratos unreachable");
+                AuthError::ServiceUnavailable("Kratos unreachable".to_string())
+            }
+            KratosError::CircuitOpen => AuthError::ServiceUnavailable(e.to_string()),
+            KratosError::Upstream { status, message } => {
+                error!(status, body = %message, "Kratos upstream error");
+                AuthError::ServiceUnavailable(format!("Kratos returned status {}", status))
+            }
+            KratosError::FlowExpired => AuthError::FlowExpired,
+            KratosError::CsrfViolation => AuthError::CsrfViolation,
+            KratosError::InvalidCredentials(errors) => AuthError::InvalidCredentials(errors),
+            KratosError::DuplicateIdentifier(errors) => AuthError::DuplicateIdentifier(errors),
+            KratosError::Validation(errors) => AuthError::Validation(errors),
+            KratosError::AlreadyAuthenticated => AuthError::AlreadyAuthenticated,
+            KratosError::ReauthenticationRequired => AuthError::ReauthenticationRequired,
+            KratosError::Aal2Required => AuthError::SecondFactorRequired,
+            KratosError::Unauthorized => AuthError::NotAuthenticated,
+            KratosError::NotFound => AuthError::NotFound("Resource".to_string()),
+            KratosError::InvalidResponse(message) => {
+                error!(error = %message, "Unexpected Kratos response");
+                AuthError::Internal("Unexpected Kratos response".to_string())
+            }
+        }
+    }
+}
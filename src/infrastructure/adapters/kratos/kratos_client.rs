@@ -1,13 +1,84 @@
-use reqwest::StatusCode;
-use reqwest::{Client, header};
+use crate::domain::auth::inputs::{ClientType, WebauthnMethod};
+use crate::domain::gateway::resilience::{CircuitBreakerPolicy, RetryPolicy};
+use crate::infrastructure::adapters::http::resilience::{CircuitBreaker, backoff_delay};
+use crate::infrastructure::adapters::kratos::error::KratosError;
+use reqwest::{Client, Method, RequestBuilder, header};
+use reqwest::{Response, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use uuid::Uuid;
+
+/// How long the provider list read from a login flow is reused; providers
+/// only change with the Kratos configuration.
+const OIDC_PROVIDERS_TTL: Duration = Duration::from_secs(300);
+
+type CachedProviders = Option<(Instant, Vec<OidcProvider>)>;
 
 #[derive(Clone)]
 pub struct KratosClient {
     client: Client,
     admin_url: String,
     public_url: String,
+    resilience: KratosResilience,
+    breaker: Arc<CircuitBreaker>,
+    oidc_providers: Arc<Mutex<CachedProviders>>,
+}
+
+/// Kinds of Kratos calls, each with its own timeout.
+#[derive(Debug, Clone, Copy)]
+enum KratosCall {
+    /// Creating or reading a self-service flow.
+    Flow,
+    /// Submitting a flow, OIDC callbacks and logout.
+    Submit,
+    /// `whoami` and the session list.
+    Session,
+    /// Admin API.
+    Admin,
+}
+
+impl KratosCall {
+    /// Only `GET`s of these are retried; submissions may have side effects.
+    fn is_retryable(&self) -> bool {
+        !matches!(self, KratosCall::Submit)
+    }
+}
+
+/// Timeouts, retries and circuit breaking for every call to Kratos.
+#[derive(Debug, Clone, Copy)]
+pub struct KratosResilience {
+    pub flow_timeout: Duration,
+    pub submit_timeout: Duration,
+    pub session_timeout: Duration,
+    pub admin_timeout: Duration,
+    pub retry: RetryPolicy,
+    pub breaker: CircuitBreakerPolicy,
+}
+
+impl Default for KratosResilience {
+    fn default() -> Self {
+        Self {
+            flow_timeout: Duration::from_secs(5),
+            submit_timeout: Duration::from_secs(10),
+            session_timeout: Duration::from_secs(3),
+            admin_timeout: Duration::from_secs(10),
+            retry: RetryPolicy::default(),
+            breaker: CircuitBreakerPolicy::default(),
+        }
+    }
+}
+
+impl KratosResilience {
+    fn timeout(&self, call: KratosCall) -> Duration {
+        match call {
+            KratosCall::Flow => self.flow_timeout,
+            KratosCall::Submit => self.submit_timeout,
+            KratosCall::Session => self.session_timeout,
+            KratosCall::Admin => self.admin_timeout,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +88,32 @@ pub struct KratosIdentity {
     pub traits: IdentityTraits,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub verifiable_addresses: Vec<VerifiableAddress>,
+    /// `active` or `inactive`; inactive identities cannot log in.
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Gateway roles, kept in `metadata_public.roles` so only the admin API can set them.
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+impl KratosIdentity {
+    /// Whether the address in the `email` trait has been verified.
+    pub fn is_email_verified(&self) -> bool {
+        self.verifiable_addresses.iter().any(|address| {
+            address.verified && address.value.eq_ignore_ascii_case(&self.traits.email)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifiableAddress {
+    pub value: String,
+    pub verified: bool,
+    pub verified_at: Option<String>,
+    pub status: String,
+    pub via: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,13 +129,210 @@ pub struct KratosSession {
     pub id: String,
     pub active: bool,
     pub identity: KratosIdentity,
+    pub authenticated_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub authenticator_assurance_level: Option<String>,
+    pub authentication_methods: Vec<String>,
+    pub issued_at: Option<String>,
+    /// Devices (IP / user agent) the session was used from, oldest first.
+    #[serde(default)]
+    pub devices: Vec<SessionDevice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDevice {
+    pub id: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub location: Option<String>,
+}
+
+/// Kratos self-service flow flavour: cookie/CSRF based browser flows or
+/// native (API) flows that hand out a session token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowType {
+    Browser,
+    Api,
+}
+
+impl FlowType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FlowType::Browser => "browser",
+            FlowType::Api => "api",
+        }
+    }
+}
+
+impl From<ClientType> for FlowType {
+    fn from(client_type: ClientType) -> Self {
+        match client_type {
+            ClientType::Browser => FlowType::Browser,
+            ClientType::Native => FlowType::Api,
+        }
+    }
+}
+
+/// How the caller proves its Kratos session.
+#[derive(Debug, Clone, Copy)]
+pub enum SessionCredentials<'a> {
+    Cookie(&'a str),
+    Token(&'a str),
+}
+
+impl<'a> SessionCredentials<'a> {
+    pub fn cookie(&self) -> Option<&'a str> {
+        match self {
+            SessionCredentials::Cookie(cookie) => Some(cookie),
+            SessionCredentials::Token(_) => None,
+        }
+    }
+
+    pub fn token(&self) -> Option<&'a str> {
+        match self {
+            SessionCredentials::Cookie(_) => None,
+            SessionCredentials::Token(token) => Some(token),
+        }
+    }
+
+    /// Session-bound flows (settings) must match how the session was issued.
+    pub fn flow_type(&self) -> FlowType {
+        match self {
+            SessionCredentials::Cookie(_) => FlowType::Browser,
+            SessionCredentials::Token(_) => FlowType::Api,
+        }
+    }
+
+    fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            SessionCredentials::Cookie(cookie) => request.header(header::COOKIE, *cookie),
+            SessionCredentials::Token(token) => request.header("X-Session-Token", *token),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoginResult {
+    pub session: KratosSession,
+    /// Only set for API flows.
+    pub session_token: Option<String>,
+    pub cookies: Vec<String>,
+    /// Set when the password login only reached AAL1 but Kratos requires AAL2.
+    pub second_factor: Option<SecondFactorChallenge>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SecondFactorChallenge {
+    pub flow_id: String,
+    /// Methods offered by the AAL2 login flow, e.g. `totp` or `lookup_secret`.
+    pub methods: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SecondFactor<'a> {
+    Totp(&'a str),
+    LookupSecret(&'a str),
+}
+
+#[derive(Debug, Clone)]
+pub struct TotpEnrollment {
+    pub flow_id: String,
+    /// `data:image/png;base64,...` QR code for authenticator apps.
+    pub qr_code: Option<String>,
+    pub secret: Option<String>,
+    pub cookies: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecoveryResult {
+    /// Settings flow Kratos continues with so the user can pick a new password.
+    pub settings_flow_id: Option<String>,
+    /// Only set for API flows.
+    pub session_token: Option<String>,
+    pub cookies: Vec<String>,
+}
+
+/// WebAuthn ceremony options taken from a flow's `webauthn_*_trigger` or
+/// `passkey_*` node.
+#[derive(Debug, Clone)]
+pub struct WebauthnChallenge {
+    pub flow_id: String,
+    /// `PublicKeyCredentialCreationOptions` or `PublicKeyCredentialRequestOptions`
+    /// as produced by Kratos, to be passed to `navigator.credentials`.
+    pub options: serde_json::Value,
+    pub cookies: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebauthnCredential {
+    pub id: String,
+    pub display_name: String,
+    pub added_at: Option<String>,
+    pub is_passwordless: bool,
+}
+
+/// One page of `GET /admin/identities`.
+#[derive(Debug, Clone)]
+pub struct IdentityPage {
+    pub identities: Vec<KratosIdentity>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewIdentity {
+    pub traits: IdentityTraits,
+    pub password: Option<String>,
+    /// Pre-hashed password in PHC / modular crypt format (bcrypt, argon2),
+    /// imported without re-hashing.
+    pub password_hash: Option<String>,
+    pub state: Option<String>,
+    pub roles: Vec<String>,
+    /// Marks the email address as already verified.
+    pub verified: bool,
+}
+
+/// Admin changes to an identity; `None` fields are left untouched.
+#[derive(Debug, Clone, Default)]
+pub struct IdentityUpdate {
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub geo_location: Option<String>,
+    pub state: Option<String>,
+    pub roles: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OidcProvider {
+    pub id: String,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OidcLoginStart {
+    pub flow_id: String,
+    /// Provider authorization URL the browser has to be sent to.
+    pub redirect_url: String,
+    /// CSRF and continuity cookies Kratos needs back on the callback.
+    pub cookies: Vec<String>,
+}
+
+/// Kratos' answer to an OIDC callback, replayed to the browser as-is.
+#[derive(Debug, Clone)]
+pub struct OidcCallbackResult {
+    pub status: u16,
+    pub location: Option<String>,
+    pub cookies: Vec<String>,
+    pub body: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct FlowResult {
     pub flow: serde_json::Value,
     pub csrf_token: String,
+    /// Cookies to send back to Kratos when submitting the flow.
     pub cookies: Vec<String>,
+    /// `Set-Cookie` headers Kratos issued while creating the flow (CSRF cookie).
+    pub issued_cookies: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -52,7 +346,6 @@ impl KratosClient {
         let client = Client::builder()
             .cookie_store(false)
             .redirect(reqwest::redirect::Policy::none())
-            .timeout(Duration::from_secs(30))
             .connect_timeout(Duration::from_secs(10))
             .pool_idle_timeout(Duration::from_secs(90))
             .pool_max_idle_per_host(10)
@@ -60,42 +353,142 @@ impl KratosClient {
             .build()
             .expect("Failed to build HTTP client");
 
+        let resilience = KratosResilience::default();
         Self {
             client,
             admin_url,
             public_url,
+            resilience,
+            breaker: Arc::new(CircuitBreaker::new("kratos", resilience.breaker)),
+            oidc_providers: Arc::default(),
         }
     }
 
-    async fn check_active_session(&self, cookie: Option<&str>) -> bool {
-        if let Some(cookie_value) = cookie {
-            if let Ok(_) = self.handle_get_current_user(cookie_value).await {
-                return true;
+    pub fn with_resilience(self, resilience: KratosResilience) -> Self {
+        Self {
+            resilience,
+            breaker: Arc::new(CircuitBreaker::new("kratos", resilience.breaker)),
+            ..self
+        }
+    }
+
+    pub fn breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    /// Sends `request` with the call's timeout through the circuit breaker.
+    /// Retryable `GET`s are repeated with backoff on network errors and 5xx;
+    /// other responses are returned as they are for the caller to classify.
+    async fn send(
+        &self,
+        call: KratosCall,
+        endpoint: &str,
+        request: RequestBuilder,
+    ) -> Result<Response, KratosError> {
+        let network_error =
+            |e: reqwest::Error| KratosError::Network(format!("{}: {}", endpoint, e));
+
+        let mut request = request
+            .timeout(self.resilience.timeout(call))
+            .build()
+            .map_err(network_error)?;
+        let attempts = if call.is_retryable() && request.method() == Method::GET {
+            self.resilience.retry.attempts
+        } else {
+            1
+        };
+
+        let mut permit = self.breaker.try_acquire().ok_or(KratosError::CircuitOpen)?;
+        let mut attempt = 1;
+        loop {
+            let retry = if attempt < attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+
+            let result = self.client.execute(request).await;
+            let failed = result
+                .as_ref()
+                .map_or(true, |response| response.status().is_server_error());
+            permit.record(!failed);
+
+            let Some(next) = retry.filter(|_| failed) else {
+                return result.map_err(network_error);
+            };
+            let delay = backoff_delay(&self.resilience.retry, attempt);
+            warn!(
+                endpoint,
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                "Retrying Kratos call"
+            );
+            actix_web::rt::time::sleep(delay).await;
+
+            // The circuit may have opened meanwhile; then the last failure stands
+            match self.breaker.try_acquire() {
+                Some(next_permit) => permit = next_permit,
+                None => return result.map_err(network_error),
             }
+            self.breaker.record_retry();
+            request = next;
+            attempt += 1;
+        }
+    }
+
+    async fn check_active_session(&self, credentials: Option<SessionCredentials<'_>>) -> bool {
+        if let Some(credentials) = credentials
+            && self.handle_get_current_user(credentials).await.is_ok()
+        {
+            return true;
         }
         false
     }
 
+    fn extract_csrf_token(
+        flow: &serde_json::Value,
+        flow_type: FlowType,
+    ) -> Result<String, KratosError> {
+        let csrf_token = flow["ui"]["nodes"]
+            .as_array()
+            .and_then(|nodes| {
+                nodes
+                    .iter()
+                    .find(|node| node["attributes"]["name"].as_str() == Some("csrf_token"))
+            })
+            .and_then(|node| node["attributes"]["value"].as_str());
+
+        match (csrf_token, flow_type) {
+            (Some(token), _) => Ok(token.to_string()),
+            // API flows are not protected by CSRF tokens
+            (None, FlowType::Api) => Ok(String::new()),
+            (None, FlowType::Browser) => Err("CSRF token not found in flow response".into()),
+        }
+    }
+
     async fn fetch_flow(
         &self,
         endpoint: &str,
-        cookie: Option<&str>,
-    ) -> Result<FlowResult, Box<dyn std::error::Error>> {
-        let url = format!("{}/self-service/{}/browser", self.public_url, endpoint);
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+        query: &[(&str, &str)],
+    ) -> Result<FlowResult, KratosError> {
+        let url = format!(
+            "{}/self-service/{}/{}",
+            self.public_url,
+            endpoint,
+            flow_type.as_str()
+        );
         let url = url.replace("localhost", "127.0.0.1");
+        let cookie = credentials.and_then(|c| c.cookie());
 
-        let mut request = self.client.get(&url);
+        let mut request = self.client.get(&url).query(query);
 
-        if let Some(cookie_value) = cookie {
-            request = request.header(header::COOKIE, cookie_value);
+        if let Some(credentials) = credentials {
+            request = credentials.apply(request);
         }
 
-        let response = request.send().await.map_err(|e| {
-            format!(
-                "Failed to connect to Kratos at {}: {}. Make sure Kratos is running.",
-                url, e
-            )
-        })?;
+        let response = self.send(KratosCall::Flow, &url, request).await?;
 
         let status = response.status();
         let flow_cookies: Vec<String> = response
@@ -118,64 +511,23 @@ impl KratosClient {
                 .nth(1)
                 .ok_or("Flow ID not found in redirect URL")?;
 
-            let flow_url = format!(
-                "{}/self-service/{}/flows?id={}",
-                self.public_url.replace("localhost", "127.0.0.1"),
-                endpoint,
-                flow_id
-            );
-
-            let mut flow_request = self.client.get(&flow_url);
-
-            if !flow_cookies.is_empty() {
-                flow_request = flow_request.header(header::COOKIE, flow_cookies.join("; "));
-            } else if let Some(cookie_value) = cookie {
-                flow_request = flow_request.header(header::COOKIE, cookie_value);
-            }
-
-            let flow_response = flow_request.send().await?;
-
-            if !flow_response.status().is_success() {
-                let status = flow_response.status();
+            // Settings flows are bound to the session, so keep the caller's cookie
+            // alongside the freshly issued CSRF cookie
+            let all_cookies: Vec<String> = cookie
+                .map(|c| c.to_string())
+                .into_iter()
+                .chain(flow_cookies.iter().cloned())
+                .collect();
 
-                let error_text = flow_response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Unknown error".to_string());
-
-                return Err(format!(
-                    "Failed to fetch {} flow (status {}): {}",
-                    endpoint, status, error_text
-                )
-                .into());
-            }
+            let flow = self.get_flow(endpoint, flow_id, &all_cookies, None).await?;
 
-            let flow: serde_json::Value = flow_response
-                .json()
-                .await
-                .map_err(|e| format!("Failed to parse {} flow response: {}", endpoint, e))?;
-
-            let csrf_token = flow["ui"]["nodes"]
-                .as_array()
-                .and_then(|nodes| {
-                    nodes
-                        .iter()
-                        .find(|node| node["attributes"]["name"].as_str() == Some("csrf_token"))
-                })
-                .and_then(|node| node["attributes"]["value"].as_str())
-                .ok_or("CSRF token not found in flow response")?
-                .to_string();
-
-            let mut all_cookies = Vec::new();
-            if let Some(existing_cookie) = cookie {
-                all_cookies.push(existing_cookie.to_string());
-            }
-            all_cookies.extend(flow_cookies);
+            let csrf_token = Self::extract_csrf_token(&flow, flow_type)?;
 
             return Ok(FlowResult {
                 flow,
                 csrf_token,
                 cookies: all_cookies,
+                issued_cookies: flow_cookies,
             });
         }
 
@@ -184,39 +536,107 @@ impl KratosClient {
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!(
-                "Failed to fetch {} flow (status {}): {}",
-                endpoint, status, error_text
-            )
-            .into());
+            return Err(KratosError::from_response(status, &error_text));
         }
 
-        let flow: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse {} flow response: {}", endpoint, e))?;
+        let flow: serde_json::Value = response.json().await.map_err(|e| {
+            KratosError::InvalidResponse(format!(
+                "Failed to parse {} flow response: {}",
+                endpoint, e
+            ))
+        })?;
 
-        let csrf_token = flow["ui"]["nodes"]
-            .as_array()
-            .and_then(|nodes| {
-                nodes
-                    .iter()
-                    .find(|node| node["attributes"]["name"].as_str() == Some("csrf_token"))
-            })
-            .and_then(|node| node["attributes"]["value"].as_str())
-            .ok_or("CSRF token not found in flow response")?
-            .to_string();
+        let csrf_token = Self::extract_csrf_token(&flow, flow_type)?;
 
         let mut all_cookies = Vec::new();
         if let Some(existing_cookie) = cookie {
             all_cookies.push(existing_cookie.to_string());
         }
-        all_cookies.extend(flow_cookies);
+        all_cookies.extend(flow_cookies.iter().cloned());
 
         Ok(FlowResult {
             flow,
             csrf_token,
             cookies: all_cookies,
+            issued_cookies: flow_cookies,
+        })
+    }
+
+    async fn get_flow(
+        &self,
+        endpoint: &str,
+        flow_id: &str,
+        cookies: &[String],
+        session_token: Option<&str>,
+    ) -> Result<serde_json::Value, KratosError> {
+        let flow_url = format!(
+            "{}/self-service/{}/flows?id={}",
+            self.public_url.replace("localhost", "127.0.0.1"),
+            endpoint,
+            flow_id
+        );
+
+        let mut flow_request = self.client.get(&flow_url);
+
+        if !cookies.is_empty() {
+            flow_request = flow_request.header(header::COOKIE, cookies.join("; "));
+        }
+
+        if let Some(token) = session_token {
+            flow_request = flow_request.header("X-Session-Token", token);
+        }
+
+        let flow_response = self.send(KratosCall::Flow, &flow_url, flow_request).await?;
+
+        if !flow_response.status().is_success() {
+            let status = flow_response.status();
+
+            let error_text = flow_response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+
+            return Err(KratosError::from_response(status, &error_text));
+        }
+
+        flow_response.json().await.map_err(|e| {
+            KratosError::InvalidResponse(format!(
+                "Failed to parse {} flow response: {}",
+                endpoint, e
+            ))
+        })
+    }
+
+    /// Loads a flow the client started earlier, e.g. to submit a recovery code.
+    pub async fn fetch_existing_flow(
+        &self,
+        endpoint: &str,
+        flow_id: &str,
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<FlowResult, KratosError> {
+        let cookies: Vec<String> = credentials
+            .and_then(|c| c.cookie())
+            .map(|c| c.to_string())
+            .into_iter()
+            .collect();
+
+        let flow = self
+            .get_flow(
+                endpoint,
+                flow_id,
+                &cookies,
+                credentials.and_then(|c| c.token()),
+            )
+            .await?;
+
+        let csrf_token = Self::extract_csrf_token(&flow, flow_type)?;
+
+        Ok(FlowResult {
+            flow,
+            csrf_token,
+            cookies,
+            issued_cookies: Vec::new(),
         })
     }
 
@@ -226,23 +646,35 @@ impl KratosClient {
         flow_id: &str,
         data: serde_json::Value,
         cookies: &[String],
-    ) -> Result<PostFlowResult, Box<dyn std::error::Error>> {
-        let cookie_header = cookies.join("; ");
+        session_token: Option<&str>,
+    ) -> Result<PostFlowResult, KratosError> {
         let url = format!(
             "{}/self-service/{}?flow={}",
             self.public_url, endpoint, flow_id
         );
         let url = url.replace("localhost", "127.0.0.1");
 
-        let response = self
+        let mut request = self
             .client
             .post(&url)
             .header(header::CONTENT_TYPE, "application/json")
-            .header(header::COOKIE, cookie_header)
-            .json(&data)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to submit {} flow: {}", endpoint, e))?;
+            .header(header::ACCEPT, "application/json");
+
+        if !cookies.is_empty() {
+            request = request.header(header::COOKIE, cookies.join("; "));
+        }
+
+        if let Some(token) = session_token {
+            request = request.header("X-Session-Token", token);
+        }
+
+        let response = self
+            .send(
+                KratosCall::Submit,
+                &format!("{} flow submission", endpoint),
+                request.json(&data),
+            )
+            .await?;
 
         let response_cookies: Vec<String> = response
             .headers()
@@ -252,19 +684,20 @@ impl KratosClient {
             .map(|s| s.to_string())
             .collect();
 
+        // 422 means "continue in the browser" (e.g. recovery -> settings) and carries
+        // `redirect_browser_to`, so hand it to the caller together with the cookies
         let status = response.status();
-        if !status.is_success() {
+        if !status.is_success() && status != StatusCode::UNPROCESSABLE_ENTITY {
             let error_text = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("{} failed (status {}): {}", endpoint, status, error_text).into());
+            return Err(KratosError::from_response(status, &error_text));
         }
 
-        let data: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse {} response: {}", endpoint, e))?;
+        let data: serde_json::Value = response.json().await.map_err(|e| {
+            KratosError::InvalidResponse(format!("Failed to parse {} response: {}", endpoint, e))
+        })?;
 
         Ok(PostFlowResult {
             data,
@@ -272,13 +705,17 @@ impl KratosClient {
         })
     }
 
-    fn parse_identity(
-        data: &serde_json::Value,
-    ) -> Result<KratosIdentity, Box<dyn std::error::Error>> {
+    fn parse_identity(data: &serde_json::Value) -> Result<KratosIdentity, KratosError> {
         let identity_data = data
             .get("identity")
             .ok_or("Identity not found in response")?;
 
+        Self::parse_identity_object(identity_data)
+    }
+
+    fn parse_identity_object(
+        identity_data: &serde_json::Value,
+    ) -> Result<KratosIdentity, KratosError> {
         Ok(KratosIdentity {
             id: identity_data["id"]
                 .as_str()
@@ -309,23 +746,71 @@ impl KratosClient {
                 .as_str()
                 .unwrap_or("")
                 .to_string(),
+            verifiable_addresses: Self::parse_verifiable_addresses(identity_data),
+            state: identity_data["state"].as_str().map(|s| s.to_string()),
+            roles: Self::parse_roles(identity_data),
         })
     }
 
+    fn parse_roles(identity_data: &serde_json::Value) -> Vec<String> {
+        identity_data["metadata_public"]["roles"]
+            .as_array()
+            .map(|roles| {
+                roles
+                    .iter()
+                    .filter_map(|role| role.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn parse_verifiable_addresses(identity_data: &serde_json::Value) -> Vec<VerifiableAddress> {
+        identity_data["verifiable_addresses"]
+            .as_array()
+            .map(|addresses| {
+                addresses
+                    .iter()
+                    .map(|address| VerifiableAddress {
+                        value: address["value"].as_str().unwrap_or_default().to_string(),
+                        verified: address["verified"].as_bool().unwrap_or(false),
+                        verified_at: address["verified_at"].as_str().map(|s| s.to_string()),
+                        status: address["status"].as_str().unwrap_or_default().to_string(),
+                        via: address["via"].as_str().unwrap_or("email").to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn parse_authentication_methods(session_json: &serde_json::Value) -> Vec<String> {
+        session_json["authentication_methods"]
+            .as_array()
+            .map(|methods| {
+                methods
+                    .iter()
+                    .filter_map(|m| m["method"].as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub async fn handle_signup(
         &self,
         email: &str,
         username: &str,
         password: &str,
-        cookie: Option<&str>,
-    ) -> Result<(KratosSession, Vec<String>), Box<dyn std::error::Error>> {
-        if self.check_active_session(cookie).await {
-            return Err(Box::from(
-                "Already logged in. Please logout first before logging in again.",
-            ));
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<LoginResult, KratosError> {
+        if self.check_active_session(credentials).await {
+            return Err(KratosError::AlreadyAuthenticated);
         }
 
-        let flow_result = self.fetch_flow("registration", cookie).await?;
+        let flow_result = self
+            .fetch_flow("registration", flow_type, credentials, &[])
+            .await?;
 
         let registration_data = serde_json::json!({
             "method": "password",
@@ -343,6 +828,7 @@ impl KratosClient {
                 flow_result.flow["id"].as_str().ok_or("Flow ID not found")?,
                 registration_data,
                 &flow_result.cookies,
+                None,
             )
             .await?;
 
@@ -352,22 +838,24 @@ impl KratosClient {
             return Err("Registration failed: identity not found in response".into());
         }
 
-        self.handle_login(email, password, cookie).await
+        self.handle_login(email, password, flow_type, credentials)
+            .await
     }
 
     pub async fn handle_login(
         &self,
         identifier: &str,
         password: &str,
-        cookie: Option<&str>,
-    ) -> Result<(KratosSession, Vec<String>), Box<dyn std::error::Error>> {
-        if self.check_active_session(cookie).await {
-            return Err(Box::from(
-                "Already logged in. Please logout first before logging in again.",
-            ));
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<LoginResult, KratosError> {
+        if self.check_active_session(credentials).await {
+            return Err(KratosError::AlreadyAuthenticated);
         }
 
-        let flow_result = self.fetch_flow("login", cookie).await?;
+        let flow_result = self
+            .fetch_flow("login", flow_type, credentials, &[])
+            .await?;
 
         let login_data = serde_json::json!({
             "method": "password",
@@ -382,9 +870,24 @@ impl KratosClient {
                 flow_result.flow["id"].as_str().ok_or("Flow ID not found")?,
                 login_data,
                 &flow_result.cookies,
+                None,
             )
             .await?;
 
+        let mut login_result = Self::parse_login_response(post_result)?;
+
+        if let Some((challenge, flow_cookies)) = self
+            .second_factor_challenge(&login_result, flow_type)
+            .await?
+        {
+            login_result.second_factor = Some(challenge);
+            login_result.cookies.extend(flow_cookies);
+        }
+
+        Ok(login_result)
+    }
+
+    fn parse_login_response(post_result: PostFlowResult) -> Result<LoginResult, KratosError> {
         let response_data = &post_result.data;
 
         let identity = if response_data.get("session").is_some() {
@@ -408,36 +911,970 @@ impl KratosClient {
                 .to_string()
         };
 
+        let session_json = response_data
+            .get("session")
+            .unwrap_or(&serde_json::Value::Null);
+
         let session = KratosSession {
             id: session_id,
             active: true,
             identity,
+            authenticated_at: session_json["authenticated_at"]
+                .as_str()
+                .map(|s| s.to_string()),
+            expires_at: session_json["expires_at"].as_str().map(|s| s.to_string()),
+            authenticator_assurance_level: session_json["authenticator_assurance_level"]
+                .as_str()
+                .map(|s| s.to_string()),
+            authentication_methods: Self::parse_authentication_methods(session_json),
+            issued_at: session_json["issued_at"].as_str().map(|s| s.to_string()),
+            devices: Self::parse_session_devices(session_json),
         };
 
-        Ok((session, post_result.cookies))
+        let session_token = response_data["session_token"]
+            .as_str()
+            .map(|s| s.to_string());
+
+        Ok(LoginResult {
+            session,
+            session_token,
+            cookies: post_result.cookies,
+            second_factor: None,
+        })
+    }
+
+    /// Turns `Set-Cookie` header values into a `Cookie` request header.
+    fn cookie_header(set_cookies: &[String]) -> String {
+        set_cookies
+            .iter()
+            .filter_map(|c| c.split(';').next())
+            .map(|pair| pair.trim())
+            .collect::<Vec<_>>()
+            .join("; ")
     }
 
-    pub async fn handle_logout(
+    /// Kratos enforces `session.whoami.required_aal`: when a freshly created AAL1
+    /// session is rejected with `session_aal2_required`, start the AAL2 login flow
+    /// the client has to complete with a second factor.
+    async fn second_factor_challenge(
         &self,
-        cookie: &str,
-    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        let url = format!("{}/self-service/logout/browser", self.public_url);
+        login_result: &LoginResult,
+        flow_type: FlowType,
+    ) -> Result<Option<(SecondFactorChallenge, Vec<String>)>, KratosError> {
+        let cookie_header = Self::cookie_header(&login_result.cookies);
+        let credentials = match &login_result.session_token {
+            Some(token) => SessionCredentials::Token(token),
+            None => SessionCredentials::Cookie(&cookie_header),
+        };
+
+        match self.get_session(credentials).await {
+            Err(KratosError::Aal2Required) => {}
+            _ => return Ok(None),
+        }
+
+        let flow_result = self
+            .fetch_flow("login", flow_type, Some(credentials), &[("aal", "aal2")])
+            .await?;
+
+        let flow_id = flow_result.flow["id"]
+            .as_str()
+            .ok_or("Flow ID not found")?
+            .to_string();
+
+        let mut methods: Vec<String> = flow_result.flow["ui"]["nodes"]
+            .as_array()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .filter_map(|node| node["group"].as_str())
+                    .filter(|group| !matches!(*group, "default" | "password" | "oidc"))
+                    .map(|group| group.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        methods.sort();
+        methods.dedup();
+
+        Ok(Some((
+            SecondFactorChallenge { flow_id, methods },
+            flow_result.issued_cookies,
+        )))
+    }
+
+    /// Completes an AAL2 login flow started by [`Self::handle_login`].
+    pub async fn handle_login_second_factor(
+        &self,
+        flow_id: &str,
+        factor: SecondFactor<'_>,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<LoginResult, KratosError> {
+        let flow_type = credentials.flow_type();
+        let flow_result = self
+            .fetch_existing_flow("login", flow_id, flow_type, Some(credentials))
+            .await?;
+
+        let login_data = match factor {
+            SecondFactor::Totp(code) => serde_json::json!({
+                "method": "totp",
+                "totp_code": code,
+                "csrf_token": flow_result.csrf_token,
+            }),
+            SecondFactor::LookupSecret(secret) => serde_json::json!({
+                "method": "lookup_secret",
+                "lookup_secret": secret,
+                "csrf_token": flow_result.csrf_token,
+            }),
+        };
+
+        let post_result = self
+            .post_flow(
+                "login",
+                flow_id,
+                login_data,
+                &flow_result.cookies,
+                credentials.token(),
+            )
+            .await?;
+
+        let mut login_result = Self::parse_login_response(post_result)?;
+
+        // API flows keep the same token, now upgraded to AAL2
+        if login_result.session_token.is_none() {
+            login_result.session_token = credentials.token().map(|s| s.to_string());
+        }
+
+        Ok(login_result)
+    }
+
+    pub async fn fetch_settings_flow(
+        &self,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<FlowResult, KratosError> {
+        self.fetch_flow("settings", credentials.flow_type(), Some(credentials), &[])
+            .await
+    }
+
+    pub async fn submit_settings_flow(
+        &self,
+        flow_result: &FlowResult,
+        mut data: serde_json::Value,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<PostFlowResult, KratosError> {
+        data["csrf_token"] = serde_json::Value::String(flow_result.csrf_token.clone());
+
+        self.post_flow(
+            "settings",
+            flow_result.flow["id"].as_str().ok_or("Flow ID not found")?,
+            data,
+            &flow_result.cookies,
+            credentials.token(),
+        )
+        .await
+    }
+
+    pub async fn handle_change_password(
+        &self,
+        password: &str,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<(KratosIdentity, Vec<String>), KratosError> {
+        let flow_result = self.fetch_settings_flow(credentials).await?;
+
+        let settings_data = serde_json::json!({
+            "method": "password",
+            "password": password,
+        });
+
+        let post_result = self
+            .submit_settings_flow(&flow_result, settings_data, credentials)
+            .await?;
+
+        Ok((
+            Self::parse_identity(&post_result.data)?,
+            post_result.cookies,
+        ))
+    }
+
+    /// Updates the given traits, keeping the current value of every `None` field.
+    pub async fn handle_update_profile(
+        &self,
+        email: Option<&str>,
+        username: Option<&str>,
+        geo_location: Option<&str>,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<(KratosIdentity, Vec<String>), KratosError> {
+        let flow_result = self.fetch_settings_flow(credentials).await?;
+        let current = Self::parse_identity(&flow_result.flow)?.traits;
+
+        let traits = IdentityTraits {
+            email: email.map(|s| s.to_string()).unwrap_or(current.email),
+            username: username.map(|s| s.to_string()).unwrap_or(current.username),
+            geo_location: geo_location.map(|s| s.to_string()).or(current.geo_location),
+        };
+
+        let settings_data = serde_json::json!({
+            "method": "profile",
+            "traits": traits,
+        });
+
+        let post_result = self
+            .submit_settings_flow(&flow_result, settings_data, credentials)
+            .await?;
+
+        Ok((
+            Self::parse_identity(&post_result.data)?,
+            post_result.cookies,
+        ))
+    }
+
+    fn find_node<'v>(flow: &'v serde_json::Value, id: &str) -> Option<&'v serde_json::Value> {
+        flow["ui"]["nodes"]
+            .as_array()?
+            .iter()
+            .find(|node| node["attributes"]["id"].as_str() == Some(id))
+    }
+
+    /// Opens a settings flow and returns the TOTP QR code and secret to show
+    /// the user. Both are `None` when TOTP is already enabled.
+    pub async fn handle_totp_enrollment_start(
+        &self,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<TotpEnrollment, KratosError> {
+        let flow_result = self.fetch_settings_flow(credentials).await?;
+
+        let flow_id = flow_result.flow["id"]
+            .as_str()
+            .ok_or("Flow ID not found")?
+            .to_string();
+
+        let qr_code = Self::find_node(&flow_result.flow, "totp_qr")
+            .and_then(|node| node["attributes"]["src"].as_str())
+            .map(|s| s.to_string());
+
+        let secret = Self::find_node(&flow_result.flow, "totp_secret_key")
+            .and_then(|node| node["attributes"]["text"]["text"].as_str())
+            .map(|s| s.to_string());
+
+        Ok(TotpEnrollment {
+            flow_id,
+            qr_code,
+            secret,
+            cookies: flow_result.issued_cookies,
+        })
+    }
+
+    /// Confirms TOTP enrollment with a code from the authenticator app.
+    pub async fn handle_totp_enrollment_confirm(
+        &self,
+        flow_id: &str,
+        code: &str,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<(KratosIdentity, Vec<String>), KratosError> {
+        let flow_result = self
+            .fetch_existing_flow(
+                "settings",
+                flow_id,
+                credentials.flow_type(),
+                Some(credentials),
+            )
+            .await?;
+
+        let settings_data = serde_json::json!({
+            "method": "totp",
+            "totp_code": code,
+        });
+
+        let post_result = self
+            .submit_settings_flow(&flow_result, settings_data, credentials)
+            .await?;
+
+        Ok((
+            Self::parse_identity(&post_result.data)?,
+            post_result.cookies,
+        ))
+    }
+
+    pub async fn handle_totp_disable(
+        &self,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<(KratosIdentity, Vec<String>), KratosError> {
+        let flow_result = self.fetch_settings_flow(credentials).await?;
+
+        let settings_data = serde_json::json!({
+            "method": "totp",
+            "totp_unlink": true,
+        });
+
+        let post_result = self
+            .submit_settings_flow(&flow_result, settings_data, credentials)
+            .await?;
+
+        Ok((
+            Self::parse_identity(&post_result.data)?,
+            post_result.cookies,
+        ))
+    }
+
+    /// Generates a new set of lookup (backup) codes and confirms them in the
+    /// same settings flow, invalidating any previous codes.
+    pub async fn handle_regenerate_lookup_secrets(
+        &self,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<(Vec<String>, Vec<String>), KratosError> {
+        let flow_result = self.fetch_settings_flow(credentials).await?;
+
+        let regenerate_data = serde_json::json!({
+            "method": "lookup_secret",
+            "lookup_secret_regenerate": true,
+        });
+
+        let regenerate_result = self
+            .submit_settings_flow(&flow_result, regenerate_data, credentials)
+            .await?;
+
+        let codes_node = Self::find_node(&regenerate_result.data, "lookup_secret_codes")
+            .ok_or("Lookup secret codes not found in settings flow")?;
+
+        let codes: Vec<String> =
+            match codes_node["attributes"]["text"]["context"]["secrets"].as_array() {
+                Some(secrets) => secrets
+                    .iter()
+                    .filter_map(|secret| secret["text"].as_str())
+                    .map(|s| s.to_string())
+                    .collect(),
+                None => codes_node["attributes"]["text"]["text"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            };
+
+        let confirm_data = serde_json::json!({
+            "method": "lookup_secret",
+            "lookup_secret_confirm": true,
+        });
+
+        let confirm_result = self
+            .submit_settings_flow(&flow_result, confirm_data, credentials)
+            .await?;
+
+        let mut cookies = regenerate_result.cookies;
+        cookies.extend(confirm_result.cookies);
+
+        Ok((codes, cookies))
+    }
+
+    /// Reads the WebAuthn options from the trigger node. Kratos 1.0 embeds them in
+    /// the `onclick` script (`window.__oryWebAuthnRegistration({...})`), newer
+    /// versions put them in the node value. `passkey_create_data` wraps them in
+    /// `credentialOptions`.
+    fn webauthn_options(flow: &serde_json::Value, trigger: &str) -> Option<serde_json::Value> {
+        let node = flow["ui"]["nodes"]
+            .as_array()?
+            .iter()
+            .find(|node| node["attributes"]["name"].as_str() == Some(trigger))?;
+
+        if let Some(value) = node["attributes"]["value"].as_str()
+            && let Ok(options) = serde_json::from_str::<serde_json::Value>(value)
+            && options.is_object()
+        {
+            return Some(options.get("credentialOptions").cloned().unwrap_or(options));
+        }
+
+        let onclick = node["attributes"]["onclick"].as_str()?;
+        let start = onclick.find('(')?;
+        let end = onclick.rfind(')')?;
+        serde_json::from_str(onclick.get(start + 1..end)?).ok()
+    }
+
+    /// Opens a settings flow and returns the credential creation options for a
+    /// new security key (`webauthn`) or passkey (`passkey`).
+    pub async fn handle_webauthn_registration_start(
+        &self,
+        method: WebauthnMethod,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<WebauthnChallenge, KratosError> {
+        let flow_result = self.fetch_settings_flow(credentials).await?;
+
+        let flow_id = flow_result.flow["id"]
+            .as_str()
+            .ok_or("Flow ID not found")?
+            .to_string();
+
+        let trigger = match method {
+            WebauthnMethod::Webauthn => "webauthn_register_trigger",
+            WebauthnMethod::Passkey => "passkey_create_data",
+        };
+        let options = Self::webauthn_options(&flow_result.flow, trigger)
+            .ok_or("WebAuthn registration options not found in settings flow")?;
+
+        Ok(WebauthnChallenge {
+            flow_id,
+            options,
+            cookies: flow_result.issued_cookies,
+        })
+    }
+
+    /// Submits the browser's `PublicKeyCredential` (JSON encoded) to the settings flow.
+    /// Kratos names passkeys itself, so `display_name` only applies to `webauthn`.
+    pub async fn handle_webauthn_registration_finish(
+        &self,
+        method: WebauthnMethod,
+        flow_id: &str,
+        display_name: Option<&str>,
+        credential: &str,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<(KratosIdentity, Vec<String>), KratosError> {
+        let flow_result = self
+            .fetch_existing_flow(
+                "settings",
+                flow_id,
+                credentials.flow_type(),
+                Some(credentials),
+            )
+            .await?;
+
+        let settings_data = match method {
+            WebauthnMethod::Webauthn => serde_json::json!({
+                "method": "webauthn",
+                "webauthn_register": credential,
+                "webauthn_register_displayname": display_name.unwrap_or_default(),
+            }),
+            WebauthnMethod::Passkey => serde_json::json!({
+                "method": "passkey",
+                "passkey_settings_register": credential,
+            }),
+        };
+
+        let post_result = self
+            .submit_settings_flow(&flow_result, settings_data, credentials)
+            .await?;
+
+        Ok((
+            Self::parse_identity(&post_result.data)?,
+            post_result.cookies,
+        ))
+    }
+
+    /// Starts a passwordless login. With `webauthn` Kratos answers the identifier
+    /// step with the flow, which then carries the assertion options; `passkey`
+    /// flows carry them from the start, for discoverable credentials without an
+    /// identifier. WebAuthn is browser-only.
+    pub async fn handle_webauthn_login_start(
+        &self,
+        method: WebauthnMethod,
+        identifier: Option<&str>,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<WebauthnChallenge, KratosError> {
+        if self.check_active_session(credentials).await {
+            return Err(KratosError::AlreadyAuthenticated);
+        }
+
+        let flow_result = self
+            .fetch_flow("login", FlowType::Browser, credentials, &[])
+            .await?;
+
+        let flow_id = flow_result.flow["id"]
+            .as_str()
+            .ok_or("Flow ID not found")?
+            .to_string();
+
+        if method == WebauthnMethod::Passkey {
+            let options = Self::webauthn_options(&flow_result.flow, "passkey_challenge")
+                .ok_or("Passkey login options not found in login flow")?;
+
+            return Ok(WebauthnChallenge {
+                flow_id,
+                options,
+                cookies: flow_result.issued_cookies,
+            });
+        }
+
+        let identifier_data = serde_json::json!({
+            "method": "webauthn",
+            "identifier": identifier.unwrap_or_default(),
+            "csrf_token": flow_result.csrf_token,
+        });
+
+        // The identifier step "fails" with 400 and the updated flow, without error messages
+        match self
+            .post_flow(
+                "login",
+                &flow_id,
+                identifier_data,
+                &flow_result.cookies,
+                None,
+            )
+            .await
+        {
+            Ok(_) | Err(KratosError::InvalidResponse(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let flow = self
+            .get_flow("login", &flow_id, &flow_result.cookies, None)
+            .await?;
+
+        let options = Self::webauthn_options(&flow, "webauthn_login_trigger").ok_or_else(|| {
+            let messages = KratosError::parse_flow_messages(&flow);
+            if messages.is_empty() {
+                KratosError::InvalidResponse(
+                    "WebAuthn login options not found in login flow".to_string(),
+                )
+            } else {
+                KratosError::Validation(messages)
+            }
+        })?;
+
+        Ok(WebauthnChallenge {
+            flow_id,
+            options,
+            cookies: flow_result.issued_cookies,
+        })
+    }
+
+    /// Completes a login flow started by [`Self::handle_webauthn_login_start`]
+    /// with the browser's assertion (JSON encoded).
+    pub async fn handle_webauthn_login_finish(
+        &self,
+        method: WebauthnMethod,
+        flow_id: &str,
+        identifier: Option<&str>,
+        assertion: &str,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<LoginResult, KratosError> {
+        let flow_result = self
+            .fetch_existing_flow("login", flow_id, FlowType::Browser, credentials)
+            .await?;
+
+        let login_data = match method {
+            WebauthnMethod::Webauthn => serde_json::json!({
+                "method": "webauthn",
+                "identifier": identifier.unwrap_or_default(),
+                "webauthn_login": assertion,
+                "csrf_token": flow_result.csrf_token,
+            }),
+            WebauthnMethod::Passkey => serde_json::json!({
+                "method": "passkey",
+                "passkey_login": assertion,
+                "csrf_token": flow_result.csrf_token,
+            }),
+        };
+
+        let post_result = self
+            .post_flow("login", flow_id, login_data, &flow_result.cookies, None)
+            .await?;
+
+        let mut login_result = Self::parse_login_response(post_result)?;
+
+        if let Some((challenge, flow_cookies)) = self
+            .second_factor_challenge(&login_result, FlowType::Browser)
+            .await?
+        {
+            login_result.second_factor = Some(challenge);
+            login_result.cookies.extend(flow_cookies);
+        }
+
+        Ok(login_result)
+    }
+
+    /// Lists the identity's security keys and passkeys through the admin API,
+    /// since `whoami` never includes credential configs.
+    pub async fn get_webauthn_credentials(
+        &self,
+        identity_id: &str,
+    ) -> Result<Vec<WebauthnCredential>, KratosError> {
+        let url = self.admin_identities_url(&format!("/{}", Self::parse_id(identity_id)?));
+        let response = self
+            .send_admin(self.client.get(url).query(&[
+                ("include_credential", "webauthn"),
+                ("include_credential", "passkey"),
+            ]))
+            .await?;
+
+        let identity: serde_json::Value = response.json().await?;
+
+        let credentials = ["webauthn", "passkey"]
+            .into_iter()
+            .flat_map(|method| {
+                identity["credentials"][method]["config"]["credentials"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |credential| WebauthnCredential {
+                        id: credential["id"].as_str().unwrap_or_default().to_string(),
+                        display_name: credential["display_name"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        added_at: credential["added_at"].as_str().map(|s| s.to_string()),
+                        // Passkeys are discoverable, so always usable without a password
+                        is_passwordless: method == "passkey"
+                            || credential["is_passwordless"].as_bool().unwrap_or(false),
+                    })
+            })
+            .collect();
+
+        Ok(credentials)
+    }
+
+    /// Lists the OpenID Connect providers offered by the login flow's `oidc` group.
+    /// The list is cached for `OIDC_PROVIDERS_TTL` so listing doesn't create a
+    /// login flow per request.
+    pub async fn handle_list_oidc_providers(&self) -> Result<Vec<OidcProvider>, KratosError> {
+        if let Ok(cached) = self.oidc_providers.lock()
+            && let Some((fetched_at, providers)) = cached.as_ref()
+            && fetched_at.elapsed() < OIDC_PROVIDERS_TTL
+        {
+            return Ok(providers.clone());
+        }
+
+        let flow_result = self
+            .fetch_flow("login", FlowType::Browser, None, &[])
+            .await?;
+
+        let providers: Vec<OidcProvider> = flow_result.flow["ui"]["nodes"]
+            .as_array()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .filter(|node| node["group"].as_str() == Some("oidc"))
+                    .filter(|node| node["attributes"]["name"].as_str() == Some("provider"))
+                    .filter_map(|node| {
+                        let id = node["attributes"]["value"].as_str()?.to_string();
+                        let label = node["meta"]["label"]["context"]["provider"]
+                            .as_str()
+                            .or_else(|| node["meta"]["label"]["text"].as_str())
+                            .map(|s| s.to_string());
+                        Some(OidcProvider { id, label })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if let Ok(mut cached) = self.oidc_providers.lock() {
+            *cached = Some((Instant::now(), providers.clone()));
+        }
+
+        Ok(providers)
+    }
+
+    /// Starts a browser login flow with the `oidc` method. Kratos answers with
+    /// `redirect_browser_to` pointing at the provider's authorization endpoint.
+    pub async fn handle_start_oidc_login(
+        &self,
+        provider: &str,
+        return_to: Option<&str>,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<OidcLoginStart, KratosError> {
+        let query: Vec<(&str, &str)> = return_to
+            .map(|url| ("return_to", url))
+            .into_iter()
+            .collect();
+
+        let flow_result = self
+            .fetch_flow("login", FlowType::Browser, credentials, &query)
+            .await?;
+
+        let flow_id = flow_result.flow["id"]
+            .as_str()
+            .ok_or("Flow ID not found")?
+            .to_string();
+
+        let oidc_data = serde_json::json!({
+            "method": "oidc",
+            "provider": provider,
+            "csrf_token": flow_result.csrf_token,
+        });
+
+        let post_result = self
+            .post_flow("login", &flow_id, oidc_data, &flow_result.cookies, None)
+            .await?;
+
+        let redirect_url = post_result.data["redirect_browser_to"]
+            .as_str()
+            .ok_or_else(|| {
+                let messages = KratosError::parse_flow_messages(&post_result.data);
+                if messages.is_empty() {
+                    KratosError::InvalidResponse("OIDC redirect URL not found".to_string())
+                } else {
+                    KratosError::Validation(messages)
+                }
+            })?
+            .to_string();
+
+        let mut cookies = flow_result.issued_cookies;
+        cookies.extend(post_result.cookies);
+
+        Ok(OidcLoginStart {
+            flow_id,
+            redirect_url,
+            cookies,
+        })
+    }
+
+    /// Replays the provider's callback to Kratos, which validates the state
+    /// against the continuity cookie, creates the session and redirects.
+    pub async fn handle_oidc_callback(
+        &self,
+        provider: &str,
+        query: &str,
+        cookie: Option<&str>,
+        form: Option<(&str, Vec<u8>)>,
+    ) -> Result<OidcCallbackResult, KratosError> {
+        let mut url = format!(
+            "{}/self-service/methods/oidc/callback/{}",
+            self.public_url, provider
+        );
+        if !query.is_empty() {
+            url = format!("{}?{}", url, query);
+        }
+        let url = url.replace("localhost", "127.0.0.1");
+
+        // Some providers use `response_mode=form_post`
+        let mut request = match form {
+            Some((content_type, body)) => self
+                .client
+                .post(&url)
+                .header(header::CONTENT_TYPE, content_type)
+                .body(body),
+            None => self.client.get(&url),
+        };
+
+        if let Some(cookie_value) = cookie {
+            request = request.header(header::COOKIE, cookie_value);
+        }
+
+        let response = self
+            .send(KratosCall::Submit, "OIDC callback endpoint", request)
+            .await?;
+
+        let status = response.status();
+
+        if status.is_server_error() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(KratosError::from_response(status, &error_text));
+        }
+
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string());
+
+        let cookies: Vec<String> = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+            .collect();
+
+        let body = response.text().await.unwrap_or_default();
+
+        Ok(OidcCallbackResult {
+            status: status.as_u16(),
+            location,
+            cookies,
+            body,
+        })
+    }
+
+    fn flow_id_from_url(url: &str) -> Option<String> {
+        url.split("flow=")
+            .nth(1)
+            .and_then(|rest| rest.split('&').next())
+            .map(|s| s.to_string())
+    }
+
+    /// Starts a code-based flow (recovery, verification) and asks Kratos to email
+    /// a one-time code. Returns the flow id the code has to be submitted to.
+    async fn start_code_flow(
+        &self,
+        endpoint: &str,
+        email: &str,
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(String, Vec<String>), KratosError> {
+        let flow_result = self
+            .fetch_flow(endpoint, flow_type, credentials, &[])
+            .await?;
+
+        let flow_id = flow_result.flow["id"]
+            .as_str()
+            .ok_or("Flow ID not found")?
+            .to_string();
+
+        let code_data = serde_json::json!({
+            "method": "code",
+            "email": email,
+            "csrf_token": flow_result.csrf_token,
+        });
+
+        let post_result = self
+            .post_flow(
+                endpoint,
+                &flow_id,
+                code_data,
+                &flow_result.cookies,
+                credentials.and_then(|c| c.token()),
+            )
+            .await?;
+
+        // The browser needs the flow's CSRF cookie to submit the code later
+        let mut cookies = flow_result.issued_cookies;
+        cookies.extend(post_result.cookies);
+
+        Ok((flow_id, cookies))
+    }
+
+    pub async fn handle_request_recovery(
+        &self,
+        email: &str,
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(String, Vec<String>), KratosError> {
+        self.start_code_flow("recovery", email, flow_type, credentials)
+            .await
+    }
+
+    pub async fn handle_complete_recovery(
+        &self,
+        flow_id: &str,
+        code: &str,
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<RecoveryResult, KratosError> {
+        let flow_result = self
+            .fetch_existing_flow("recovery", flow_id, flow_type, credentials)
+            .await?;
+
+        let recovery_data = serde_json::json!({
+            "method": "code",
+            "code": code,
+            "csrf_token": flow_result.csrf_token,
+        });
+
+        let post_result = self
+            .post_flow(
+                "recovery",
+                flow_id,
+                recovery_data,
+                &flow_result.cookies,
+                None,
+            )
+            .await?;
+
+        let data = &post_result.data;
+        let continue_with = data["continue_with"].as_array();
+
+        let settings_flow_id = data["redirect_browser_to"]
+            .as_str()
+            .and_then(Self::flow_id_from_url)
+            .or_else(|| {
+                continue_with?
+                    .iter()
+                    .find(|item| item["action"].as_str() == Some("show_settings_ui"))
+                    .and_then(|item| item["flow"]["id"].as_str())
+                    .map(|s| s.to_string())
+            });
+
+        let session_token = continue_with.and_then(|items| {
+            items
+                .iter()
+                .find(|item| item["action"].as_str() == Some("set_ory_session_token"))
+                .and_then(|item| item["ory_session_token"].as_str())
+                .map(|s| s.to_string())
+        });
+
+        // An invalid code keeps the flow in `sent_email` and only adds UI messages
+        if settings_flow_id.is_none() && session_token.is_none() {
+            let messages = KratosError::parse_flow_messages(data);
+            if messages.iter().any(|m| m.message_type == "error") {
+                return Err(KratosError::Validation(messages));
+            }
+            if data["state"].as_str() != Some("passed_challenge") {
+                return Err("Recovery flow did not complete".into());
+            }
+        }
+
+        Ok(RecoveryResult {
+            settings_flow_id,
+            session_token,
+            cookies: post_result.cookies,
+        })
+    }
+
+    pub async fn handle_send_verification(
+        &self,
+        email: &str,
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<(String, Vec<String>), KratosError> {
+        self.start_code_flow("verification", email, flow_type, credentials)
+            .await
+    }
+
+    pub async fn handle_complete_verification(
+        &self,
+        flow_id: &str,
+        code: &str,
+        flow_type: FlowType,
+        credentials: Option<SessionCredentials<'_>>,
+    ) -> Result<Vec<String>, KratosError> {
+        let flow_result = self
+            .fetch_existing_flow("verification", flow_id, flow_type, credentials)
+            .await?;
+
+        let verification_data = serde_json::json!({
+            "method": "code",
+            "code": code,
+            "csrf_token": flow_result.csrf_token,
+        });
+
+        let post_result = self
+            .post_flow(
+                "verification",
+                flow_id,
+                verification_data,
+                &flow_result.cookies,
+                credentials.and_then(|c| c.token()),
+            )
+            .await?;
+
+        // Like recovery, an invalid code keeps the flow open and only adds UI messages
+        if post_result.data["state"].as_str() != Some("passed_challenge") {
+            let messages = KratosError::parse_flow_messages(&post_result.data);
+            if messages.iter().any(|m| m.message_type == "error") {
+                return Err(KratosError::Validation(messages));
+            }
+            return Err("Verification flow did not complete".into());
+        }
+
+        Ok(post_result.cookies)
+    }
+
+    pub async fn handle_logout(&self, cookie: &str) -> Result<Vec<String>, KratosError> {
+        let url = format!("{}/self-service/logout/browser", self.public_url);
         let url = url.replace("localhost", "127.0.0.1");
 
         let flow_response = self
-            .client
-            .get(&url)
-            .header(header::COOKIE, cookie)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to connect to logout endpoint: {}", e))?;
+            .send(
+                KratosCall::Flow,
+                "logout endpoint",
+                self.client.get(&url).header(header::COOKIE, cookie),
+            )
+            .await?;
 
-        if !flow_response.status().is_success() {
+        let status = flow_response.status();
+        if !status.is_success() {
             let error_text = flow_response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Failed to get logout flow: {}", error_text).into());
+            return Err(KratosError::from_response(status, &error_text));
         }
 
         let flow_data: serde_json::Value = flow_response.json().await?;
@@ -447,18 +1884,21 @@ impl KratosClient {
             .replace("localhost", "127.0.0.1");
 
         let response = self
-            .client
-            .get(&logout_url)
-            .header(header::COOKIE, cookie)
-            .send()
+            .send(
+                KratosCall::Submit,
+                "logout URL",
+                self.client.get(&logout_url).header(header::COOKIE, cookie),
+            )
             .await?;
 
-        if !response.status().is_success() {
+        // Kratos answers the logout URL with a redirect to the after-logout page
+        let status = response.status();
+        if !status.is_success() && !status.is_redirection() {
             let error_text = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Logout failed: {}", error_text).into());
+            return Err(KratosError::from_response(status, &error_text));
         }
 
         let cookies: Vec<String> = response
@@ -472,23 +1912,47 @@ impl KratosClient {
         Ok(cookies)
     }
 
+    pub async fn handle_logout_api(&self, session_token: &str) -> Result<(), KratosError> {
+        let url = format!("{}/self-service/logout/api", self.public_url);
+        let url = url.replace("localhost", "127.0.0.1");
+
+        let request = self
+            .client
+            .delete(&url)
+            .json(&serde_json::json!({ "session_token": session_token }));
+        let response = self
+            .send(KratosCall::Submit, "logout endpoint", request)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(KratosError::from_response(status, &error_text));
+        }
+
+        Ok(())
+    }
+
     pub async fn handle_get_current_user(
         &self,
-        cookie: &str,
-    ) -> Result<IdentityTraits, Box<dyn std::error::Error>> {
+        credentials: SessionCredentials<'_>,
+    ) -> Result<IdentityTraits, KratosError> {
         let url = format!("{}/sessions/whoami", self.public_url);
         let url = url.replace("localhost", "127.0.0.1");
 
         let response = self
-            .client
-            .get(&url)
-            .header(header::COOKIE, cookie)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to connect to whoami endpoint: {}", e))?;
+            .send(
+                KratosCall::Session,
+                "whoami endpoint",
+                credentials.apply(self.client.get(&url)),
+            )
+            .await?;
 
         if !response.status().is_success() {
-            return Err("Not logged in".into());
+            return Err(KratosError::Unauthorized);
         }
 
         let session_data: serde_json::Value = response.json().await?;
@@ -512,40 +1976,51 @@ impl KratosClient {
 
     pub async fn get_session(
         &self,
-        cookie: &str,
-    ) -> Result<Option<KratosSession>, Box<dyn std::error::Error>> {
+        credentials: SessionCredentials<'_>,
+    ) -> Result<Option<KratosSession>, KratosError> {
         let url = format!("{}/sessions/whoami", self.public_url);
         let url = url.replace("localhost", "127.0.0.1");
 
         let response = self
-            .client
-            .get(&url)
-            .header(header::COOKIE, cookie)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to connect to whoami endpoint: {}", e))?;
+            .send(
+                KratosCall::Session,
+                "whoami endpoint",
+                credentials.apply(self.client.get(&url)),
+            )
+            .await?;
 
         let status = response.status();
 
-        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        if status == StatusCode::UNAUTHORIZED {
             return Ok(None);
         }
 
+        // 403 means the session exists but does not satisfy `whoami.required_aal`
+        if status == StatusCode::FORBIDDEN {
+            let error_text = response.text().await.unwrap_or_default();
+            return match KratosError::from_response(status, &error_text) {
+                KratosError::Aal2Required => Err(KratosError::Aal2Required),
+                _ => Ok(None),
+            };
+        }
+
         if !status.is_success() {
             let error_text = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!(
-                "Failed to fetch session (status {}): {}",
-                status, error_text
-            )
-            .into());
+            return Err(KratosError::from_response(status, &error_text));
         }
 
         let session_json: serde_json::Value = response.json().await?;
 
-        let session = KratosSession {
+        Ok(Some(Self::parse_session(&session_json)))
+    }
+
+    /// Lenient parser for session objects from `whoami` and `/sessions`, which
+    /// may omit the expanded identity.
+    fn parse_session(session_json: &serde_json::Value) -> KratosSession {
+        KratosSession {
             id: session_json["id"].as_str().unwrap_or_default().to_string(),
             active: session_json["active"].as_bool().unwrap_or(false),
             identity: KratosIdentity {
@@ -578,9 +2053,602 @@ impl KratosClient {
                     .as_str()
                     .unwrap_or("")
                     .to_string(),
+                verifiable_addresses: Self::parse_verifiable_addresses(&session_json["identity"]),
+                state: session_json["identity"]["state"]
+                    .as_str()
+                    .map(|s| s.to_string()),
+                roles: Self::parse_roles(&session_json["identity"]),
             },
+            authenticated_at: session_json["authenticated_at"]
+                .as_str()
+                .map(|s| s.to_string()),
+            expires_at: session_json["expires_at"].as_str().map(|s| s.to_string()),
+            authenticator_assurance_level: session_json["authenticator_assurance_level"]
+                .as_str()
+                .map(|s| s.to_string()),
+            authentication_methods: Self::parse_authentication_methods(session_json),
+            issued_at: session_json["issued_at"].as_str().map(|s| s.to_string()),
+            devices: Self::parse_session_devices(session_json),
+        }
+    }
+
+    fn parse_session_devices(session_json: &serde_json::Value) -> Vec<SessionDevice> {
+        session_json["devices"]
+            .as_array()
+            .map(|devices| {
+                devices
+                    .iter()
+                    .map(|device| SessionDevice {
+                        id: device["id"].as_str().unwrap_or_default().to_string(),
+                        ip_address: device["ip_address"].as_str().map(|s| s.to_string()),
+                        user_agent: device["user_agent"].as_str().map(|s| s.to_string()),
+                        location: device["location"]
+                            .as_str()
+                            .filter(|s| !s.is_empty())
+                            .map(|s| s.to_string()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Lists the caller's other sessions; the current one is not included.
+    pub async fn list_sessions(
+        &self,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<Vec<KratosSession>, KratosError> {
+        let url = format!("{}/sessions", self.public_url);
+        let url = url.replace("localhost", "127.0.0.1");
+
+        let response = self
+            .send(
+                KratosCall::Session,
+                "sessions endpoint",
+                credentials.apply(self.client.get(&url)),
+            )
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(KratosError::from_response(status, &error_text));
+        }
+
+        let sessions_json: serde_json::Value = response.json().await?;
+
+        Ok(sessions_json
+            .as_array()
+            .map(|sessions| sessions.iter().map(Self::parse_session).collect())
+            .unwrap_or_default())
+    }
+
+    /// Revokes one of the caller's other sessions. Kratos refuses to revoke the
+    /// current session here, that is what logout is for.
+    pub async fn revoke_session(
+        &self,
+        session_id: &str,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<(), KratosError> {
+        let url = format!(
+            "{}/sessions/{}",
+            self.public_url,
+            Self::parse_id(session_id)?
+        );
+        let url = url.replace("localhost", "127.0.0.1");
+
+        let response = self
+            .send(
+                KratosCall::Session,
+                "sessions endpoint",
+                credentials.apply(self.client.delete(&url)),
+            )
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(KratosError::from_response(status, &error_text));
+        }
+
+        Ok(())
+    }
+
+    /// Revokes every session of the caller except the current one and returns
+    /// how many were revoked.
+    pub async fn revoke_other_sessions(
+        &self,
+        credentials: SessionCredentials<'_>,
+    ) -> Result<u64, KratosError> {
+        let url = format!("{}/sessions", self.public_url);
+        let url = url.replace("localhost", "127.0.0.1");
+
+        let response = self
+            .send(
+                KratosCall::Session,
+                "sessions endpoint",
+                credentials.apply(self.client.delete(&url)),
+            )
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(KratosError::from_response(status, &error_text));
+        }
+
+        let result: serde_json::Value = response.json().await?;
+
+        Ok(result["count"].as_u64().unwrap_or_default())
+    }
+
+    async fn send_admin(&self, request: RequestBuilder) -> Result<reqwest::Response, KratosError> {
+        let response = self
+            .send(KratosCall::Admin, "Kratos admin API", request)
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(KratosError::from_response(status, &error_text));
+        }
+
+        Ok(response)
+    }
+
+    /// Identity and session ids are UUIDs; anything else would end up in the
+    /// URL path and can't name an existing resource anyway.
+    fn parse_id(id: &str) -> Result<Uuid, KratosError> {
+        Uuid::parse_str(id).map_err(|_| KratosError::NotFound)
+    }
+
+    fn admin_identities_url(&self, path: &str) -> String {
+        format!("{}/admin/identities{}", self.admin_url, path).replace("localhost", "127.0.0.1")
+    }
+
+    /// Pulls `page_token` out of the `rel="next"` entry of the `Link` header.
+    fn next_page_token(response: &reqwest::Response) -> Option<String> {
+        let link = response.headers().get(header::LINK)?.to_str().ok()?;
+
+        link.split(',')
+            .find(|entry| entry.contains("rel=\"next\""))
+            .and_then(|entry| entry.split("page_token=").nth(1))
+            .and_then(|rest| rest.split(['&', '>']).next())
+            .filter(|token| !token.is_empty())
+            .map(|token| token.to_string())
+    }
+
+    /// Lists identities page by page. `credentials_identifier` is an exact
+    /// match on a login identifier (email or username).
+    pub async fn admin_list_identities(
+        &self,
+        page_size: u32,
+        page_token: Option<&str>,
+        credentials_identifier: Option<&str>,
+    ) -> Result<IdentityPage, KratosError> {
+        let mut query = vec![("page_size", page_size.to_string())];
+        if let Some(token) = page_token {
+            query.push(("page_token", token.to_string()));
+        }
+        if let Some(identifier) = credentials_identifier {
+            query.push(("credentials_identifier", identifier.to_string()));
+        }
+
+        let response = self
+            .send_admin(self.client.get(self.admin_identities_url("")).query(&query))
+            .await?;
+
+        let next_page_token = Self::next_page_token(&response);
+        let identities_json: serde_json::Value = response.json().await?;
+
+        let identities = identities_json
+            .as_array()
+            .map(|identities| {
+                identities
+                    .iter()
+                    .map(Self::parse_identity_object)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(IdentityPage {
+            identities,
+            next_page_token,
+        })
+    }
+
+    pub async fn admin_get_identity(
+        &self,
+        identity_id: &str,
+    ) -> Result<KratosIdentity, KratosError> {
+        let url = self.admin_identities_url(&format!("/{}", Self::parse_id(identity_id)?));
+        let response = self.send_admin(self.client.get(url)).await?;
+
+        Self::parse_identity_object(&response.json().await?)
+    }
+
+    fn new_identity_body(new_identity: &NewIdentity) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "schema_id": "default",
+            "traits": new_identity.traits,
+            "state": new_identity.state.as_deref().unwrap_or("active"),
+            "metadata_public": { "roles": new_identity.roles },
+        });
+
+        if let Some(password_hash) = &new_identity.password_hash {
+            body["credentials"] = serde_json::json!({
+                "password": { "config": { "hashed_password": password_hash } }
+            });
+        } else if let Some(password) = &new_identity.password {
+            body["credentials"] = serde_json::json!({
+                "password": { "config": { "password": password } }
+            });
+        }
+
+        if new_identity.verified {
+            body["verifiable_addresses"] = serde_json::json!([{
+                "value": new_identity.traits.email,
+                "verified": true,
+                "via": "email",
+                "status": "completed",
+            }]);
+        }
+
+        body
+    }
+
+    pub async fn admin_create_identity(
+        &self,
+        new_identity: NewIdentity,
+    ) -> Result<KratosIdentity, KratosError> {
+        let body = Self::new_identity_body(&new_identity);
+
+        let response = self
+            .send_admin(self.client.post(self.admin_identities_url("")).json(&body))
+            .await?;
+
+        Self::parse_identity_object(&response.json().await?)
+    }
+
+    /// Creates identities in one `PATCH /admin/identities` call and returns
+    /// their ids in input order. Kratos rejects the whole batch if any
+    /// identity is invalid.
+    pub async fn admin_batch_create_identities(
+        &self,
+        new_identities: &[NewIdentity],
+    ) -> Result<Vec<String>, KratosError> {
+        let patches: Vec<serde_json::Value> = new_identities
+            .iter()
+            .map(|new_identity| serde_json::json!({ "create": Self::new_identity_body(new_identity) }))
+            .collect();
+
+        let response = self
+            .send_admin(
+                self.client
+                    .patch(self.admin_identities_url(""))
+                    .json(&serde_json::json!({ "identities": patches })),
+            )
+            .await?;
+
+        let result: serde_json::Value = response.json().await?;
+
+        let ids: Vec<String> = result["identities"]
+            .as_array()
+            .map(|identities| {
+                identities
+                    .iter()
+                    .filter_map(|identity| identity["identity"].as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if ids.len() != new_identities.len() {
+            return Err(KratosError::InvalidResponse(format!(
+                "batch import returned {} identities for {} rows",
+                ids.len(),
+                new_identities.len()
+            )));
+        }
+
+        Ok(ids)
+    }
+
+    /// Applies the update as a JSON Patch, so traits that are not mentioned keep
+    /// their current value.
+    pub async fn admin_update_identity(
+        &self,
+        identity_id: &str,
+        update: IdentityUpdate,
+    ) -> Result<KratosIdentity, KratosError> {
+        // `add` replaces existing members and creates missing ones
+        let patch_op = |path: &str, value: serde_json::Value| serde_json::json!({ "op": "add", "path": path, "value": value });
+
+        let mut patch = Vec::new();
+        if let Some(email) = update.email {
+            patch.push(patch_op("/traits/email", email.into()));
+        }
+        if let Some(username) = update.username {
+            patch.push(patch_op("/traits/username", username.into()));
+        }
+        if let Some(geo_location) = update.geo_location {
+            patch.push(patch_op("/traits/geo_location", geo_location.into()));
+        }
+        if let Some(state) = update.state {
+            patch.push(patch_op("/state", state.into()));
+        }
+        if let Some(roles) = update.roles {
+            patch.push(patch_op(
+                "/metadata_public",
+                serde_json::json!({ "roles": roles }),
+            ));
+        }
+
+        if patch.is_empty() {
+            return self.admin_get_identity(identity_id).await;
+        }
+
+        let url = self.admin_identities_url(&format!("/{}", Self::parse_id(identity_id)?));
+        let response = self.send_admin(self.client.patch(url).json(&patch)).await?;
+
+        Self::parse_identity_object(&response.json().await?)
+    }
+
+    pub async fn admin_delete_identity(&self, identity_id: &str) -> Result<(), KratosError> {
+        let url = self.admin_identities_url(&format!("/{}", Self::parse_id(identity_id)?));
+        self.send_admin(self.client.delete(url)).await?;
+
+        Ok(())
+    }
+
+    /// Revokes every session of the identity, logging it out everywhere.
+    pub async fn admin_revoke_identity_sessions(
+        &self,
+        identity_id: &str,
+    ) -> Result<(), KratosError> {
+        let url = self.admin_identities_url(&format!("/{}/sessions", Self::parse_id(identity_id)?));
+        self.send_admin(self.client.delete(url)).await?;
+
+        Ok(())
+    }
+
+    /// Looks a session up by id via the admin API, without the caller's
+    /// credentials. Unknown sessions come back as `None`.
+    pub async fn admin_get_session(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<KratosSession>, KratosError> {
+        let Ok(session_id) = Self::parse_id(session_id) else {
+            return Ok(None);
         };
+        let url = format!(
+            "{}/admin/sessions/{}?expand=identity",
+            self.admin_url, session_id
+        )
+        .replace("localhost", "127.0.0.1");
+
+        match self.send_admin(self.client.get(url)).await {
+            Ok(response) => Ok(Some(Self::parse_session(&response.json().await?))),
+            Err(KratosError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, HttpResponse, HttpServer, web};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Login flow offering the `mock` provider of `kratos/kratos.yml`.
+    fn login_flow() -> serde_json::Value {
+        serde_json::json!({
+            "id": "flow-1",
+            "ui": {
+                "nodes": [
+                    {
+                        "group": "default",
+                        "attributes": { "name": "csrf_token", "value": "csrf-1" },
+                        "meta": {}
+                    },
+                    {
+                        "group": "oidc",
+                        "attributes": { "name": "provider", "value": "mock" },
+                        "meta": {
+                            "label": { "text": "Sign in with Mock OIDC", "context": { "provider": "Mock OIDC" } }
+                        }
+                    }
+                ]
+            }
+        })
+    }
+
+    /// Kratos with the mock OIDC provider: counts created login flows, sends
+    /// OIDC logins to the provider and finishes callbacks with a session.
+    async fn mock_kratos(flows_created: Arc<AtomicUsize>) -> String {
+        let server = HttpServer::new(move || {
+            let flows_created = flows_created.clone();
+            App::new()
+                .route(
+                    "/self-service/login/browser",
+                    web::get().to(move || {
+                        flows_created.fetch_add(1, Ordering::SeqCst);
+                        async {
+                            HttpResponse::Ok()
+                                .insert_header(("Set-Cookie", "csrf_token=csrf-cookie"))
+                                .json(login_flow())
+                        }
+                    }),
+                )
+                .route(
+                    "/self-service/login",
+                    web::post().to(|body: web::Json<serde_json::Value>| async move {
+                        assert_eq!(body["method"], "oidc");
+                        assert_eq!(body["csrf_token"], "csrf-1");
+                        HttpResponse::UnprocessableEntity()
+                            .insert_header(("Set-Cookie", "ory_kratos_continuity=state"))
+                            .json(serde_json::json!({
+                                "redirect_browser_to": format!(
+                                    "http://mock-oidc:8090/default/authorize?client_id=gateway&state={}",
+                                    body["provider"].as_str().unwrap_or_default()
+                                )
+                            }))
+                    }),
+                )
+                .route(
+                    "/self-service/methods/oidc/callback/{provider}",
+                    web::get().to(|req: actix_web::HttpRequest| async move {
+                        let continuity = req
+                            .headers()
+                            .get("cookie")
+                            .and_then(|value| value.to_str().ok())
+                            == Some("ory_kratos_continuity=state");
+                        if !continuity || req.query_string() != "code=abc&state=mock" {
+                            return HttpResponse::BadRequest().finish();
+                        }
+                        HttpResponse::SeeOther()
+                            .insert_header(("Location", "http://localhost:8080/"))
+                            .insert_header(("Set-Cookie", "ory_kratos_session=session"))
+                            .finish()
+                    }),
+                )
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("Failed to bind mock Kratos");
+
+        let url = format!("http://{}", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+        url
+    }
+
+    #[test]
+    fn reads_webauthn_and_passkey_options_from_flow_nodes() {
+        let options = serde_json::json!({ "publicKey": { "challenge": "abc" } });
+        let flow = serde_json::json!({
+            "ui": {
+                "nodes": [
+                    {
+                        "attributes": {
+                            "name": "webauthn_register_trigger",
+                            "onclick": format!("window.__oryWebAuthnRegistration({})", options)
+                        }
+                    },
+                    {
+                        "attributes": {
+                            "name": "passkey_create_data",
+                            "value": serde_json::json!({
+                                "credentialOptions": options,
+                                "displayName": "user@example.com"
+                            }).to_string()
+                        }
+                    },
+                    {
+                        "attributes": { "name": "passkey_challenge", "value": options.to_string() }
+                    }
+                ]
+            }
+        });
+
+        for trigger in [
+            "webauthn_register_trigger",
+            "passkey_create_data",
+            "passkey_challenge",
+        ] {
+            assert_eq!(
+                KratosClient::webauthn_options(&flow, trigger),
+                Some(options.clone()),
+                "{}",
+                trigger
+            );
+        }
+        assert_eq!(
+            KratosClient::webauthn_options(&flow, "webauthn_login_trigger"),
+            None
+        );
+    }
+
+    #[actix_web::test]
+    async fn admin_calls_reject_ids_that_are_not_uuids() {
+        // Nothing listens here, so only a rejected id can come back as NotFound
+        let client = KratosClient::new(
+            "http://127.0.0.1:9".to_string(),
+            "http://127.0.0.1:9".to_string(),
+        );
+
+        for id in ["../sessions", "x?page_size=1000", "a/b", ""] {
+            assert!(matches!(
+                client.admin_get_identity(id).await,
+                Err(KratosError::NotFound)
+            ));
+            assert!(matches!(
+                client.admin_delete_identity(id).await,
+                Err(KratosError::NotFound)
+            ));
+            assert!(matches!(
+                client.admin_revoke_identity_sessions(id).await,
+                Err(KratosError::NotFound)
+            ));
+            assert!(matches!(client.admin_get_session(id).await, Ok(None)));
+        }
+
+        assert!(matches!(
+            client
+                .admin_get_identity("5f0c2b4e-8d0a-4c59-9a57-2a4f5f8c1e11")
+                .await,
+            Err(KratosError::Network(_))
+        ));
+    }
+
+    #[actix_web::test]
+    async fn lists_providers_from_one_cached_login_flow() {
+        let flows_created = Arc::new(AtomicUsize::new(0));
+        let url = mock_kratos(flows_created.clone()).await;
+        let client = KratosClient::new(url.clone(), url);
+
+        for _ in 0..3 {
+            let providers = client.handle_list_oidc_providers().await.unwrap();
+            assert_eq!(providers.len(), 1);
+            assert_eq!(providers[0].id, "mock");
+            assert_eq!(providers[0].label.as_deref(), Some("Mock OIDC"));
+        }
+
+        assert_eq!(flows_created.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix_web::test]
+    async fn oidc_login_redirects_to_the_provider_and_callback_sets_the_session() {
+        let url = mock_kratos(Arc::new(AtomicUsize::new(0))).await;
+        let client = KratosClient::new(url.clone(), url);
+
+        let start = client
+            .handle_start_oidc_login("mock", None, None)
+            .await
+            .unwrap();
+        assert_eq!(start.flow_id, "flow-1");
+        assert!(
+            start
+                .redirect_url
+                .starts_with("http://mock-oidc:8090/default/authorize")
+        );
+        assert!(
+            start
+                .cookies
+                .contains(&"ory_kratos_continuity=state".to_string())
+        );
 
-        Ok(Some(session))
+        let callback = client
+            .handle_oidc_callback(
+                "mock",
+                "code=abc&state=mock",
+                Some("ory_kratos_continuity=state"),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(callback.status, 303);
+        assert_eq!(callback.location.as_deref(), Some("http://localhost:8080/"));
+        assert_eq!(callback.cookies, vec!["ory_kratos_session=session"]);
     }
 }
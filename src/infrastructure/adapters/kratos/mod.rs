@@ -1,4 +1,6 @@
+pub mod error;
 pub mod kratos_client;
 
+pub use error::KratosError;
 #[allow(unused)]
 pub use kratos_client::KratosClient;
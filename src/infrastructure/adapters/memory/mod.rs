@@ -0,0 +1,9 @@
+pub mod permission_repository;
+pub mod rate_limit_repository;
+#[cfg(test)]
+pub mod refresh_token_repository;
+
+pub use permission_repository::InMemoryPermissionRepository;
+pub use rate_limit_repository::InMemoryRateLimitRepository;
+#[cfg(test)]
+pub use refresh_token_repository::InMemoryRefreshTokenRepository;
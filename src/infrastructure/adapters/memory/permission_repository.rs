@@ -0,0 +1,33 @@
+use crate::domain::repositories::permission_repository::{PermissionRepository, RelationTuple};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Matches direct tuples only (no subject sets or namespace rewrites); meant
+/// for tests and local development without Keto.
+#[derive(Clone, Default)]
+pub struct InMemoryPermissionRepository {
+    tuples: Arc<HashSet<RelationTuple>>,
+}
+
+impl InMemoryPermissionRepository {
+    pub fn new(tuples: impl IntoIterator<Item = RelationTuple>) -> Self {
+        Self {
+            tuples: Arc::new(tuples.into_iter().collect()),
+        }
+    }
+
+    /// Loads a JSON array of relation tuples in Keto's REST format.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let tuples: Vec<RelationTuple> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid relation tuples: {}", e))?;
+        Ok(Self::new(tuples))
+    }
+}
+
+#[async_trait]
+impl PermissionRepository for InMemoryPermissionRepository {
+    async fn check(&self, tuple: &RelationTuple) -> Result<bool, String> {
+        Ok(self.tuples.contains(tuple))
+    }
+}
@@ -0,0 +1,116 @@
+use crate::domain::gateway::rate_limit::{RateLimitAlgorithm, RateLimitDecision, RateLimitRule};
+use crate::domain::repositories::rate_limit_repository::RateLimitRepository;
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Expired entries are swept every this many hits.
+const SWEEP_EVERY: u64 = 1024;
+
+enum Counter {
+    /// Times of the hits still inside the window, oldest first.
+    Window(VecDeque<Instant>),
+    Bucket {
+        tokens: f64,
+        updated: Instant,
+    },
+}
+
+struct Entry {
+    counter: Counter,
+    expires: Instant,
+}
+
+/// Counts per process, so every replica enforces the limits on its own;
+/// used without Redis and while Redis is unreachable.
+#[derive(Clone, Default)]
+pub struct InMemoryRateLimitRepository {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+    hits: Arc<AtomicU64>,
+}
+
+impl InMemoryRateLimitRepository {
+    fn sweep(entries: &mut HashMap<String, Entry>, now: Instant) {
+        entries.retain(|_, entry| entry.expires > now);
+    }
+}
+
+#[async_trait]
+impl RateLimitRepository for InMemoryRateLimitRepository {
+    async fn hit(&self, key: &str, rule: &RateLimitRule) -> Result<RateLimitDecision, String> {
+        let now = Instant::now();
+        let window = rule.window();
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+
+        if self
+            .hits
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(SWEEP_EVERY)
+        {
+            Self::sweep(&mut entries, now);
+        }
+
+        let entry = entries.entry(key.to_string()).or_insert_with(|| Entry {
+            counter: match rule.algorithm {
+                RateLimitAlgorithm::SlidingWindow => Counter::Window(VecDeque::new()),
+                RateLimitAlgorithm::TokenBucket => Counter::Bucket {
+                    tokens: rule.limit as f64,
+                    updated: now,
+                },
+            },
+            expires: now,
+        });
+        entry.expires = now + window;
+
+        let decision = match &mut entry.counter {
+            Counter::Window(hits) => {
+                while hits
+                    .front()
+                    .is_some_and(|hit| now.duration_since(*hit) >= window)
+                {
+                    hits.pop_front();
+                }
+
+                if hits.len() < rule.limit as usize {
+                    hits.push_back(now);
+                    RateLimitDecision {
+                        allowed: true,
+                        remaining: rule.limit - hits.len() as u32,
+                        retry_after: Duration::ZERO,
+                    }
+                } else {
+                    RateLimitDecision {
+                        allowed: false,
+                        remaining: 0,
+                        retry_after: (hits[0] + window).saturating_duration_since(now),
+                    }
+                }
+            }
+            Counter::Bucket { tokens, updated } => {
+                let per_ms = rule.limit as f64 / rule.window_ms as f64;
+                let elapsed_ms = now.duration_since(*updated).as_secs_f64() * 1000.0;
+                *tokens = (*tokens + elapsed_ms * per_ms).min(rule.limit as f64);
+                *updated = now;
+
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    RateLimitDecision {
+                        allowed: true,
+                        remaining: *tokens as u32,
+                        retry_after: Duration::ZERO,
+                    }
+                } else {
+                    RateLimitDecision {
+                        allowed: false,
+                        remaining: 0,
+                        retry_after: Duration::from_secs_f64((1.0 - *tokens) / per_ms / 1000.0),
+                    }
+                }
+            }
+        };
+
+        Ok(decision)
+    }
+}
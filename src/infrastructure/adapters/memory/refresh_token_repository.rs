@@ -0,0 +1,93 @@
+use crate::domain::repositories::refresh_token_repository::{
+    RefreshTokenFamily, RefreshTokenRepository, RefreshTokenUse,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct State {
+    /// Token to its family and use count.
+    tokens: HashMap<String, (String, u32)>,
+    families: HashMap<String, RefreshTokenFamily>,
+}
+
+/// Mirrors `RedisRefreshTokenRepository` without expiry; for tests.
+#[derive(Clone, Default)]
+pub struct InMemoryRefreshTokenRepository {
+    state: Arc<Mutex<State>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl InMemoryRefreshTokenRepository {
+    fn lookup(&self, token: &str, spend: bool) -> Result<RefreshTokenUse, String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        let State { tokens, families } = &mut *state;
+
+        let Some((family_id, uses)) = tokens.get_mut(token) else {
+            return Ok(RefreshTokenUse::Invalid);
+        };
+        let previous_uses = *uses;
+        if spend {
+            *uses += 1;
+        }
+
+        Ok(match families.get(family_id) {
+            None => RefreshTokenUse::Invalid,
+            Some(family) if previous_uses > 0 => RefreshTokenUse::Reused(family.clone()),
+            Some(family) => RefreshTokenUse::Valid(family.clone()),
+        })
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for InMemoryRefreshTokenRepository {
+    async fn create_family(
+        &self,
+        identity_id: &str,
+        session_id: &str,
+    ) -> Result<(RefreshTokenFamily, String), String> {
+        let family = RefreshTokenFamily {
+            family_id: format!("family-{}", self.next_id.fetch_add(1, Ordering::Relaxed)),
+            identity_id: identity_id.to_string(),
+            session_id: session_id.to_string(),
+        };
+        let token = self.issue(&family).await?;
+        Ok((family, token))
+    }
+
+    async fn issue(&self, family: &RefreshTokenFamily) -> Result<String, String> {
+        let token = format!("token-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        state
+            .families
+            .insert(family.family_id.clone(), family.clone());
+        state
+            .tokens
+            .insert(token.clone(), (family.family_id.clone(), 0));
+        Ok(token)
+    }
+
+    async fn peek(&self, token: &str) -> Result<RefreshTokenUse, String> {
+        self.lookup(token, false)
+    }
+
+    async fn consume(&self, token: &str) -> Result<RefreshTokenUse, String> {
+        self.lookup(token, true)
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<(), String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        state.families.remove(family_id);
+        Ok(())
+    }
+
+    async fn revoke_session(&self, session_id: &str) -> Result<(), String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        state
+            .families
+            .retain(|_, family| family.session_id != session_id);
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
 pub mod graphql;
 pub mod http;
+pub mod jwt;
+pub mod keto;
 pub mod kratos;
+pub mod memory;
+pub mod redis;
@@ -0,0 +1,5 @@
+pub mod rate_limit_repository;
+pub mod refresh_token_repository;
+
+pub use rate_limit_repository::RedisRateLimitRepository;
+pub use refresh_token_repository::RedisRefreshTokenRepository;
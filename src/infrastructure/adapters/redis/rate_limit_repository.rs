@@ -0,0 +1,121 @@
+use crate::domain::gateway::rate_limit::{RateLimitAlgorithm, RateLimitDecision, RateLimitRule};
+use crate::domain::repositories::rate_limit_repository::RateLimitRepository;
+use crate::infrastructure::adapters::memory::InMemoryRateLimitRepository;
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::{Client, RedisError, Script};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::time::Duration;
+use tracing::warn;
+
+const KEY_PREFIX: &str = "ratelimit:";
+
+/// Sorted set of hit times (µs, from the Redis clock) inside the window;
+/// `ARGV[3]` makes simultaneous hits distinct members.
+/// Returns `{allowed, remaining, retry_after_ms}`.
+const SLIDING_WINDOW_SCRIPT: &str = r"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+local window = tonumber(ARGV[1]) * 1000
+local limit = tonumber(ARGV[2])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - window)
+local count = redis.call('ZCARD', KEYS[1])
+if count < limit then
+  redis.call('ZADD', KEYS[1], now, now .. ':' .. ARGV[3])
+  redis.call('PEXPIRE', KEYS[1], ARGV[1])
+  return {1, limit - count - 1, 0}
+end
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+return {0, 0, math.ceil((tonumber(oldest[2]) + window - now) / 1000)}
+";
+
+/// Hash of the remaining tokens and the last refill (ms, Redis clock).
+/// Returns `{allowed, remaining, retry_after_ms}`.
+const TOKEN_BUCKET_SCRIPT: &str = r"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local window = tonumber(ARGV[1])
+local limit = tonumber(ARGV[2])
+local per_ms = limit / window
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'updated')
+local tokens = tonumber(state[1]) or limit
+local updated = tonumber(state[2]) or now
+tokens = math.min(limit, tokens + math.max(0, now - updated) * per_ms)
+local allowed, retry_after = 0, 0
+if tokens >= 1 then
+  tokens = tokens - 1
+  allowed = 1
+else
+  retry_after = math.ceil((1 - tokens) / per_ms)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated', now)
+redis.call('PEXPIRE', KEYS[1], window)
+return {allowed, math.floor(tokens), retry_after}
+";
+
+/// Counters shared by every replica. While Redis is unreachable each
+/// replica counts in memory instead of letting everything through.
+#[derive(Clone)]
+pub struct RedisRateLimitRepository {
+    connection: ConnectionManager,
+    fallback: InMemoryRateLimitRepository,
+    rng: SystemRandom,
+}
+
+impl RedisRateLimitRepository {
+    pub async fn connect(redis_url: &str) -> Result<Self, RedisError> {
+        let client = Client::open(redis_url)?;
+        let connection = ConnectionManager::new(client).await?;
+
+        Ok(Self {
+            connection,
+            fallback: InMemoryRateLimitRepository::default(),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    async fn hit_redis(
+        &self,
+        key: &str,
+        rule: &RateLimitRule,
+    ) -> Result<RateLimitDecision, String> {
+        let script = match rule.algorithm {
+            RateLimitAlgorithm::SlidingWindow => SLIDING_WINDOW_SCRIPT,
+            RateLimitAlgorithm::TokenBucket => TOKEN_BUCKET_SCRIPT,
+        };
+
+        let mut nonce = [0u8; 8];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| "Failed to generate rate limit nonce".to_string())?;
+
+        let mut connection = self.connection.clone();
+        let (allowed, remaining, retry_after_ms): (i64, i64, i64) = Script::new(script)
+            .key(format!("{}{}", KEY_PREFIX, key))
+            .arg(rule.window_ms)
+            .arg(rule.limit)
+            .arg(u64::from_be_bytes(nonce))
+            .invoke_async(&mut connection)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(RateLimitDecision {
+            allowed: allowed == 1,
+            remaining: remaining.max(0) as u32,
+            retry_after: Duration::from_millis(retry_after_ms.max(0) as u64),
+        })
+    }
+}
+
+#[async_trait]
+impl RateLimitRepository for RedisRateLimitRepository {
+    async fn hit(&self, key: &str, rule: &RateLimitRule) -> Result<RateLimitDecision, String> {
+        match self.hit_redis(key, rule).await {
+            Ok(decision) => Ok(decision),
+            Err(e) => {
+                warn!(error = %e, "Redis rate limiting failed, counting in memory");
+                self.fallback.hit(key, rule).await
+            }
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use crate::domain::repositories::refresh_token_repository::{
+    RefreshTokenFamily, RefreshTokenRepository, RefreshTokenUse,
+};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, Client, RedisError, Script};
+use ring::digest;
+use ring::rand::{SecureRandom, SystemRandom};
+
+const TOKEN_PREFIX: &str = "refresh:token:";
+const FAMILY_PREFIX: &str = "refresh:family:";
+const SESSION_PREFIX: &str = "refresh:session:";
+
+/// Adds `ARGV[2]` (1 to spend, 0 to peek) to the use counter of a stored
+/// token and returns its previous uses together with the owner of its
+/// family, so spending and reuse detection happen atomically.
+const LOOKUP_SCRIPT: &str = r"
+local family = redis.call('HGET', KEYS[1], 'family')
+if not family then return false end
+local uses = redis.call('HINCRBY', KEYS[1], 'uses', ARGV[2])
+local owner = redis.call('HMGET', ARGV[1] .. family, 'identity', 'session')
+return {uses - tonumber(ARGV[2]), family, owner[1] or '', owner[2] or ''}
+";
+
+/// Keeps only the SHA-256 of each refresh token. Token, family and session
+/// keys share one TTL that slides forward on every rotation.
+#[derive(Clone)]
+pub struct RedisRefreshTokenRepository {
+    connection: ConnectionManager,
+    ttl_seconds: i64,
+    rng: SystemRandom,
+}
+
+impl RedisRefreshTokenRepository {
+    pub async fn connect(redis_url: &str, ttl: chrono::Duration) -> Result<Self, RedisError> {
+        let client = Client::open(redis_url)?;
+        let connection = ConnectionManager::new(client).await?;
+
+        Ok(Self {
+            connection,
+            ttl_seconds: ttl.num_seconds(),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    fn random_value(&self, len: usize) -> Result<String, String> {
+        let mut bytes = vec![0u8; len];
+        self.rng
+            .fill(&mut bytes)
+            .map_err(|_| "Failed to generate refresh token".to_string())?;
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    fn hash_token(token: &str) -> String {
+        digest::digest(&digest::SHA256, token.as_bytes())
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    async fn lookup(&self, token: &str, spend: bool) -> Result<RefreshTokenUse, String> {
+        let token_key = format!("{}{}", TOKEN_PREFIX, Self::hash_token(token));
+
+        let mut connection = self.connection.clone();
+        let result: Option<(i64, String, String, String)> = Script::new(LOOKUP_SCRIPT)
+            .key(&token_key)
+            .arg(FAMILY_PREFIX)
+            .arg(i64::from(spend))
+            .invoke_async(&mut connection)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(match result {
+            Some((previous_uses, family_id, identity_id, session_id)) => {
+                token_use(previous_uses, family_id, identity_id, session_id)
+            }
+            None => RefreshTokenUse::Invalid,
+        })
+    }
+}
+
+/// Classifies a stored token by how often it was presented before.
+fn token_use(
+    previous_uses: i64,
+    family_id: String,
+    identity_id: String,
+    session_id: String,
+) -> RefreshTokenUse {
+    // The family key is gone once it has been revoked or has expired
+    if identity_id.is_empty() {
+        return RefreshTokenUse::Invalid;
+    }
+
+    let family = RefreshTokenFamily {
+        family_id,
+        identity_id,
+        session_id,
+    };
+    if previous_uses > 0 {
+        RefreshTokenUse::Reused(family)
+    } else {
+        RefreshTokenUse::Valid(family)
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for RedisRefreshTokenRepository {
+    async fn create_family(
+        &self,
+        identity_id: &str,
+        session_id: &str,
+    ) -> Result<(RefreshTokenFamily, String), String> {
+        let family = RefreshTokenFamily {
+            family_id: self.random_value(16)?,
+            identity_id: identity_id.to_string(),
+            session_id: session_id.to_string(),
+        };
+
+        let token = self.issue(&family).await?;
+
+        Ok((family, token))
+    }
+
+    async fn issue(&self, family: &RefreshTokenFamily) -> Result<String, String> {
+        let token = self.random_value(32)?;
+        let token_key = format!("{}{}", TOKEN_PREFIX, Self::hash_token(&token));
+        let family_key = format!("{}{}", FAMILY_PREFIX, family.family_id);
+        let session_key = format!("{}{}", SESSION_PREFIX, family.session_id);
+
+        // The session's family set slides with its tokens, so `revoke_session`
+        // still finds families that were refreshed after it would have expired
+        let mut connection = self.connection.clone();
+        redis::pipe()
+            .atomic()
+            .sadd(&session_key, &family.family_id)
+            .ignore()
+            .expire(&session_key, self.ttl_seconds)
+            .ignore()
+            .hset_multiple(
+                &family_key,
+                &[
+                    ("identity", family.identity_id.as_str()),
+                    ("session", family.session_id.as_str()),
+                ],
+            )
+            .ignore()
+            .expire(&family_key, self.ttl_seconds)
+            .ignore()
+            .hset_multiple(
+                &token_key,
+                &[("family", family.family_id.as_str()), ("uses", "0")],
+            )
+            .ignore()
+            .expire(&token_key, self.ttl_seconds)
+            .ignore()
+            .query_async::<()>(&mut connection)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(token)
+    }
+
+    async fn peek(&self, token: &str) -> Result<RefreshTokenUse, String> {
+        self.lookup(token, false).await
+    }
+
+    async fn consume(&self, token: &str) -> Result<RefreshTokenUse, String> {
+        self.lookup(token, true).await
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<(), String> {
+        let mut connection = self.connection.clone();
+        connection
+            .del::<_, ()>(format!("{}{}", FAMILY_PREFIX, family_id))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn revoke_session(&self, session_id: &str) -> Result<(), String> {
+        let session_key = format!("{}{}", SESSION_PREFIX, session_id);
+
+        let mut connection = self.connection.clone();
+        let family_ids: Vec<String> = connection
+            .smembers(&session_key)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut keys: Vec<String> = family_ids
+            .iter()
+            .map(|family_id| format!("{}{}", FAMILY_PREFIX, family_id))
+            .collect();
+        keys.push(session_key);
+
+        connection
+            .del::<_, ()>(keys)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(previous_uses: i64, identity_id: &str) -> RefreshTokenUse {
+        token_use(
+            previous_uses,
+            "family-1".to_string(),
+            identity_id.to_string(),
+            "session-1".to_string(),
+        )
+    }
+
+    #[test]
+    fn stores_tokens_by_their_sha256() {
+        assert_eq!(
+            RedisRefreshTokenRepository::hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(
+            RedisRefreshTokenRepository::hash_token("abc"),
+            RedisRefreshTokenRepository::hash_token("abd")
+        );
+    }
+
+    #[test]
+    fn only_the_first_presentation_is_valid() {
+        assert!(matches!(
+            classify(0, "identity-1"),
+            RefreshTokenUse::Valid(family) if family.family_id == "family-1"
+        ));
+        assert!(matches!(
+            classify(1, "identity-1"),
+            RefreshTokenUse::Reused(_)
+        ));
+        assert!(matches!(
+            classify(5, "identity-1"),
+            RefreshTokenUse::Reused(_)
+        ));
+    }
+
+    #[test]
+    fn tokens_of_a_revoked_family_are_invalid() {
+        // The script reports an empty owner once the family key is deleted
+        assert!(matches!(classify(0, ""), RefreshTokenUse::Invalid));
+        assert!(matches!(classify(1, ""), RefreshTokenUse::Invalid));
+    }
+}